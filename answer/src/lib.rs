@@ -0,0 +1,36 @@
+//! Types shared by the days' solutions for returning their answers.
+
+use std::fmt;
+
+/// One answer per case, for inputs which hold several independent cases.
+///
+/// Displays each answer on its own line, prefixed by the index of its case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers<T>(pub Vec<T>);
+
+impl<T> FromIterator<T> for Answers<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Answers(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Answers<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Answers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, answer) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {}", idx, answer)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Errors shared by every day's solution.
+//!
+//! Each day declares its `Error` with [`day_error!`], which adds the variants every day needs
+//! to the day's own. Any day's error converts into this crate's [`Error`], so the runner can
+//! handle every day's results the same way.
+
+/// A day's failure, as seen from outside that day.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("no solution found")]
+    NoSolution,
+    #[error("day {day} part {part} is not implemented")]
+    Unimplemented { day: u8, part: u8 },
+    #[error(transparent)]
+    Day(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Declare a day's `Error`.
+///
+/// The enum gets `Io` and `NoSolution` variants ahead of the ones listed, and derives `Debug`
+/// and `thiserror::Error`; the day's crate must depend on `thiserror`.
+///
+/// ```ignore
+/// aoc_errors::day_error! {
+///     pub enum Error {
+///         #[error("malformed input")]
+///         MalformedInput,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! day_error {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variants:tt)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, thiserror::Error)]
+        $vis enum $name {
+            #[error(transparent)]
+            Io(#[from] std::io::Error),
+            #[error("no solution found")]
+            NoSolution,
+            $($variants)*
+        }
+
+        impl From<$name> for $crate::Error {
+            fn from(err: $name) -> Self {
+                match err {
+                    $name::Io(err) => $crate::Error::Io(err),
+                    $name::NoSolution => $crate::Error::NoSolution,
+                    #[allow(unreachable_patterns)]
+                    err => $crate::Error::Day(Box::new(err)),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    day_error! {
+        enum Error {
+            #[error("malformed input")]
+            MalformedInput,
+        }
+    }
+
+    day_error! {
+        enum CommonError {}
+    }
+
+    #[test]
+    fn shared_variants_convert() {
+        for err in [CommonError::NoSolution.into(), Error::NoSolution.into()] {
+            assert!(matches!(err, crate::Error::NoSolution));
+        }
+        let err = crate::Error::from(Error::from(std::io::Error::from(
+            std::io::ErrorKind::NotFound,
+        )));
+        assert!(matches!(err, crate::Error::Io(_)));
+    }
+
+    #[test]
+    fn day_variants_are_boxed() {
+        let err = crate::Error::from(Error::MalformedInput);
+        assert!(matches!(err, crate::Error::Day(_)));
+        assert_eq!(err.to_string(), "malformed input");
+    }
+}
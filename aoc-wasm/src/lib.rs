@@ -0,0 +1,53 @@
+//! JavaScript bindings shared by every day's solution.
+//!
+//! Each day declares its bindings with [`wasm_bindings!`], which only compiles them when the day
+//! is built with its `wasm` feature.
+
+/// Declare a day's `wasm` module, exporting `part1` and `part2` to JavaScript.
+///
+/// Each binding takes the raw input and returns the answer as a string, or the error message on
+/// failure. The module only exists with the day's `wasm` feature, which must enable the day's
+/// optional `wasm-bindgen` dependency.
+///
+/// Most days bind their solvers directly. A solver which needs more than the input can be
+/// wrapped instead:
+///
+/// ```ignore
+/// aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+///
+/// aoc_wasm::wasm_bindings! {
+///     part1: |input| solve_part1(input, 80),
+///     part2: |input| solve_part2(input),
+/// }
+/// ```
+#[macro_export]
+macro_rules! wasm_bindings {
+    ($part1:path, $part2:path $(,)?) => {
+        $crate::wasm_bindings! {
+            part1: |input| $part1(input),
+            part2: |input| $part2(input),
+        }
+    };
+    (part1: |$input1:ident| $part1:expr, part2: |$input2:ident| $part2:expr $(,)?) => {
+        /// JavaScript bindings, built with the `wasm` feature.
+        #[cfg(feature = "wasm")]
+        pub mod wasm {
+            use super::*;
+            use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+
+            #[wasm_bindgen]
+            pub fn part1($input1: &str) -> Result<String, JsValue> {
+                $part1
+                    .map(|answer| answer.to_string())
+                    .map_err(|err| JsValue::from_str(&err.to_string()))
+            }
+
+            #[wasm_bindgen]
+            pub fn part2($input2: &str) -> Result<String, JsValue> {
+                $part2
+                    .map(|answer| answer.to_string())
+                    .map_err(|err| JsValue::from_str(&err.to_string()))
+            }
+        }
+    };
+}
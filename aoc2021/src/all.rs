@@ -0,0 +1,83 @@
+//! Run every day's parts, timing each.
+
+use aoc2021::solve;
+use color_eyre::eyre::{eyre, Result};
+#[cfg(feature = "parallelism")]
+use rayon::prelude::*;
+use std::{
+    cmp::Reverse,
+    time::{Duration, Instant},
+};
+
+/// The outcome of running one part of one day.
+#[derive(Debug)]
+pub struct Timing {
+    pub day: u8,
+    pub part: u8,
+    pub elapsed: Duration,
+    pub answer: Result<String>,
+}
+
+/// Run both parts of every day in `inputs`.
+///
+/// Days whose input could not be read are reported as failures without running. With the
+/// `parallelism` feature, the parts run concurrently, so each timing includes some contention
+/// with the others.
+pub fn run_all(inputs: Vec<(u8, Result<String>)>) -> Vec<Timing> {
+    let mut timings = Vec::new();
+    let mut jobs = Vec::new();
+    for (day, input) in &inputs {
+        for part in [1, 2] {
+            match input {
+                Ok(input) => jobs.push((*day, part, input.as_str())),
+                Err(err) => timings.push(Timing {
+                    day: *day,
+                    part,
+                    elapsed: Duration::ZERO,
+                    answer: Err(eyre!("reading input: {}", err)),
+                }),
+            }
+        }
+    }
+
+    let time = |&(day, part, input): &(u8, u8, &str)| {
+        let started = Instant::now();
+        let answer = solve(day, part, input).map_err(Into::into);
+        Timing {
+            day,
+            part,
+            elapsed: started.elapsed(),
+            answer,
+        }
+    };
+    #[cfg(not(feature = "parallelism"))]
+    let solved = jobs.iter().map(time);
+    #[cfg(feature = "parallelism")]
+    let solved = jobs.par_iter().map(time);
+    timings.extend(solved.collect::<Vec<_>>());
+    timings
+}
+
+/// Print `timings` as a table, slowest first, followed by their total.
+pub fn print_report(mut timings: Vec<Timing>) {
+    timings.sort_by_key(|timing| Reverse(timing.elapsed));
+
+    println!("{:>3} {:>4} {:>12}  answer", "day", "part", "time");
+    for timing in &timings {
+        let answer = match timing.answer {
+            // some answers span several lines; keep each row on one
+            Ok(ref answer) => answer.lines().collect::<Vec<_>>().join("; "),
+            Err(ref err) => format!("error: {}", err),
+        };
+        println!(
+            "{:>3} {:>4} {:>12}  {}",
+            timing.day,
+            timing.part,
+            format!("{:.3?}", timing.elapsed),
+            answer
+        );
+    }
+
+    let total: Duration = timings.iter().map(|timing| timing.elapsed).sum();
+    println!("{:>8} {:>12}", "total", format!("{:.3?}", total));
+}
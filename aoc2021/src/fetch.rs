@@ -0,0 +1,36 @@
+//! Download personal puzzle inputs from adventofcode.com.
+
+use crate::YEAR;
+
+use color_eyre::eyre::{Result, WrapErr};
+use std::path::{Path, PathBuf};
+
+const USER_AGENT: &str =
+    "github.com/coriolinus/adventofcode-2021 by peter.r.goodspeedniklaus@gmail.com";
+
+/// Where the input for `day` is stored: `dayNN.txt` in the `inputs` directory.
+pub fn input_path(inputs: &Path, day: u8) -> PathBuf {
+    inputs.join(format!("day{:02}.txt", day))
+}
+
+/// Download the input for `day` to its [`input_path`], replacing anything already there.
+///
+/// `session` is the value of the `session` cookie of a logged-in browser.
+pub fn fetch(inputs: &Path, day: u8, session: &str) -> Result<PathBuf> {
+    let url = format!("https://adventofcode.com/{}/day/{}/input", YEAR, day);
+    let input = ureq::get(&url)
+        .set("Cookie", &format!("session={}", session))
+        .set("User-Agent", USER_AGENT)
+        .call()
+        .wrap_err_with(|| format!("requesting {}", url))?
+        .into_string()
+        .wrap_err("reading input")?;
+
+    let path = input_path(inputs, day);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(&path, input).wrap_err_with(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
@@ -0,0 +1,61 @@
+//! Every day's solution, behind one dependency.
+//!
+//! Each day's crate is re-exported under its own name, so `aoc2021::day01::part1` is
+//! `day01::part1`. [`solve`] picks the day and part at runtime.
+
+use aoc_errors::Error;
+
+pub use day01;
+pub use day02;
+pub use day03;
+pub use day04;
+pub use day05;
+pub use day06;
+pub use day07;
+pub use day08;
+pub use day09;
+pub use day10;
+pub use day11;
+pub use day12;
+pub use day13;
+pub use day14;
+pub use day15;
+pub use day16;
+pub use day17;
+pub use day18;
+pub use day19;
+pub use day20;
+pub use day21;
+pub use day22;
+pub use day23;
+pub use day24;
+pub use day25;
+
+/// Dispatch to the requested part of the requested day.
+///
+/// Day 6's part 1 takes the number of days to simulate; it gets the puzzle's 80.
+macro_rules! dispatch {
+    ($day:expr, $part:expr, $input:expr; $($n:literal => $krate:ident),* $(,)?) => {
+        match ($day, $part) {
+            (6, 1) => day06::solve_part1($input, 80)?.to_string(),
+            (6, 2) => day06::solve_part2($input)?.to_string(),
+            $(
+                ($n, 1) => $krate::solve_part1($input)?.to_string(),
+                ($n, 2) => $krate::solve_part2($input)?.to_string(),
+            )*
+            (day, part) => return Err(Error::Unimplemented { day, part }),
+        }
+    };
+}
+
+/// Solve the requested part of the requested day.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, Error> {
+    let _solve = tracing::info_span!("solve", day, part).entered();
+    Ok(dispatch!(day, part, input;
+        1 => day01, 2 => day02, 3 => day03, 4 => day04, 5 => day05,
+        7 => day07, 8 => day08, 9 => day09, 10 => day10,
+        11 => day11, 12 => day12, 13 => day13, 14 => day14, 15 => day15,
+        16 => day16, 17 => day17, 18 => day18, 19 => day19, 20 => day20,
+        21 => day21, 22 => day22, 23 => day23, 24 => day24, 25 => day25,
+    ))
+}
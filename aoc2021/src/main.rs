@@ -0,0 +1,137 @@
+mod all;
+mod fetch;
+
+use aoc2021::solve;
+use color_eyre::eyre::{bail, eyre, Result};
+use input_source::InputSource;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tracing::Level;
+
+const YEAR: u32 = 2021;
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// download a day's input to dayNN.txt in the inputs directory
+    Fetch {
+        /// day to fetch
+        #[structopt(long)]
+        day: u8,
+    },
+    /// run every day's parts, then list how long each took, slowest first
+    All,
+}
+
+#[derive(StructOpt, Debug)]
+struct RunArgs {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    /// adventofcode.com session cookie, for downloading inputs
+    #[structopt(long, env = "AOC_SESSION", hide_env_values = true, global = true)]
+    session: Option<String>,
+
+    /// directory in which downloaded inputs are stored
+    #[structopt(
+        long,
+        env = "AOC_INPUTS",
+        default_value = "inputs",
+        parse(from_os_str),
+        global = true
+    )]
+    inputs: PathBuf,
+
+    /// show diagnostics; repeat for more detail
+    #[structopt(short, long, parse(from_occurrences), global = true)]
+    verbose: u8,
+
+    /// day to run
+    #[structopt(long)]
+    day: Option<u8>,
+
+    /// part to run
+    #[structopt(long, default_value = "1", possible_values = &["1", "2"])]
+    part: u8,
+
+    /// input file, or "-" to read from stdin
+    #[structopt(long, parse(from_os_str))]
+    input: Option<InputSource>,
+
+    /// report the progress of long-running parts on stderr
+    #[structopt(long)]
+    progress: bool,
+}
+
+impl RunArgs {
+    fn log_level(&self) -> Level {
+        match self.verbose {
+            0 => Level::WARN,
+            1 => Level::INFO,
+            2 => Level::DEBUG,
+            _ => Level::TRACE,
+        }
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.session
+            .as_deref()
+            .ok_or_else(|| eyre!("downloading inputs needs --session or AOC_SESSION"))
+    }
+
+    /// The stored input for `day`, downloading it first if it is missing.
+    fn stored_input(&self, day: u8) -> Result<InputSource> {
+        let path = fetch::input_path(&self.inputs, day);
+        if path.exists() {
+            Ok(path.into())
+        } else {
+            Ok(fetch::fetch(&self.inputs, day, self.session()?)?.into())
+        }
+    }
+
+    fn input(&self, day: u8) -> Result<InputSource> {
+        match self.input {
+            None => self.stored_input(day),
+            Some(ref source) => Ok(source.clone()),
+        }
+    }
+}
+
+fn check_day(day: u8) -> Result<u8> {
+    if !(1..=25).contains(&day) {
+        bail!("day must be in 1..=25; got {}", day);
+    }
+    Ok(day)
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = RunArgs::from_args();
+    tracing_subscriber::fmt()
+        .with_max_level(args.log_level())
+        .with_writer(std::io::stderr)
+        .init();
+
+    if args.progress {
+        progress::enable();
+    }
+
+    match args.command {
+        Some(Command::Fetch { day }) => {
+            let path = fetch::fetch(&args.inputs, check_day(day)?, args.session()?)?;
+            println!("{}", path.display());
+            return Ok(());
+        }
+        Some(Command::All) => {
+            let read = |day| -> Result<String> { Ok(args.stored_input(day)?.read()?) };
+            let inputs = (1..=25).map(|day| (day, read(day))).collect();
+            all::print_report(all::run_all(inputs));
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let day = check_day(args.day.ok_or_else(|| eyre!("--day is required"))?)?;
+    let input = args.input(day)?.read()?;
+    println!("{}", solve(day, args.part, &input)?);
+    Ok(())
+}
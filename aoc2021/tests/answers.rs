@@ -0,0 +1,67 @@
+//! Check every day's solver against the answers accepted for my real input.
+//!
+//! Neither the inputs nor the answers are committed. Inputs live in `inputs/dayNN.txt`, and the
+//! accepted answers in `answers.toml`, one table per day:
+//!
+//! ```toml
+//! [day01]
+//! part1 = 1502
+//! part2 = "1538"
+//! ```
+//!
+//! Answers may be integers or strings. Days without a recorded answer or an input are skipped.
+
+use std::{fs, path::Path};
+use toml::Value;
+
+#[test]
+fn answers() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let answers = match fs::read_to_string(root.join("answers.toml")) {
+        Ok(answers) => answers,
+        Err(_) => {
+            eprintln!("no answers.toml; nothing to check");
+            return;
+        }
+    };
+    let answers: Value = answers.parse().expect("parsing answers.toml");
+    let answers = answers.as_table().expect("answers.toml is a table");
+
+    let mut failures = Vec::new();
+    for day in 1..=25 {
+        let parts = match answers.get(&format!("day{:02}", day)) {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let input = match fs::read_to_string(root.join(format!("inputs/day{:02}.txt", day))) {
+            Ok(input) => input,
+            Err(_) => {
+                eprintln!("day {}: no input; skipping", day);
+                continue;
+            }
+        };
+
+        for part in 1..=2 {
+            let expected = match parts.get(&format!("part{}", part)) {
+                Some(Value::String(expected)) => expected.clone(),
+                Some(Value::Integer(expected)) => expected.to_string(),
+                Some(other) => panic!("day {} part {}: unusable answer {}", day, part, other),
+                None => continue,
+            };
+
+            let case = format!("day {} part {}", day, part);
+            match aoc2021::solve(day, part, &input) {
+                Ok(answer) if answer == expected.trim_end() => {}
+                Ok(answer) => failures.push(format!(
+                    "{}: expected {:?}, got {:?}",
+                    case,
+                    expected.trim_end(),
+                    answer
+                )),
+                Err(err) => failures.push(format!("{}: {}", case, err)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,64 @@
+//! Run every day's solver against the puzzle examples.
+//!
+//! Fixtures live in `examples/dayNN/<case>/`: `input.txt` holds the example input, and `part1.txt`
+//! and `part2.txt` hold the answer the runner should print for it. A missing part file means that
+//! part isn't checked for that case.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn sorted_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs = fs::read_dir(path)
+        .unwrap_or_else(|err| panic!("reading {}: {}", path.display(), err))
+        .map(|entry| entry.expect("reading directory entry").path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs
+}
+
+#[test]
+fn examples() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    for day_dir in sorted_dirs(&examples) {
+        let day: u8 = day_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|day| day.parse().ok())
+            .unwrap_or_else(|| panic!("{} is not named dayNN", day_dir.display()));
+
+        for case in sorted_dirs(&day_dir) {
+            let input = fs::read_to_string(case.join("input.txt"))
+                .unwrap_or_else(|err| panic!("reading input for {}: {}", case.display(), err));
+
+            for part in 1..=2 {
+                let expected = match fs::read_to_string(case.join(format!("part{}.txt", part))) {
+                    Ok(expected) => expected,
+                    Err(_) => continue,
+                };
+                checked += 1;
+
+                let case = format!("day {} part {} ({})", day, part, case.display());
+                match aoc2021::solve(day, part, &input) {
+                    Ok(answer) if answer == expected.trim_end() => {}
+                    Ok(answer) => failures.push(format!(
+                        "{}: expected {:?}, got {:?}",
+                        case,
+                        expected.trim_end(),
+                        answer
+                    )),
+                    Err(err) => failures.push(format!("{}: {}", case, err)),
+                }
+            }
+        }
+    }
+
+    assert!(checked > 0, "no examples found in {}", examples.display());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -1,4 +1,4 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 use itertools::Itertools;
 use std::path::Path;
 
@@ -18,23 +18,27 @@ where
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let increases = parse::<u32>(input)?.count_increases();
-    println!("increases: {}", increases);
-    Ok(())
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
+    Ok(parse_str::<u32>(input)?.count_increases())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let increases = parse::<u32>(input)?
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<usize, Error> {
+    Ok(parse_str::<u32>(input)?
         .tuple_windows::<(_, _, _)>()
         .map(|(a, b, c)| a + b + c)
-        .count_increases();
-    println!("increases (3-windows): {}", increases);
-    Ok(())
+        .count_increases())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {}
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,4 +1,4 @@
-use aoclib::{geometry::Point, parse};
+use aoclib::{geometry::Point, input::parse_str};
 use std::path::Path;
 
 #[derive(Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
@@ -9,9 +9,9 @@ pub enum Command {
     Up(i32),
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<i32, Error> {
     let final_position =
-        parse::<Command>(input)?.fold(Point::default(), |mut position, command| {
+        parse_str::<Command>(input)?.fold(Point::default(), |mut position, command| {
             match command {
                 Command::Forward(x) => position.x += x,
                 Command::Down(y) => position.y += y,
@@ -19,15 +19,15 @@ pub fn part1(input: &Path) -> Result<(), Error> {
             }
             position
         });
-    println!(
-        "product of horizontal position and depth: {}",
-        final_position.x * final_position.y
-    );
-    Ok(())
+    Ok(final_position.x * final_position.y)
+}
+
+pub fn part1(input: &Path) -> Result<i32, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let (final_position, _) = parse::<Command>(input)?.fold(
+pub fn solve_part2(input: &str) -> Result<i32, Error> {
+    let (final_position, _) = parse_str::<Command>(input)?.fold(
         (Point::default(), 0_i32),
         |(mut position, mut aim), command| {
             match command {
@@ -41,15 +41,15 @@ pub fn part2(input: &Path) -> Result<(), Error> {
             (position, aim)
         },
     );
-    println!(
-        "product of horizontal position and depth (with aim): {}",
-        final_position.x * final_position.y
-    );
-    Ok(())
+    Ok(final_position.x * final_position.y)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2(input: &Path) -> Result<i32, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {}
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
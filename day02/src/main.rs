@@ -1,18 +1,18 @@
 use aoclib::{config::Config, website::get_input};
-use day02::{part1, part2};
+use day02::{solve_part1, solve_part2};
 
 use color_eyre::eyre::Result;
+use input_source::InputSource;
 use structopt::StructOpt;
-use std::path::PathBuf;
 
 const YEAR: u32 = 2021;
 const DAY: u8 = 2;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
+    /// input file, or "-" to read from stdin
     #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    input: Option<InputSource>,
 
     /// skip part 1
     #[structopt(long)]
@@ -24,16 +24,16 @@ struct RunArgs {
 }
 
 impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
+    fn input(&self) -> Result<InputSource> {
         match self.input {
             None => {
                 let config = Config::load()?;
                 // this does nothing if the input file already exists, but
                 // simplifies the workflow after cloning the repo on a new computer
                 get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                Ok(config.input_for(YEAR, DAY).into())
             }
-            Some(ref path) => Ok(path.clone()),
+            Some(ref source) => Ok(source.clone()),
         }
     }
 }
@@ -41,13 +41,13 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    let input = args.input()?.read()?;
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        println!("{}", solve_part1(&input)?);
     }
     if args.part2 {
-        part2(&input_path)?;
+        println!("{}", solve_part2(&input)?);
     }
     Ok(())
 }
@@ -1,4 +1,4 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::{cmp::Ordering, path::Path, str::FromStr};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -95,30 +95,32 @@ fn locate_rating(report: &[DiagnosticCondition], rating_type: LifeSupport) -> Re
     Err(Error::NoSolution)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let diagnostic_report: Vec<DiagnosticCondition> = parse(input)?.collect();
+pub fn solve_part1(input: &str) -> Result<u32, Error> {
+    let diagnostic_report: Vec<DiagnosticCondition> = parse_str(input)?.collect();
     let (gamma, epsilon) = find_rates(&diagnostic_report);
-    println!("power consumption: {}", gamma as u32 * epsilon as u32);
-    Ok(())
+    Ok(gamma as u32 * epsilon as u32)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let diagnostic_report: Vec<DiagnosticCondition> = parse(input)?.collect();
+pub fn part1(input: &Path) -> Result<u32, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u32, Error> {
+    let diagnostic_report: Vec<DiagnosticCondition> = parse_str(input)?.collect();
     let oxygen_generator_rating = locate_rating(&diagnostic_report, LifeSupport::OxygenGenerator)?;
     let co2_scrubber_rating = locate_rating(&diagnostic_report, LifeSupport::Co2Scrubber)?;
-    println!(
-        "life support rating: {}",
-        oxygen_generator_rating as u32 * co2_scrubber_rating as u32
-    );
-    Ok(())
+    Ok(oxygen_generator_rating as u32 * co2_scrubber_rating as u32)
+}
+
+pub fn part2(input: &Path) -> Result<u32, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("parsing diagnostic condition")]
-    ParseDiagnosticCondition(#[from] std::num::ParseIntError),
-    #[error("No solution found")]
-    NoSolution,
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("parsing diagnostic condition")]
+        ParseDiagnosticCondition(#[from] std::num::ParseIntError),
+    }
 }
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,7 +1,4 @@
-use aoclib::{
-    geometry::{tile::DisplayWidth, Direction, Map},
-    input::{parse_two_phase, TrimmedCommaSep, TwoPhaseError},
-};
+use aoclib::geometry::{tile::DisplayWidth, Direction, Map};
 use std::{fmt::Display, path::Path, str::FromStr};
 
 const HIGH_BIT: u8 = 0x80;
@@ -118,20 +115,31 @@ impl FromStr for Bingo {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let (calls, boards) = parse_two_phase::<TrimmedCommaSep<u8>, Bingo>(input)?;
-    let calls: Vec<_> = calls.into();
-    let mut boards: Vec<_> = boards.collect();
+/// The called numbers on the first line, then blank-line-separated boards.
+fn parse_input(input: &str) -> Result<(Vec<u8>, Vec<Bingo>), Error> {
+    let input = input.trim();
+    let (calls, boards) = input.split_once("\n\n").unwrap_or((input, ""));
+    let calls = calls
+        .split(',')
+        .map(|call| call.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| Error::BadCalls)?;
+    let boards = boards
+        .split("\n\n")
+        .filter(|board| !board.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()?;
+    Ok((calls, boards))
+}
+
+pub fn solve_part1(input: &str) -> Result<u32, Error> {
+    let (calls, mut boards) = parse_input(input)?;
 
     for call in calls {
         for board in boards.iter_mut() {
             board.call(call);
             if board.check_bingo() {
-                println!(
-                    "winning score (first): {}",
-                    board.sum_unmarked() * call as u32
-                );
-                return Ok(());
+                return Ok(board.sum_unmarked() * call as u32);
             }
         }
     }
@@ -139,10 +147,12 @@ pub fn part1(input: &Path) -> Result<(), Error> {
     Err(Error::NoSolution)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let (calls, boards) = parse_two_phase::<TrimmedCommaSep<u8>, Bingo>(input)?;
-    let calls: Vec<_> = calls.into();
-    let mut boards: Vec<_> = boards.collect();
+pub fn part1(input: &Path) -> Result<u32, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u32, Error> {
+    let (calls, mut boards) = parse_input(input)?;
     let mut boards_remaining = boards.len();
 
     for call in calls {
@@ -153,11 +163,7 @@ pub fn part2(input: &Path) -> Result<(), Error> {
                 board.has_won = true;
             }
             if boards_remaining == 0 {
-                println!(
-                    "winning score (last):  {}",
-                    board.sum_unmarked() * call as u32
-                );
-                return Ok(());
+                return Ok(board.sum_unmarked() * call as u32);
             }
         }
     }
@@ -165,12 +171,17 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Err(Error::NoSolution)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] TwoPhaseError),
-    #[error("no solution found")]
-    NoSolution,
-    #[error("bad board")]
-    BadBoard,
+pub fn part2(input: &Path) -> Result<u32, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("bad called numbers")]
+        BadCalls,
+        #[error("bad board")]
+        BadBoard,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,6 +1,6 @@
 use aoclib::{
     geometry::{line::Line, Point},
-    parse,
+    input::parse_str,
 };
 use std::path::Path;
 
@@ -55,9 +55,9 @@ fn line_points(line: Line) -> impl Iterator<Item = Point> {
 
 type Map = aoclib::geometry::Map<u8>;
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
     let mut map = Map::new(MAP_EDGE, MAP_EDGE);
-    for point in parse::<VentLine>(input)?
+    for point in parse_str::<VentLine>(input)?
         .map(Into::into)
         .filter(is_horizontal_or_vertical)
         .map(line_points)
@@ -66,19 +66,16 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         map[point] += 1;
     }
 
-    let intersections_count = map.iter().filter(|(_, count)| **count > 1).count();
-
-    println!(
-        "count of intersections (horiz or vert): {}",
-        intersections_count
-    );
+    Ok(map.iter().filter(|(_, count)| **count > 1).count())
+}
 
-    Ok(())
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn solve_part2(input: &str) -> Result<usize, Error> {
     let mut map = Map::new(MAP_EDGE, MAP_EDGE);
-    for point in parse::<VentLine>(input)?
+    for point in parse_str::<VentLine>(input)?
         .map(Into::into)
         .map(line_points)
         .flatten()
@@ -86,15 +83,15 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         map[point] += 1;
     }
 
-    let intersections_count = map.iter().filter(|(_, count)| **count > 1).count();
-
-    println!("count of intersections (all): {}", intersections_count);
+    Ok(map.iter().filter(|(_, count)| **count > 1).count())
+}
 
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+aoc_errors::day_error! {
+    pub enum Error {}
 }
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
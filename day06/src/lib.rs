@@ -1,4 +1,5 @@
-use aoclib::{input::CommaSep, parse};
+use answer::Answers;
+use aoclib::input::{parse_str, CommaSep};
 use std::{
     ops::{Deref, DerefMut},
     path::Path,
@@ -53,30 +54,39 @@ impl School {
     }
 }
 
-pub fn part1(input: &Path, days: usize) -> Result<(), Error> {
-    for (idx, line) in parse::<CommaSep<usize>>(input)?.enumerate() {
+/// Total fish after `days`, for each school in the input.
+pub fn solve_part1(input: &str, days: usize) -> Result<Answers<u64>, Error> {
+    let mut answers = Vec::new();
+    for (idx, line) in parse_str::<CommaSep<usize>>(input)?.enumerate() {
         let mut school = School::new(line).ok_or(Error::ElderFish(idx))?;
         for _day in 0..days {
             school.next();
         }
-        println!(
-            "{}: total fish after {} days: {}",
-            idx,
-            days,
-            school.sum_fish()
-        );
+        answers.push(school.sum_fish());
     }
-    Ok(())
+    Ok(Answers(answers))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    part1(input, 256)
+pub fn solve_part2(input: &str) -> Result<Answers<u64>, Error> {
+    solve_part1(input, 256)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("Fish is too old (line {0})")]
-    ElderFish(usize),
+pub fn part1(input: &Path, days: usize) -> Result<Answers<u64>, Error> {
+    solve_part1(&std::fs::read_to_string(input)?, days)
+}
+
+pub fn part2(input: &Path) -> Result<Answers<u64>, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("Fish is too old (line {0})")]
+        ElderFish(usize),
+    }
+}
+
+aoc_wasm::wasm_bindings! {
+    part1: |input| solve_part1(input, 80),
+    part2: |input| solve_part2(input),
 }
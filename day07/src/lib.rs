@@ -1,4 +1,5 @@
-use aoclib::{parse, CommaSep};
+use answer::Answers;
+use aoclib::{input::parse_str, CommaSep};
 use std::path::Path;
 
 #[cfg(feature = "parallelism")]
@@ -33,36 +34,40 @@ fn triangular_sequence(n: i32) -> i32 {
     n * (n + 1) / 2
 }
 
+/// Total fuel at the best position, for each line of crab submarines in the input.
 fn solve(
-    input: &Path,
-    nature: &str,
+    input: &str,
     fuel_per_submarine: impl Sync + Fn(i32) -> i32,
-) -> Result<(), Error> {
-    for (idx, line) in parse::<CommaSep<i32>>(input)?.enumerate() {
+) -> Result<Answers<i32>, Error> {
+    let mut answers = Vec::new();
+    for line in parse_str::<CommaSep<i32>>(input)? {
         let crab_submarines: Vec<_> = line.into();
-        let total_fuel_at_best_position =
+        answers.push(
             total_fuel_at_best_position(&crab_submarines, &fuel_per_submarine)
-                .ok_or(Error::NoSolution)?;
-        println!(
-            "{}: total fuel at best position ({}): {}",
-            idx, nature, total_fuel_at_best_position
-        )
+                .ok_or(Error::NoSolution)?,
+        );
     }
-    Ok(())
+    Ok(Answers(answers))
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    solve(input, "linear", std::convert::identity)
+pub fn solve_part1(input: &str) -> Result<Answers<i32>, Error> {
+    solve(input, std::convert::identity)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    solve(input, "increasing rate", triangular_sequence)
+pub fn solve_part2(input: &str) -> Result<Answers<i32>, Error> {
+    solve(input, triangular_sequence)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no solution found")]
-    NoSolution,
+pub fn part1(input: &Path) -> Result<Answers<i32>, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
+
+pub fn part2(input: &Path) -> Result<Answers<i32>, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {}
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,4 +1,4 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::{path::Path, str::FromStr};
 
 /// A `SegmentMap` maps all valid signals to outputs.
@@ -84,8 +84,7 @@ impl Entry {
         macro_rules! eq_or {
             ($left:expr, $right:expr, $err:literal) => {
                 if $left != $right {
-                    #[cfg(debug_assertions)]
-                    eprintln!($err);
+                    tracing::debug!($err);
                     return None;
                 }
             };
@@ -249,43 +248,46 @@ impl Entry {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let output_identifiable_digits_count = parse::<Entry>(input)?
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
+    let output_identifiable_digits_count = parse_str::<Entry>(input)?
         .flat_map(|entry| entry.output_value.into_iter())
         .filter(|signals| matches!(signals.segment_count(), 2 | 3 | 4 | 7))
         .count();
-    println!(
-        "identifiable output digits: {}",
-        output_identifiable_digits_count
-    );
-    Ok(())
+    Ok(output_identifiable_digits_count)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<u32, Error> {
     let mut output_sum = 0;
-    for entry in parse::<Entry>(input)? {
+    for (idx, entry) in parse_str::<Entry>(input)?.enumerate() {
+        let _entry = tracing::debug_span!("entry", line = idx + 1).entered();
         let map = entry.analyze_signals().ok_or(Error::NoSegmentMap)?;
         let value = entry.output_value(&map).ok_or(Error::UnknownSignal)?;
         output_sum += value;
     }
-    println!("output sum: {}", output_sum);
-    Ok(())
+    Ok(output_sum)
+}
+
+pub fn part2(input: &Path) -> Result<u32, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("duplicate letter in signal ({0})")]
-    DuplicateLetter(char),
-    #[error("invalid letter in signal ({0})")]
-    InvalidLetter(char),
-    #[error("malformed entry")]
-    MalformedEntry,
-    #[error("could not calculate an appropriate segment map")]
-    NoSegmentMap,
-    #[error("signal not found in segment map")]
-    UnknownSignal,
-    #[error("no solution found")]
-    NoSolution,
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("duplicate letter in signal ({0})")]
+        DuplicateLetter(char),
+        #[error("invalid letter in signal ({0})")]
+        InvalidLetter(char),
+        #[error("malformed entry")]
+        MalformedEntry,
+        #[error("could not calculate an appropriate segment map")]
+        NoSegmentMap,
+        #[error("signal not found in segment map")]
+        UnknownSignal,
+    }
 }
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -31,8 +31,8 @@ impl ContextInto<Traversable> for Digit {
 
 type Map = aoclib::geometry::Map<Digit>;
 
-fn read_input(input: &Path) -> Result<(Map, Vec<Point>), Error> {
-    let map = <Map as TryFrom<&Path>>::try_from(input)?;
+fn read_input(input: &str) -> Result<(Map, Vec<Point>), Error> {
+    let map = <Map as TryFrom<&str>>::try_from(input.trim())?;
     let low_points = map
         .iter()
         .filter(|(point, height)| {
@@ -44,18 +44,20 @@ fn read_input(input: &Path) -> Result<(Map, Vec<Point>), Error> {
     Ok((map, low_points))
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<u32, Error> {
     let (map, low_points) = read_input(input)?;
     let risk_level = low_points
         .iter()
         .map(|point| <Digit as Into<u8>>::into(map[*point]) as u32 + 1)
         .sum::<u32>();
+    Ok(risk_level)
+}
 
-    println!("sum of low point risk levels: {}", risk_level);
-    Ok(())
+pub fn part1(input: &Path) -> Result<u32, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
     let (map, low_points) = read_input(input)?;
     let mut region_sizes: Vec<_> = low_points
         .iter()
@@ -69,17 +71,18 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         })
         .collect();
     region_sizes.sort_unstable();
-    let basin_size_product: u64 = region_sizes.iter().rev().take(3).product();
-
-    println!("product of 3 largest basin sizes: {}", basin_size_product);
+    Ok(region_sizes.iter().rev().take(3).product())
+}
 
-    Ok(())
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("could not read map")]
-    MapConv(#[from] aoclib::geometry::map::MapConversionErr),
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("could not read map")]
+        MapConv(#[from] aoclib::geometry::map::MapConversionErr),
+    }
 }
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,4 +1,4 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::path::Path;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display)]
@@ -66,10 +66,10 @@ fn process_line(line_no: usize, line: String) -> Result<Stack, u32> {
             Ok(()) => {}
             Err(Error::Corrupted(bracket)) => return Err(bracket.corruption_penalty()),
             Err(Error::NotABracket(ch)) => {
-                eprintln!(
-                    "Line {}: {:?} is not a bracket. Don't trust the results!",
-                    line_no + 1,
-                    ch
+                tracing::warn!(
+                    line = line_no + 1,
+                    ?ch,
+                    "not a bracket; don't trust the results"
                 );
                 return Err(0);
             }
@@ -88,33 +88,38 @@ fn score_stack(stack: Stack) -> u64 {
     score
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let score = parse::<String>(input)?
+pub fn solve_part1(input: &str) -> Result<u32, Error> {
+    let score = parse_str::<String>(input)?
         .enumerate()
         .filter_map(|(line_no, line)| process_line(line_no, line).err())
         .sum::<u32>();
+    Ok(score)
+}
 
-    println!("syntax err score: {}", score);
-    Ok(())
+pub fn part1(input: &Path) -> Result<u32, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut scores: Vec<_> = parse::<String>(input)?
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
+    let mut scores: Vec<_> = parse_str::<String>(input)?
         .enumerate()
         .filter_map(|(line_no, line)| process_line(line_no, line).ok().map(score_stack))
         .collect();
     scores.sort_unstable();
-    let middle_score = scores[scores.len() / 2];
-    println!("median autocomplete score: {}", middle_score);
-    Ok(())
+    Ok(scores[scores.len() / 2])
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("not a bracket: {0}")]
-    NotABracket(char),
-    #[error("wrong close: {0}")]
-    Corrupted(Bracket),
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("not a bracket: {0}")]
+        NotABracket(char),
+        #[error("wrong close: {0}")]
+        Corrupted(Bracket),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,5 +1,6 @@
 use aoclib::geometry::{tile::Digit, Map};
-use std::path::Path;
+use std::{io::Write, path::Path};
+use viz::{render, Animation, Color};
 
 /// Advance a map's state, returning the number of flashes.
 fn step(map: &mut Map<u8>) -> u64 {
@@ -37,37 +38,67 @@ fn step(map: &mut Map<u8>) -> u64 {
     flashes
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let map = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
+pub fn solve_part1(input: &str) -> Result<u64, Error> {
+    let map = <Map<Digit> as TryFrom<&str>>::try_from(input.trim())?;
     let mut map: Map<u8> = map.convert_tile_type();
     let mut flashes = 0;
 
     for _ in 0..100 {
         flashes += step(&mut map);
     }
+    Ok(flashes)
+}
 
-    println!("flashes after 100 steps: {}", flashes);
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
 
-    Ok(())
+pub fn solve_part2(input: &str) -> Result<usize, Error> {
+    let map = <Map<Digit> as TryFrom<&str>>::try_from(input.trim())?;
+    let mut map: Map<u8> = map.convert_tile_type();
+
+    let all = (map.width() * map.height()) as u64;
+    Ok((1..)
+        .find(|_| step(&mut map) == all)
+        .expect("the range is unbounded"))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let map = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+/// Animate the octopuses until they all flash at once, highlighting each step's flashes.
+pub fn visualize(input: &str, animation: &mut Animation<impl Write>) -> Result<(), Error> {
+    let map = <Map<Digit> as TryFrom<&str>>::try_from(input.trim())?;
     let mut map: Map<u8> = map.convert_tile_type();
+    let all = (map.width() * map.height()) as u64;
 
-    for step_no in 1.. {
+    for step_number in 1.. {
         let flashes = step(&mut map);
-        if flashes == (map.width() * map.height()) as u64 {
-            println!("first step when all flash together: {}", step_no);
+        let octopuses = render(&map, |_, &energy| {
+            let color = if energy == 0 {
+                Color::BrightWhite
+            } else {
+                Color::Blue
+            };
+            animation.paint(color, energy)
+        });
+        animation.draw(format!(
+            "step {}: {} flashes\n{}",
+            step_number, flashes, octopuses
+        ))?;
+        if flashes == all {
             break;
         }
     }
-
     Ok(())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("could not read map")]
+        MapConv(#[from] aoclib::geometry::map::MapConversionErr),
+    }
 }
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -1,5 +1,6 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 use bitvec::prelude::*;
+use progress::Progress;
 use std::{
     collections::{HashSet, VecDeque},
     path::Path,
@@ -28,8 +29,8 @@ type Edges = std::collections::HashMap<usize, Vec<usize>>;
 /// - a 2-tuple:
 ///   - the index of the start cave in the caves list
 ///   - the index of the end cave in the caves list
-fn parse_input(input: &Path) -> Result<(Vec<Cave>, Edges, (usize, usize)), Error> {
-    let prim_edges: Vec<_> = parse::<PrimitiveEdge>(input)?.collect();
+fn parse_input(input: &str) -> Result<(Vec<Cave>, Edges, (usize, usize)), Error> {
+    let prim_edges: Vec<_> = parse_str::<PrimitiveEdge>(input)?.collect();
     let mut labels = Vec::with_capacity(prim_edges.len() * 2);
     for pe in prim_edges.iter() {
         labels.push(pe.from.clone());
@@ -72,7 +73,7 @@ struct SearchNode {
     visited_twice: bool,
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
     let (caves, edges, (start, end)) = parse_input(input)?;
 
     let mut queue = VecDeque::new();
@@ -113,8 +114,11 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         }
     }
 
-    println!("distinct paths through the cave system: {}", paths);
-    Ok(())
+    Ok(paths)
+}
+
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
 }
 
 /// make the path to this location
@@ -127,14 +131,17 @@ fn make_path(node: &SearchNode) -> Vec<usize> {
     path
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn solve_part2(input: &str) -> Result<usize, Error> {
     let (caves, edges, (start, end)) = parse_input(input)?;
 
     let mut paths = HashSet::new();
 
-    for can_visit_twice in (0..caves.len())
+    let twice_visitable: Vec<_> = (0..caves.len())
         .filter(|&cave_idx| !caves[cave_idx].is_big && !(caves[cave_idx].label == "start"))
-    {
+        .collect();
+    let progress = Progress::new("caves visited twice", twice_visitable.len() as u64);
+
+    for can_visit_twice in twice_visitable {
         let mut queue = VecDeque::new();
         queue.push_back(SearchNode {
             location: start,
@@ -174,17 +181,18 @@ pub fn part2(input: &Path) -> Result<(), Error> {
                 }
             }
         }
+        progress.inc(1);
     }
 
-    println!(
-        "distinct paths through the cave system visiting 1 small twice: {}",
-        paths.len()
-    );
-    Ok(())
+    Ok(paths.len())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {}
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
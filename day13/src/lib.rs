@@ -1,8 +1,9 @@
 use aoclib::{
     geometry::{tile::Bool, Map, Point},
-    parse,
+    input::parse_str,
 };
-use std::{cmp::Ordering, collections::HashSet, path::Path};
+use std::{cmp::Ordering, collections::HashSet, io::Write, path::Path};
+use viz::{render, Animation, Color};
 
 #[derive(Debug, Clone, Copy, parse_display::FromStr)]
 enum Axis {
@@ -39,7 +40,7 @@ impl Fold {
                 // no change
             }
             Ordering::Equal => {
-                eprintln!("folding {:?} over {:?}, point on fold line", point, self);
+                tracing::warn!(?point, fold = ?self, "point on fold line");
             }
             Ordering::Greater => {
                 // reflect the point
@@ -52,11 +53,11 @@ impl Fold {
     }
 }
 
-fn parse_input(input: &Path) -> Result<(Vec<Point>, Vec<Fold>), Error> {
+fn parse_input(input: &str) -> Result<(Vec<Point>, Vec<Fold>), Error> {
     let mut points = Vec::new();
     let mut folds = Vec::new();
 
-    for instruction in parse::<Instruction>(input)? {
+    for instruction in parse_str::<Instruction>(input)? {
         match instruction {
             Instruction::Dot(x, y) => points.push(Point::new(x, y)),
             Instruction::Fold(axis, offset) => folds.push(Fold { axis, offset }),
@@ -67,33 +68,28 @@ fn parse_input(input: &Path) -> Result<(Vec<Point>, Vec<Fold>), Error> {
     Ok((points, folds))
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
     let (points, folds) = parse_input(input)?;
     let first_fold = *folds.first().ok_or(Error::NoSolution)?;
     let mut point_collection = HashSet::with_capacity(points.len());
     for point in points {
         point_collection.insert(first_fold.apply(point));
     }
-    println!("{} points after first fold", point_collection.len());
-    Ok(())
+    Ok(point_collection.len())
 }
 
-type DisplayBoard = Map<Bool>;
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let (points, folds) = parse_input(input)?;
-    let mut point_collection = HashSet::with_capacity(points.len());
-    for mut point in points {
-        for fold in &folds {
-            point = fold.apply(point);
-        }
-        point_collection.insert(point);
-    }
+type DisplayBoard = Map<Bool>;
 
+/// The transparent paper, as it appears with dots at `point_collection`.
+fn paper(point_collection: &HashSet<Point>) -> DisplayBoard {
     // determine the max and min points
     let mut max = Point::new(i32::MIN, i32::MIN);
     let mut min = Point::new(i32::MAX, i32::MAX);
-    for point in &point_collection {
+    for point in point_collection {
         max.x = max.x.max(point.x);
         max.y = max.y.max(point.y);
         min.x = min.x.min(point.x);
@@ -105,19 +101,138 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         (max.x - min.x + 1) as usize,
         (max.y - min.y + 1) as usize,
     );
-    for point in point_collection {
+    for &point in point_collection {
         board[point] = true.into();
     }
-    board = board.flip_vertical();
+    board.flip_vertical()
+}
+
+/// The activation code written on the transparent paper.
+///
+/// The puzzle's example folds into a 5x5 square rather than letters, so it fails with
+/// [`ocr::Error::Height`].
+pub fn solve_part2(input: &str) -> Result<String, Error> {
+    let (points, folds) = parse_input(input)?;
+    let mut point_collection = HashSet::with_capacity(points.len());
+    for mut point in points {
+        for fold in &folds {
+            point = fold.apply(point);
+        }
+        point_collection.insert(point);
+    }
+    Ok(ocr::recognize(&paper(&point_collection))?)
+}
 
-    println!("activation code:\n{}", board);
+pub fn part2(input: &Path) -> Result<String, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+fn draw_paper(
+    animation: &mut Animation<impl Write>,
+    point_collection: &HashSet<Point>,
+    caption: String,
+) -> std::io::Result<()> {
+    let dots = render(&paper(point_collection), |_, &tile| {
+        if bool::from(tile) {
+            animation.paint(Color::Yellow, '#')
+        } else {
+            animation.paint(Color::Blue, '.')
+        }
+    });
+    animation.draw(format!("{}\n{}", caption, dots))
+}
+
+/// Animate the paper being folded, one fold per frame.
+///
+/// Until the last few folds, the paper is likely to be larger than the terminal.
+pub fn visualize(input: &str, animation: &mut Animation<impl Write>) -> Result<(), Error> {
+    let (points, folds) = parse_input(input)?;
+    let mut point_collection: HashSet<_> = points.into_iter().collect();
+    draw_paper(animation, &point_collection, "unfolded".into())?;
+
+    for (idx, fold) in folds.iter().enumerate() {
+        point_collection = point_collection
+            .into_iter()
+            .map(|point| fold.apply(point))
+            .collect();
+        let caption = format!("fold {} of {}", idx + 1, folds.len());
+        draw_paper(animation, &point_collection, caption)?;
+    }
     Ok(())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no solution found")]
-    NoSolution,
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error(transparent)]
+        Ocr(#[from] ocr::Error),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_into_letters() {
+        let drawing = [
+            "####.#..#",
+            "#....#..#",
+            "###..####",
+            "#....#..#",
+            "#....#..#",
+            "####.#..#",
+        ];
+        let mut input = String::new();
+        for (y, row) in drawing.iter().enumerate() {
+            for (x, dot) in row.chars().enumerate() {
+                if dot != '#' {
+                    continue;
+                }
+                let (mut x, mut y) = (x as i32, y as i32);
+                // reflect some dots across each fold, so the word only appears once folded
+                if (x + y) % 2 == 0 {
+                    y = 14 - y;
+                }
+                if x % 3 == 0 {
+                    x = 20 - x;
+                }
+                input.push_str(&format!("{},{}\n", x, y));
+            }
+        }
+        input.push_str("\nfold along y=7\nfold along x=10\n");
+        assert_eq!(solve_part2(&input).unwrap(), "EH");
+    }
+
+    #[test]
+    fn example_is_not_letters() {
+        let input = "
+6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5
+";
+        assert!(matches!(
+            solve_part2(input.trim()),
+            Err(Error::Ocr(ocr::Error::Height(5)))
+        ));
+    }
 }
@@ -122,15 +122,15 @@ impl PairTable {
     }
 }
 
-fn parse_input(input: &Path) -> Result<(PairTable, Vec<InsertionRule>), Error> {
-    use aoclib::input::{parse_newline_sep, parse_str};
+fn parse_input(input: &str) -> Result<(PairTable, Vec<InsertionRule>), Error> {
+    use aoclib::input::parse_str;
 
-    let mut sections = parse_newline_sep::<String>(input)?;
+    let mut sections = input.trim().split("\n\n");
     let polymer_template = sections.next().ok_or(Error::MalformedInput)?;
     let polymer_template = polymer_template.trim().parse()?;
 
     let insertion_rules = sections.next().ok_or(Error::MalformedInput)?;
-    let insertion_rules: Vec<InsertionRule> = parse_str(&insertion_rules)?.collect();
+    let insertion_rules: Vec<InsertionRule> = parse_str(insertion_rules)?.collect();
 
     if sections.next().is_some() {
         return Err(Error::MalformedInput);
@@ -139,31 +139,35 @@ fn parse_input(input: &Path) -> Result<(PairTable, Vec<InsertionRule>), Error> {
     Ok((polymer_template, insertion_rules))
 }
 
-fn solve(input: &Path, iterations: u8) -> Result<(), Error> {
+fn solve(input: &str, iterations: u8) -> Result<u64, Error> {
     let (mut pair_table, insertion_rules) = parse_input(input)?;
     for _ in 0..iterations {
         pair_table = pair_table.apply(&insertion_rules);
     }
-    let solution = pair_table.puzzle_solution();
-
-    println!("part 1 solution: {}", solution);
-    Ok(())
+    Ok(pair_table.puzzle_solution())
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn solve_part1(input: &str) -> Result<u64, Error> {
     solve(input, 10)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
     solve(input, 40)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("malformed input")]
-    MalformedInput,
-    #[error("no solution found")]
-    NoSolution,
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
 }
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("malformed input")]
+        MalformedInput,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
@@ -0,0 +1,45 @@
+use aoclib::geometry::Map;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day15::{risk_map::VirtualMap, Algorithm};
+
+/// Deterministic pseudorandom risk map.
+fn pseudorandom_map(width: usize, height: usize, mut seed: u64) -> Map<u8> {
+    let mut map = Map::new(width, height);
+    for (_, tile) in map.iter_mut() {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *tile = (seed >> 33) as u8 % 9 + 1;
+    }
+    map
+}
+
+fn algorithms() -> Vec<Algorithm> {
+    vec![
+        Algorithm::Dijkstra,
+        Algorithm::Bidirectional,
+        #[cfg(feature = "parallelism")]
+        Algorithm::DeltaStepping,
+    ]
+}
+
+fn bench_shortest_path(c: &mut Criterion) {
+    let small_map = pseudorandom_map(100, 100, 15);
+
+    let mut group = c.benchmark_group("shortest path");
+    group.sample_size(10);
+    for tiles in [1, 5, 10] {
+        let map = VirtualMap::new(&small_map, tiles);
+        for algorithm in algorithms() {
+            group.bench_with_input(
+                BenchmarkId::new(algorithm.to_string(), map.width()),
+                &map,
+                |b, map| b.iter(|| algorithm.lowest_risk_path(map)),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_shortest_path);
+criterion_main!(benches);
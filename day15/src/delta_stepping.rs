@@ -0,0 +1,104 @@
+//! Parallel single-source shortest path via delta-stepping.
+//!
+//! See Meyer & Sanders, "Δ-stepping: a parallelizable shortest path algorithm" (2003).
+//!
+//! Tentative risks are kept in buckets of width `delta`. Each phase drains the lowest
+//! nonempty bucket: relaxation requests for every node in the bucket are generated in
+//! parallel, then applied sequentially. Light edges (risk no greater than `delta`) may
+//! refill the current bucket, so they are repeated until the bucket stays empty; heavy
+//! edges can only ever land in later buckets, so they are relaxed once per phase.
+
+use crate::risk_map::{orthogonal_adjacencies, RiskMap};
+use aoclib::geometry::Point;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+struct DeltaStepping<'a, M> {
+    map: &'a M,
+    delta: u64,
+    buckets: Vec<Vec<Point>>,
+    total_risk: HashMap<Point, u64>,
+}
+
+impl<'a, M: RiskMap + Sync> DeltaStepping<'a, M> {
+    fn new(map: &'a M, delta: u64) -> Self {
+        assert!(delta > 0, "delta must be positive");
+        let mut search = DeltaStepping {
+            map,
+            delta,
+            buckets: Vec::new(),
+            total_risk: HashMap::new(),
+        };
+        search.relax(map.top_left(), 0);
+        search
+    }
+
+    fn relax(&mut self, position: Point, total_risk: u64) {
+        let known = self.total_risk.entry(position).or_insert(u64::MAX);
+        if total_risk < *known {
+            *known = total_risk;
+            let bucket = (total_risk / self.delta) as usize;
+            if bucket >= self.buckets.len() {
+                self.buckets.resize_with(bucket + 1, Vec::new);
+            }
+            self.buckets[bucket].push(position);
+        }
+    }
+
+    /// Generate relaxation requests for the edges leaving `nodes` which satisfy `edge_filter`.
+    fn requests(
+        &self,
+        nodes: &[Point],
+        edge_filter: impl Sync + Fn(u64) -> bool,
+    ) -> Vec<(Point, u64)> {
+        nodes
+            .par_iter()
+            .flat_map_iter(|&node| {
+                let total_risk = self.total_risk[&node];
+                let edge_filter = &edge_filter;
+                orthogonal_adjacencies(self.map, node).filter_map(move |adjacent| {
+                    let risk = self.map.risk(adjacent);
+                    edge_filter(risk).then(|| (adjacent, total_risk + risk))
+                })
+            })
+            .collect()
+    }
+
+    fn run(mut self) -> Option<u64> {
+        let delta = self.delta;
+        let mut bucket_idx = 0;
+        while bucket_idx < self.buckets.len() {
+            let mut phase_nodes = Vec::new();
+
+            while !self.buckets[bucket_idx].is_empty() {
+                let mut nodes = std::mem::take(&mut self.buckets[bucket_idx]);
+                // nodes whose risk has since improved into an earlier bucket were already handled
+                nodes.retain(|node| (self.total_risk[node] / delta) as usize == bucket_idx);
+                nodes.sort_unstable();
+                nodes.dedup();
+
+                let requests = self.requests(&nodes, |risk| risk <= delta);
+                phase_nodes.extend(nodes);
+                for (position, total_risk) in requests {
+                    self.relax(position, total_risk);
+                }
+            }
+
+            phase_nodes.sort_unstable();
+            phase_nodes.dedup();
+            for (position, total_risk) in self.requests(&phase_nodes, |risk| risk > delta) {
+                self.relax(position, total_risk);
+            }
+
+            bucket_idx += 1;
+        }
+
+        self.total_risk.get(&self.map.bottom_right()).copied()
+    }
+}
+
+/// Find the lowest total risk from the top left to the bottom right of `map` using
+/// delta-stepping with the specified bucket width.
+pub fn lowest_risk_path(map: &(impl RiskMap + Sync), delta: u64) -> Option<u64> {
+    DeltaStepping::new(map, delta).run()
+}
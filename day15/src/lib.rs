@@ -1,10 +1,17 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{binary_heap::BinaryHeap, HashSet},
+    collections::{binary_heap::BinaryHeap, HashMap, HashSet},
     path::Path,
 };
 
+#[cfg(feature = "parallelism")]
+pub mod delta_stepping;
+pub mod risk_map;
+
+use answer::Answers;
 use aoclib::geometry::{tile::Digit, Map, Point};
+use progress::Progress;
+use risk_map::{orthogonal_adjacencies, RiskMap, VirtualMap};
 
 #[derive(Debug, PartialEq, Eq, Default)]
 struct HeapNode {
@@ -26,28 +33,101 @@ impl PartialOrd for HeapNode {
     }
 }
 
-fn find_lowest_risk_path_top_left_to_bottom_right(map: &Map<u8>) -> u64 {
-    let mut visited = HashSet::new();
+/// The number of positions in `map`.
+fn area(map: &impl RiskMap) -> u64 {
+    let (top_left, bottom_right) = (map.top_left(), map.bottom_right());
+    ((bottom_right.x - top_left.x + 1) * (top_left.y - bottom_right.y + 1)) as u64
+}
+
+/// The lowest-risk route from the top left to the bottom right of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The risk accumulated along the route, excluding the risk of the starting position.
+    pub total_risk: u64,
+    /// Every position along the route, from the top left to the bottom right inclusive.
+    pub points: Vec<Point>,
+}
+
+impl Route {
+    /// Render `map` with each position along this route showing its risk, and every other
+    /// position as `.`.
+    ///
+    /// When some risk in the map has more than one digit, each column is padded to the same
+    /// width and separated by a space.
+    pub fn render(&self, map: &impl RiskMap) -> String {
+        let on_route: HashSet<_> = self.points.iter().copied().collect();
+        let width = map.max_risk().to_string().len();
+        let separator = if width > 1 { " " } else { "" };
+        let (top_left, bottom_right) = (map.top_left(), map.bottom_right());
+
+        (bottom_right.y..=top_left.y)
+            .rev()
+            .map(|y| {
+                (top_left.x..=bottom_right.x)
+                    .map(|x| {
+                        let point = Point::new(x, y);
+                        if on_route.contains(&point) {
+                            format!("{:>width$}", map.risk(point), width = width)
+                        } else {
+                            format!("{:>width$}", '.', width = width)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Walk `predecessors` back from `position` to the origin, which is its own predecessor.
+fn trace_back(predecessors: &HashMap<Point, Point>, mut position: Point) -> Vec<Point> {
+    let mut points = vec![position];
+    while let Some(&previous) = predecessors.get(&position) {
+        if previous == position {
+            break;
+        }
+        points.push(previous);
+        position = previous;
+    }
+    points.reverse();
+    points
+}
+
+fn find_lowest_risk_route_top_left_to_bottom_right(map: &impl RiskMap) -> Route {
+    let progress = Progress::new("positions visited", area(map));
+    // each visited position maps to the position from which it was first reached
+    let mut predecessors = HashMap::new();
     let mut heap = BinaryHeap::new();
 
-    heap.push(Reverse(HeapNode {
-        position: map.top_left(),
-        ..HeapNode::default()
-    }));
-    while let Some(Reverse(node)) = heap.pop() {
-        if visited.contains(&node.position) {
+    heap.push(Reverse((
+        HeapNode {
+            position: map.top_left(),
+            ..HeapNode::default()
+        },
+        map.top_left(),
+    )));
+    while let Some(Reverse((node, previous))) = heap.pop() {
+        if predecessors.contains_key(&node.position) {
             continue;
         }
+        predecessors.insert(node.position, previous);
         if node.position == map.bottom_right() {
-            return node.total_risk;
-        }
-        visited.insert(node.position);
-        for adjacent in map.orthogonal_adjacencies(node.position) {
-            if !visited.contains(&adjacent) {
-                heap.push(Reverse(HeapNode {
-                    position: adjacent,
-                    total_risk: node.total_risk + map[adjacent] as u64,
-                }));
+            return Route {
+                total_risk: node.total_risk,
+                points: trace_back(&predecessors, node.position),
+            };
+        }
+        progress.inc(1);
+        for adjacent in orthogonal_adjacencies(map, node.position) {
+            if !predecessors.contains_key(&adjacent) {
+                heap.push(Reverse((
+                    HeapNode {
+                        position: adjacent,
+                        total_risk: node.total_risk + map.risk(adjacent),
+                    },
+                    node.position,
+                )));
             }
         }
     }
@@ -55,53 +135,608 @@ fn find_lowest_risk_path_top_left_to_bottom_right(map: &Map<u8>) -> u64 {
     unreachable!("every map has _some_ traversable path")
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let map = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
+/// Find the lowest-risk route from the top left to the bottom right of `map`.
+///
+/// This always uses single-direction Dijkstra, which tracks each position's predecessor.
+pub fn lowest_risk_route(map: &impl RiskMap) -> Route {
+    find_lowest_risk_route_top_left_to_bottom_right(map)
+}
+
+/// One half of a bidirectional search: a Dijkstra frontier expanding from a single origin.
+///
+/// Distances are stored as the risk accumulated along the path, excluding the risk
+/// of the origin itself.
+#[derive(Debug, Default)]
+struct Frontier {
+    heap: BinaryHeap<Reverse<HeapNode>>,
+    tentative: HashMap<Point, u64>,
+    settled: HashSet<Point>,
+}
+
+impl Frontier {
+    fn new(origin: Point) -> Self {
+        let mut frontier = Self::default();
+        frontier.relax(origin, 0);
+        frontier
+    }
+
+    /// Record a path to `position` if it is better than any known so far.
+    fn relax(&mut self, position: Point, total_risk: u64) {
+        if self.settled.contains(&position) {
+            return;
+        }
+        let known = self.tentative.entry(position).or_insert(u64::MAX);
+        if total_risk < *known {
+            *known = total_risk;
+            self.heap.push(Reverse(HeapNode {
+                position,
+                total_risk,
+            }));
+        }
+    }
+
+    /// Discard stale heap entries, then return the lowest unsettled risk.
+    fn peek(&mut self) -> Option<u64> {
+        while let Some(Reverse(node)) = self.heap.peek() {
+            if self.settled.contains(&node.position) {
+                self.heap.pop();
+            } else {
+                return Some(node.total_risk);
+            }
+        }
+        None
+    }
+
+    /// Settle and return the lowest unsettled node.
+    fn pop(&mut self) -> Option<HeapNode> {
+        self.peek()?;
+        let Reverse(node) = self.heap.pop()?;
+        self.settled.insert(node.position);
+        Some(node)
+    }
+
+    fn distance(&self, position: Point) -> Option<u64> {
+        self.tentative.get(&position).copied()
+    }
+}
+
+/// Bidirectional Dijkstra: expand from both corners at once, meeting in the middle.
+///
+/// The forward search is ordinary. The backward search runs over the reversed graph:
+/// stepping from `position` back to a neighbor costs the risk of `position`.
+///
+/// The search may stop as soon as the sum of both frontiers' minimum risks is no
+/// better than the best complete path seen so far. On large maps this explores
+/// roughly half the area of the single-direction search.
+fn bidirectional_lowest_risk_path_top_left_to_bottom_right(map: &impl RiskMap) -> u64 {
+    let source = map.top_left();
+    let target = map.bottom_right();
+    if source == target {
+        return 0;
+    }
+
+    let progress = Progress::new("positions settled", area(map));
+    let mut forward = Frontier::new(source);
+    let mut backward = Frontier::new(target);
+    let mut best = u64::MAX;
+
+    while let (Some(forward_min), Some(backward_min)) = (forward.peek(), backward.peek()) {
+        if forward_min.saturating_add(backward_min) >= best {
+            break;
+        }
+
+        if forward_min <= backward_min {
+            let node = forward.pop().expect("peek succeeded");
+            progress.inc(1);
+            for adjacent in orthogonal_adjacencies(map, node.position) {
+                let total_risk = node.total_risk + map.risk(adjacent);
+                forward.relax(adjacent, total_risk);
+                if let Some(remaining) = backward.distance(adjacent) {
+                    best = best.min(total_risk + remaining);
+                }
+            }
+        } else {
+            let node = backward.pop().expect("peek succeeded");
+            progress.inc(1);
+            let total_risk = node.total_risk + map.risk(node.position);
+            for adjacent in orthogonal_adjacencies(map, node.position) {
+                backward.relax(adjacent, total_risk);
+                if let Some(prefix) = forward.distance(adjacent) {
+                    best = best.min(prefix + total_risk);
+                }
+            }
+        }
+    }
+
+    assert_ne!(best, u64::MAX, "every map has _some_ traversable path");
+    best
+}
+
+/// Which shortest-path algorithm to use.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Algorithm {
+    /// Single-direction Dijkstra from the top left.
+    #[default]
+    Dijkstra,
+    /// Dijkstra from both corners at once, meeting in the middle.
+    Bidirectional,
+    /// Parallel delta-stepping.
+    #[cfg(feature = "parallelism")]
+    DeltaStepping,
+}
+
+impl Algorithm {
+    /// Find the lowest total risk from the top left to the bottom right of `map`.
+    pub fn lowest_risk_path(self, map: &(impl RiskMap + Sync)) -> u64 {
+        match self {
+            Algorithm::Dijkstra => find_lowest_risk_route_top_left_to_bottom_right(map).total_risk,
+            Algorithm::Bidirectional => {
+                bidirectional_lowest_risk_path_top_left_to_bottom_right(map)
+            }
+            #[cfg(feature = "parallelism")]
+            Algorithm::DeltaStepping => {
+                // bucket width matches the greatest single risk, so every edge is light
+                // and the bucket count scales with the map's weights
+                delta_stepping::lowest_risk_path(map, map.max_risk().max(1))
+                    .expect("every map has _some_ traversable path")
+            }
+        }
+    }
+}
+
+/// How the risks of an input map are written.
+///
+/// The two formats can't be told apart reliably: a single column of multi-digit risks
+/// reads equally well as a map of one digit per position. So the format is never guessed.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Format {
+    /// One digit per position, as in the puzzle input.
+    #[default]
+    Digits,
+    /// Comma- or whitespace-separated integers, which may exceed 9.
+    Weighted,
+}
+
+/// `true` when any row of `input` contains a comma or whitespace.
+fn has_separators(input: &str) -> bool {
+    input.lines().any(|line| {
+        line.trim()
+            .contains(|c: char| c == ',' || c.is_whitespace())
+    })
+}
+
+/// Parse a map whose rows are comma- or whitespace-separated integers.
+///
+/// This supports risks greater than 9.
+fn parse_weighted_map(input: &str) -> Result<Map<u16>, Error> {
+    let rows = input
+        .lines()
+        .map(|line| {
+            line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|token| !token.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<u16>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let width = rows.first().map(Vec::len).unwrap_or_default();
+    if width == 0 || rows.iter().any(|row| row.len() != width) {
+        return Err(Error::RaggedMap);
+    }
+
+    let mut map = Map::new(width, rows.len());
+    let high_y = map.high_y() as usize;
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, risk) in row.into_iter().enumerate() {
+            map[(x, high_y - y)] = risk;
+        }
+    }
+    Ok(map)
+}
+
+/// Parse a map written in `format`.
+fn parse_map(input: &str, format: Format) -> Result<Map<u16>, Error> {
+    let input = input.trim();
+    match format {
+        Format::Weighted => return parse_weighted_map(input),
+        Format::Digits if has_separators(input) => return Err(Error::UnexpectedSeparator),
+        Format::Digits => {}
+    }
+
+    let map = <Map<Digit> as TryFrom<&str>>::try_from(input)?;
     let map: Map<u8> = map.convert_tile_type();
-    let total_risk = find_lowest_risk_path_top_left_to_bottom_right(&map);
-    println!("total risk (small map): {}", total_risk);
-    Ok(())
-}
-
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let map = {
-        let small_map = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
-        let small_map: Map<u8> = small_map.convert_tile_type();
-        let small_map = small_map.flip_vertical();
-
-        let mut map = Map::new(small_map.width() * 5, small_map.height() * 5);
-        for (point, tile) in map.iter_mut() {
-            let increase = (point.x as usize / small_map.width()
-                + point.y as usize / small_map.height()) as u8;
-            *tile = (small_map[(
-                point.x as usize % small_map.width(),
-                point.y as usize % small_map.height(),
-            )] + increase
-                - 1)
-                % 9
-                + 1;
-            if *tile == 0 {
-                *tile = 1;
+    Ok(map.convert_tile_type())
+}
+
+/// Blank-line-separated maps within `input`.
+fn maps(input: &str) -> Vec<String> {
+    let mut maps = vec![String::new()];
+    for line in input.lines() {
+        let current = maps.last_mut().expect("maps is never empty");
+        if !line.trim().is_empty() {
+            current.push_str(line);
+            current.push('\n');
+        } else if !current.is_empty() {
+            maps.push(String::new());
+        }
+    }
+    maps.retain(|map| !map.is_empty());
+    maps
+}
+
+/// Parse a map to which the expansion rule applies.
+fn parse_expandable_map(input: &str, format: Format) -> Result<Map<u16>, Error> {
+    let small_map = parse_map(input, format)?;
+    if small_map.max_risk() > 9 {
+        return Err(Error::MultiDigitExpansion);
+    }
+    Ok(small_map)
+}
+
+/// Compute the lowest total risk across each map in `input`.
+pub fn solve_part1(input: &str) -> Result<Answers<u64>, Error> {
+    solve_part1_with(input, Algorithm::default(), Format::default())
+}
+
+pub fn solve_part1_with(
+    input: &str,
+    algorithm: Algorithm,
+    format: Format,
+) -> Result<Answers<u64>, Error> {
+    maps(input)
+        .iter()
+        .map(|map| {
+            let map = parse_map(map, format)?;
+            Ok(algorithm.lowest_risk_path(&map))
+        })
+        .collect()
+}
+
+/// Compute the lowest total risk across each full map, expanded from those in `input`.
+pub fn solve_part2(input: &str) -> Result<Answers<u64>, Error> {
+    solve_part2_with(input, Algorithm::default(), Format::default())
+}
+
+pub fn solve_part2_with(
+    input: &str,
+    algorithm: Algorithm,
+    format: Format,
+) -> Result<Answers<u64>, Error> {
+    maps(input)
+        .iter()
+        .map(|map| {
+            let small_map = parse_expandable_map(map, format)?;
+            Ok(algorithm.lowest_risk_path(&VirtualMap::new(&small_map, 5)))
+        })
+        .collect()
+}
+
+/// Find the lowest-risk route across each map in `input`, rendered for display.
+pub fn render_part1_route(input: &str, format: Format) -> Result<String, Error> {
+    let mut out = String::new();
+    for (idx, map) in maps(input).iter().enumerate() {
+        let map = parse_map(map, format)?;
+        out.push_str(&format!(
+            "map {}:\n{}\n",
+            idx,
+            lowest_risk_route(&map).render(&map)
+        ));
+    }
+    Ok(out)
+}
+
+/// Find the lowest-risk route across each expanded map, rendered for display.
+pub fn render_part2_route(input: &str, format: Format) -> Result<String, Error> {
+    let mut out = String::new();
+    for (idx, map) in maps(input).iter().enumerate() {
+        let small_map = parse_expandable_map(map, format)?;
+        let map = VirtualMap::new(&small_map, 5);
+        out.push_str(&format!(
+            "map {}:\n{}\n",
+            idx,
+            lowest_risk_route(&map).render(&map)
+        ));
+    }
+    Ok(out)
+}
+
+pub fn part1(input: &Path) -> Result<Answers<u64>, Error> {
+    part1_with(input, Algorithm::default(), Format::default())
+}
+
+pub fn part1_with(
+    input: &Path,
+    algorithm: Algorithm,
+    format: Format,
+) -> Result<Answers<u64>, Error> {
+    solve_part1_with(&std::fs::read_to_string(input)?, algorithm, format)
+}
+
+pub fn part2(input: &Path) -> Result<Answers<u64>, Error> {
+    part2_with(input, Algorithm::default(), Format::default())
+}
+
+pub fn part2_with(
+    input: &Path,
+    algorithm: Algorithm,
+    format: Format,
+) -> Result<Answers<u64>, Error> {
+    solve_part2_with(&std::fs::read_to_string(input)?, algorithm, format)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("could not read map")]
+        MapConv(#[from] aoclib::geometry::map::MapConversionErr),
+        #[error("could not parse risk")]
+        ParseRisk(#[from] std::num::ParseIntError),
+        #[error("found a separator in a map of digits; use the weighted format")]
+        UnexpectedSeparator,
+        #[error("map rows must be nonempty and of equal width")]
+        RaggedMap,
+        #[error("the expansion rule only applies to single-digit risks")]
+        MultiDigitExpansion,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+";
+
+    fn map_from_rows<'a>(rows: impl IntoIterator<Item = &'a str>) -> Map<u8> {
+        let rows: Vec<Vec<u8>> = rows
+            .into_iter()
+            .map(|row| row.bytes().map(|b| b - b'0').collect())
+            .collect();
+        let mut map = Map::new(rows[0].len(), rows.len());
+        let high_y = map.high_y() as usize;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, risk) in row.iter().enumerate() {
+                map[(x, high_y - y)] = *risk;
             }
         }
-        map.flip_vertical()
-    };
-    // {
-    //     let mut dmap = Map::<Digit>::new(map.width(), map.height());
-    //     for (point, tile) in dmap.iter_mut() {
-    //         *tile = map[point].to_string().parse().unwrap();
-    //     }
-    //     eprintln!("{}", dmap);
-    // }
-    let total_risk = find_lowest_risk_path_top_left_to_bottom_right(&map);
-    println!("total risk (big map): {}", total_risk);
-    Ok(())
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no solution found")]
-    NoSolution,
+        map
+    }
+
+    /// Deterministic pseudorandom map, so the cross-checks cover more than the example.
+    fn pseudorandom_map(width: usize, height: usize, mut seed: u64) -> Map<u8> {
+        let mut map = Map::new(width, height);
+        for (_, tile) in map.iter_mut() {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            *tile = (seed >> 33) as u8 % 9 + 1;
+        }
+        map
+    }
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(solve_part1(EXAMPLE.trim()).unwrap(), Answers(vec![40]));
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(solve_part2(EXAMPLE.trim()).unwrap(), Answers(vec![315]));
+    }
+
+    #[test]
+    fn example_dijkstra() {
+        let map = map_from_rows(EXAMPLE.trim().lines());
+        assert_eq!(Algorithm::Dijkstra.lowest_risk_path(&map), 40);
+    }
+
+    #[test]
+    fn example_bidirectional() {
+        let map = map_from_rows(EXAMPLE.trim().lines());
+        assert_eq!(Algorithm::Bidirectional.lowest_risk_path(&map), 40);
+    }
+
+    #[test]
+    fn example_expanded() {
+        let small_map = map_from_rows(EXAMPLE.trim().lines());
+        let map = VirtualMap::new(&small_map, 5);
+        assert_eq!(map.risk(map.top_left()), 1);
+        assert_eq!(map.risk(map.bottom_right()), 9);
+        for algorithm in [Algorithm::Dijkstra, Algorithm::Bidirectional] {
+            assert_eq!(algorithm.lowest_risk_path(&map), 315);
+        }
+    }
+
+    #[cfg(feature = "parallelism")]
+    #[test]
+    fn delta_stepping_matches_dijkstra() {
+        let small_map = map_from_rows(EXAMPLE.trim().lines());
+        assert_eq!(Algorithm::DeltaStepping.lowest_risk_path(&small_map), 40);
+        assert_eq!(
+            Algorithm::DeltaStepping.lowest_risk_path(&VirtualMap::new(&small_map, 5)),
+            315
+        );
+
+        for (seed, (width, height)) in [(1, 1), (2, 1), (7, 13), (63, 17), (100, 100)]
+            .into_iter()
+            .enumerate()
+        {
+            let map = pseudorandom_map(width, height, seed as u64);
+            let expect = Algorithm::Dijkstra.lowest_risk_path(&map);
+            for delta in [1, 3, 9, 100] {
+                assert_eq!(
+                    delta_stepping::lowest_risk_path(&map, delta),
+                    Some(expect),
+                    "{}x{} map with seed {} and delta {}",
+                    width,
+                    height,
+                    seed,
+                    delta,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_format() {
+        let input = "
+1, 1,  1, 1
+300 300 300 1
+1,1,1,1
+1, 300, 300, 300
+1 1 1 1
+";
+        assert!(has_separators(input.trim()));
+        let map = parse_weighted_map(input.trim()).unwrap();
+        assert_eq!(map.max_risk(), 300);
+        for algorithm in [Algorithm::Dijkstra, Algorithm::Bidirectional] {
+            assert_eq!(algorithm.lowest_risk_path(&map), 13);
+        }
+        assert_eq!(
+            solve_part1_with(input, Algorithm::default(), Format::Weighted).unwrap(),
+            Answers(vec![13])
+        );
+        assert!(matches!(
+            solve_part2_with(input, Algorithm::default(), Format::Weighted),
+            Err(Error::MultiDigitExpansion)
+        ));
+        assert!(matches!(
+            solve_part1(input),
+            Err(Error::UnexpectedSeparator)
+        ));
+    }
+
+    #[test]
+    fn single_column_needs_explicit_format() {
+        let input = "12\n300\n45";
+        assert!(solve_part1(input).is_err());
+        assert_eq!(
+            solve_part1_with(input, Algorithm::default(), Format::Weighted).unwrap(),
+            Answers(vec![345])
+        );
+
+        // equally valid as two columns of digits
+        let input = "12\n34";
+        assert_eq!(solve_part1(input).unwrap(), Answers(vec![6]));
+        assert_eq!(
+            solve_part1_with(input, Algorithm::default(), Format::Weighted).unwrap(),
+            Answers(vec![34])
+        );
+    }
+
+    #[test]
+    fn one_answer_per_map() {
+        let input = format!("{}\n  \n\n19\n11\n", EXAMPLE.trim());
+        assert_eq!(maps(&input).len(), 2);
+        assert_eq!(solve_part1(&input).unwrap(), Answers(vec![40, 2]));
+        assert_eq!(solve_part2(&input).unwrap(), Answers(vec![315, 86]));
+        assert_eq!(
+            render_part1_route(&input, Format::Digits)
+                .unwrap()
+                .lines()
+                .skip_while(|line| *line != "map 1:")
+                .collect::<Vec<_>>(),
+            ["map 1:", "1.", "11"]
+        );
+    }
+
+    #[test]
+    fn weighted_format_ragged() {
+        assert!(matches!(
+            parse_weighted_map("1,2,3\n4,5"),
+            Err(Error::RaggedMap)
+        ));
+    }
+
+    /// Check that `route` is a connected walk between the corners whose risk adds up.
+    fn assert_valid_route(map: &impl RiskMap, route: &Route) {
+        assert_eq!(route.points.first(), Some(&map.top_left()));
+        assert_eq!(route.points.last(), Some(&map.bottom_right()));
+        for pair in route.points.windows(2) {
+            assert!(
+                orthogonal_adjacencies(map, pair[0]).any(|adjacent| adjacent == pair[1]),
+                "{:?} does not neighbor {:?}",
+                pair[0],
+                pair[1],
+            );
+        }
+        let risk: u64 = route.points[1..].iter().map(|&point| map.risk(point)).sum();
+        assert_eq!(risk, route.total_risk);
+    }
+
+    #[test]
+    fn example_route() {
+        let map = map_from_rows(EXAMPLE.trim().lines());
+        let route = lowest_risk_route(&map);
+        assert_eq!(route.total_risk, 40);
+        assert_valid_route(&map, &route);
+
+        let map = VirtualMap::new(&map, 5);
+        let route = lowest_risk_route(&map);
+        assert_eq!(route.total_risk, 315);
+        assert_valid_route(&map, &route);
+    }
+
+    #[test]
+    fn route_matches_dijkstra() {
+        for (seed, (width, height)) in [(1, 1), (2, 1), (7, 13), (40, 40)].into_iter().enumerate() {
+            let map = pseudorandom_map(width, height, seed as u64);
+            let route = lowest_risk_route(&map);
+            assert_eq!(
+                route.total_risk,
+                Algorithm::Bidirectional.lowest_risk_path(&map)
+            );
+            assert_valid_route(&map, &route);
+        }
+    }
+
+    #[test]
+    fn render_route() {
+        let map = map_from_rows(["19", "11"]);
+        assert_eq!(lowest_risk_route(&map).render(&map), "1.\n11");
+
+        let map = parse_weighted_map("1,300\n1,1").unwrap();
+        assert_eq!(lowest_risk_route(&map).render(&map), "  1   .\n  1   1");
+    }
+
+    #[test]
+    fn bidirectional_single_tile() {
+        let map = map_from_rows(["7"]);
+        assert_eq!(Algorithm::Bidirectional.lowest_risk_path(&map), 0);
+    }
+
+    #[test]
+    fn bidirectional_matches_dijkstra() {
+        for (seed, (width, height)) in [(1, 2), (2, 1), (7, 13), (40, 40), (63, 17), (100, 100)]
+            .into_iter()
+            .enumerate()
+        {
+            let map = pseudorandom_map(width, height, seed as u64);
+            assert_eq!(
+                Algorithm::Bidirectional.lowest_risk_path(&map),
+                Algorithm::Dijkstra.lowest_risk_path(&map),
+                "{}x{} map with seed {}",
+                width,
+                height,
+                seed,
+            );
+        }
+    }
 }
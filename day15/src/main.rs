@@ -1,18 +1,20 @@
 use aoclib::{config::Config, website::get_input};
-use day15::{part1, part2};
+use day15::{
+    render_part1_route, render_part2_route, solve_part1_with, solve_part2_with, Algorithm, Format,
+};
 
 use color_eyre::eyre::Result;
+use input_source::InputSource;
 use structopt::StructOpt;
-use std::path::PathBuf;
 
 const YEAR: u32 = 2021;
 const DAY: u8 = 15;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
+    /// input file, or "-" to read from stdin
     #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    input: Option<InputSource>,
 
     /// skip part 1
     #[structopt(long)]
@@ -21,19 +23,37 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// report the progress of long-running parts on stderr
+    #[structopt(long)]
+    progress: bool,
+
+    /// shortest-path algorithm: "dijkstra", "bidirectional", or (with the
+    /// `parallelism` feature) "delta-stepping"
+    #[structopt(long, default_value)]
+    algorithm: Algorithm,
+
+    /// input format: "digits" for one digit per position, or "weighted" for rows of
+    /// comma- or whitespace-separated integers
+    #[structopt(long, default_value)]
+    format: Format,
+
+    /// also print the map with the lowest-risk route marked
+    #[structopt(long)]
+    route: bool,
 }
 
 impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
+    fn input(&self) -> Result<InputSource> {
         match self.input {
             None => {
                 let config = Config::load()?;
                 // this does nothing if the input file already exists, but
                 // simplifies the workflow after cloning the repo on a new computer
                 get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                Ok(config.input_for(YEAR, DAY).into())
             }
-            Some(ref path) => Ok(path.clone()),
+            Some(ref source) => Ok(source.clone()),
         }
     }
 }
@@ -41,13 +61,22 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    if args.progress {
+        progress::enable();
+    }
+    let input = args.input()?.read()?;
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        println!("{}", solve_part1_with(&input, args.algorithm, args.format)?);
+        if args.route {
+            print!("{}", render_part1_route(&input, args.format)?);
+        }
     }
     if args.part2 {
-        part2(&input_path)?;
+        println!("{}", solve_part2_with(&input, args.algorithm, args.format)?);
+        if args.route {
+            print!("{}", render_part2_route(&input, args.format)?);
+        }
     }
     Ok(())
 }
@@ -0,0 +1,147 @@
+use aoclib::geometry::{Map, Point};
+
+/// A rectangular grid of risk values which the pathfinders can traverse.
+pub trait RiskMap {
+    /// The starting position.
+    fn top_left(&self) -> Point;
+
+    /// The goal position.
+    fn bottom_right(&self) -> Point;
+
+    /// `true` when `point` is within this map.
+    fn in_bounds(&self, point: Point) -> bool;
+
+    /// The risk incurred by entering `point`.
+    ///
+    /// Only valid for points which are in bounds.
+    fn risk(&self, point: Point) -> u64;
+
+    /// The greatest risk of any single position in this map.
+    fn max_risk(&self) -> u64;
+}
+
+/// Iterate over the in-bounds points orthogonally adjacent to `point`.
+pub fn orthogonal_adjacencies<M: RiskMap>(
+    map: &M,
+    point: Point,
+) -> impl '_ + Iterator<Item = Point> {
+    [(0, 1), (1, 0), (0, -1), (-1, 0)]
+        .into_iter()
+        .map(move |(dx, dy)| point + Point::new(dx, dy))
+        .filter(move |adjacent| map.in_bounds(*adjacent))
+}
+
+impl<T> RiskMap for Map<T>
+where
+    T: Copy + Into<u64>,
+{
+    fn top_left(&self) -> Point {
+        Map::top_left(self)
+    }
+
+    fn bottom_right(&self) -> Point {
+        Map::bottom_right(self)
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        Map::in_bounds(self, point)
+    }
+
+    fn risk(&self, point: Point) -> u64 {
+        self[point].into()
+    }
+
+    fn max_risk(&self) -> u64 {
+        self.iter()
+            .map(|(_, risk)| (*risk).into())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// A map made of a small map tiled repeatedly rightward and downward.
+///
+/// Each tile step rightward or downward increases the risk of every position by 1;
+/// risk above 9 wraps back around to 1.
+///
+/// Risk values are computed on demand from the small map, so the expanded map
+/// is never materialized. The top left of the virtual map coincides with the top left
+/// of the small map.
+///
+/// The wrapping rule is only meaningful when every risk in the small map is a single digit.
+pub struct VirtualMap<'a, T> {
+    small: &'a Map<T>,
+    tiles: usize,
+}
+
+impl<'a, T> Clone for VirtualMap<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for VirtualMap<'a, T> {}
+
+impl<'a, T> VirtualMap<'a, T> {
+    /// Tile `small` `tiles` times in each direction.
+    pub fn new(small: &'a Map<T>, tiles: usize) -> Self {
+        VirtualMap { small, tiles }
+    }
+
+    pub fn width(&self) -> usize {
+        self.small.width() * self.tiles
+    }
+
+    pub fn height(&self) -> usize {
+        self.small.height() * self.tiles
+    }
+
+    /// Distance of `point` rightward and downward from the top left.
+    ///
+    /// Negative for points up or left of the map.
+    fn offsets(&self, point: Point) -> (i32, i32) {
+        let top_left = self.small.top_left();
+        (point.x - top_left.x, top_left.y - point.y)
+    }
+}
+
+impl<'a, T> RiskMap for VirtualMap<'a, T>
+where
+    T: Copy + Into<u64>,
+{
+    fn top_left(&self) -> Point {
+        self.small.top_left()
+    }
+
+    fn bottom_right(&self) -> Point {
+        let top_left = RiskMap::top_left(self);
+        Point::new(
+            top_left.x + self.width() as i32 - 1,
+            top_left.y - (self.height() as i32 - 1),
+        )
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        let (dx, dy) = self.offsets(point);
+        (0..self.width() as i32).contains(&dx) && (0..self.height() as i32).contains(&dy)
+    }
+
+    fn risk(&self, point: Point) -> u64 {
+        let (dx, dy) = self.offsets(point);
+        let (width, height) = (self.small.width() as i32, self.small.height() as i32);
+        let increase = (dx / width + dy / height) as u64;
+
+        let small_top_left = self.small.top_left();
+        let small_point = Point::new(
+            small_top_left.x + dx % width,
+            small_top_left.y - dy % height,
+        );
+
+        (self.small.risk(small_point) + increase - 1) % 9 + 1
+    }
+
+    fn max_risk(&self) -> u64 {
+        let max_increase = 2 * (self.tiles as u64).saturating_sub(1);
+        (self.small.max_risk() + max_increase).min(9)
+    }
+}
@@ -0,0 +1,27 @@
+//! Decode arbitrary data as BITS transmissions, then do everything the crate can do
+//! with the packets which come out.
+#![no_main]
+
+use day16::bits::{Decoder, Packet};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parsed: Vec<_> = Packet::parse_all(data).map(Result::ok).collect();
+    let streamed: Vec<_> = Decoder::new(data).map(Result::ok).collect();
+    assert_eq!(parsed, streamed);
+
+    for packet in parsed.into_iter().flatten() {
+        let value = packet.try_value();
+        let trace = packet.value_with_trace();
+        assert_eq!(trace.map(|step| step.result), value);
+        assert_eq!(packet.validate().is_ok(), packet.violations().is_empty());
+        if let Ok(value) = value {
+            assert_eq!(packet.simplify().try_value(), Ok(value));
+        }
+        let _ = packet.to_string();
+
+        if let Ok(encoded) = packet.encode() {
+            assert_eq!(Packet::parse(&encoded).ok(), Some(packet));
+        }
+    }
+});
@@ -1,10 +1,25 @@
-use std::str::FromStr;
+use std::{
+    fmt,
+    io::{self, Read},
+    str::FromStr,
+};
 
-use bitreader::BitReader;
+use bitreader::{BitReader, BitReaderError};
 use num_enum::{FromPrimitive, IntoPrimitive};
 
+/// The type of literal values and of packet evaluation.
+///
+/// Literals are limited to 64 bits unless the `wide_literals` feature is enabled,
+/// in which case they may have up to 128. Longer literals are then decoded into
+/// [`Payload::WideLiteral`] instead of failing, but they have no value.
+#[cfg(not(feature = "wide_literals"))]
+pub type Value = u64;
+#[cfg(feature = "wide_literals")]
+pub type Value = u128;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, IntoPrimitive, FromPrimitive)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type {
     Sum = 0,
     Product = 1,
@@ -18,16 +33,219 @@ pub enum Type {
     UnknownOperator = u8::MAX,
 }
 
+impl Type {
+    /// `true` for operators whose nested applications can be flattened.
+    fn is_associative(self) -> bool {
+        matches!(
+            self,
+            Type::Sum | Type::Product | Type::Minimum | Type::Maximum
+        )
+    }
+
+    /// Apply this operator to literal operands.
+    ///
+    /// `None` when the result is not well-defined.
+    fn fold(self, values: &[Value]) -> Option<Value> {
+        let compare = |comparitor| match values {
+            [left, right] => Some((left.cmp(right) == comparitor) as Value),
+            _ => None,
+        };
+
+        match self {
+            Type::Sum => values
+                .iter()
+                .try_fold(0, |acc: Value, value| acc.checked_add(*value)),
+            Type::Product => values
+                .iter()
+                .try_fold(1, |acc: Value, value| acc.checked_mul(*value)),
+            Type::Minimum => values.iter().min().copied(),
+            Type::Maximum => values.iter().max().copied(),
+            Type::GreaterThan => compare(std::cmp::Ordering::Greater),
+            Type::LessThan => compare(std::cmp::Ordering::Less),
+            Type::EqualTo => compare(std::cmp::Ordering::Equal),
+            Type::Literal | Type::UnknownOperator => None,
+        }
+    }
+
+    /// Ensure that this operator can be applied to `count` operands.
+    pub(crate) fn check_arity(self, count: usize) -> Result<(), EvalError> {
+        match self {
+            Type::Sum | Type::Product | Type::Literal => Ok(()),
+            Type::Minimum | Type::Maximum if count == 0 => Err(EvalError::NoOperands(self)),
+            Type::Minimum | Type::Maximum => Ok(()),
+            Type::GreaterThan | Type::LessThan | Type::EqualTo if count != 2 => {
+                Err(EvalError::Arity {
+                    type_id: self,
+                    expected: 2,
+                    actual: count,
+                })
+            }
+            Type::GreaterThan | Type::LessThan | Type::EqualTo => Ok(()),
+            Type::UnknownOperator => Err(EvalError::UnknownOperator),
+        }
+    }
+}
+
+/// A source of bits, most significant first.
+trait BitSource {
+    /// Read the next `bits` bits; at most 64.
+    fn read_bits(&mut self, bits: u8) -> Result<u64, ReadError>;
+
+    /// The number of bits read so far.
+    fn position(&self) -> u64;
+}
+
+impl BitSource for BitReader<'_> {
+    fn read_bits(&mut self, bits: u8) -> Result<u64, ReadError> {
+        Ok(self.read_u64(bits)?)
+    }
+
+    fn position(&self) -> u64 {
+        BitReader::position(self)
+    }
+}
+
+/// Bits pulled from a stream on demand, either raw bytes or hex digits.
+struct StreamBits<R> {
+    inner: R,
+    hex: bool,
+    /// Hex digits read so far, for error reporting.
+    digits: usize,
+    /// The most recently read byte or hex digit.
+    current: u8,
+    /// How many of the low bits of `current` remain unread.
+    remaining: u8,
+    /// Zero bits which precede `current`, found while looking past padding.
+    pending_zeros: u64,
+    position: u64,
+}
+
+impl<R: Read> StreamBits<R> {
+    fn new(inner: R, hex: bool) -> Self {
+        StreamBits {
+            inner,
+            hex,
+            digits: 0,
+            current: 0,
+            remaining: 0,
+            pending_zeros: 0,
+            position: 0,
+        }
+    }
+
+    /// Read the next byte, or the next hex digit and its width. `None` at the end
+    /// of the stream.
+    ///
+    /// Whitespace between hex digits is skipped.
+    fn next_unit(&mut self) -> Result<Option<(u8, u8)>, ReadError> {
+        let mut byte = [0];
+        loop {
+            match self.inner.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            }
+            if !self.hex {
+                return Ok(Some((byte[0], 8)));
+            }
+            if byte[0].is_ascii_whitespace() {
+                continue;
+            }
+            let digit = (byte[0] as char).to_digit(16).ok_or_else(|| {
+                hex::FromHexError::InvalidHexCharacter {
+                    c: byte[0] as char,
+                    index: self.digits,
+                }
+            })?;
+            self.digits += 1;
+            return Ok(Some((digit as u8, 4)));
+        }
+    }
+
+    /// Ensure that at least one bit is ready to read; `false` at the end of the stream.
+    fn fill(&mut self) -> Result<bool, ReadError> {
+        if self.pending_zeros > 0 || self.remaining > 0 {
+            return Ok(true);
+        }
+        match self.next_unit()? {
+            Some((unit, width)) => {
+                self.current = unit;
+                self.remaining = width;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn read_bit(&mut self) -> Result<u64, ReadError> {
+        if !self.fill()? {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        self.position += 1;
+        if self.pending_zeros > 0 {
+            self.pending_zeros -= 1;
+            return Ok(0);
+        }
+        self.remaining -= 1;
+        Ok(((self.current >> self.remaining) & 1) as u64)
+    }
+
+    /// Skip to the next byte boundary, then look past any zero padding.
+    ///
+    /// `false` if nothing but padding remains in the stream.
+    fn skip_padding(&mut self) -> Result<bool, ReadError> {
+        while !self.position.is_multiple_of(8) {
+            if !self.fill()? {
+                return Ok(false);
+            }
+            self.read_bit()?;
+        }
+        loop {
+            let mask = (1_u16 << self.remaining) - 1;
+            if self.current as u16 & mask != 0 {
+                return Ok(true);
+            }
+            self.pending_zeros += self.remaining as u64;
+            match self.next_unit()? {
+                Some((unit, width)) => {
+                    self.current = unit;
+                    self.remaining = width;
+                }
+                None => {
+                    self.remaining = 0;
+                    return Ok(false);
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> BitSource for StreamBits<R> {
+    fn read_bits(&mut self, bits: u8) -> Result<u64, ReadError> {
+        let mut value = 0;
+        for _ in 0..bits {
+            value = (value << 1) | self.read_bit()?;
+        }
+        Ok(value)
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     pub version: u8,
     pub type_id: Type,
 }
 
 impl Header {
-    fn read(reader: &mut BitReader) -> Result<Self, Error> {
-        let version = reader.read_u8(3).map_err(Error::Header)?;
-        let type_id = reader.read_u8(3).map_err(Error::Header)?.into();
+    fn read(reader: &mut impl BitSource, budget: &Budget) -> Result<Self, Error> {
+        let version = budget.read(reader, 3, Error::Header)? as u8;
+        let type_id = (budget.read(reader, 3, Error::Header)? as u8).into();
         Ok(Header { version, type_id })
     }
 }
@@ -50,95 +268,367 @@ impl LengthType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Payload {
-    Literal(u64),
+    Literal(Value),
+    /// The 4-bit groups of a literal too wide for [`Value`], most significant first.
+    ///
+    /// Only decoded with the `wide_literals` feature.
+    WideLiteral(Vec<u8>),
     SubPackets(Vec<Packet>),
 }
 
-impl Payload {
-    fn read(type_id: Type, reader: &mut BitReader) -> Result<Self, Error> {
-        if let Type::Literal = type_id {
-            let mut is_last = false;
-            let mut filled_bits = 0;
+/// Resource limits enforced while parsing.
+///
+/// These bound the work a hostile transmission can cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum nesting depth; the outermost packet has depth 0.
+    pub max_depth: usize,
+    /// Maximum number of packets, including the outermost packet.
+    pub max_packets: usize,
+    /// Maximum number of bits consumed by a single top-level packet.
+    pub max_bits: u64,
+}
 
-            let mut value = 0;
-            while !is_last && filled_bits <= u64::BITS {
-                let group = reader.read_u64(5).map_err(Error::LiteralGroup)?;
-                value = (value << 4) | (group & 0xf);
-                filled_bits += 4;
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 256,
+            max_packets: 1 << 16,
+            max_bits: u64::MAX,
+        }
+    }
+}
 
-                is_last = group & (1 << 4) == 0;
-            }
+impl Limits {
+    /// No limits beyond the length of the data.
+    ///
+    /// Parsing never recurses, but evaluating, encoding, or comparing deeply nested
+    /// packets may overflow the stack.
+    pub fn unlimited() -> Self {
+        Limits {
+            max_depth: usize::MAX,
+            max_packets: usize::MAX,
+            max_bits: u64::MAX,
+        }
+    }
+}
 
-            if !is_last {
-                return Err(Error::OversizeLiteral);
-            }
+/// Running totals checked against the [`Limits`] while parsing a single top-level packet.
+struct Budget {
+    limits: Limits,
+    packets: usize,
+    start: u64,
+    /// Indices of the subpackets enclosing the packet being read.
+    path: Vec<usize>,
+}
 
-            Ok(Payload::Literal(value))
-        } else {
-            let length_type: LengthType = reader.read_u8(1).map_err(Error::LengthType)?.into();
-            let target = match length_type {
-                LengthType::TotalBits => reader.read_u64(15).map_err(Error::LengthTarget)?,
-                LengthType::NumberSubPackets => reader.read_u64(11).map_err(Error::LengthTarget)?,
-            };
+impl Budget {
+    fn new(limits: Limits, reader: &impl BitSource) -> Self {
+        Budget {
+            limits,
+            packets: 0,
+            start: reader.position(),
+            path: Vec::new(),
+        }
+    }
 
-            let pre_subpacket_index = reader.position();
+    fn locate(&self, offset: u64) -> Location {
+        Location {
+            offset,
+            path: self.path.clone(),
+        }
+    }
+
+    /// Read a field of `bits` bits, reporting failure with `error`.
+    fn read(
+        &self,
+        reader: &mut impl BitSource,
+        bits: u8,
+        error: fn(Location, ReadError) -> Error,
+    ) -> Result<u64, Error> {
+        let offset = reader.position();
+        reader
+            .read_bits(bits)
+            .map_err(|err| error(self.locate(offset), err))
+    }
+
+    fn check_bits(&self, reader: &impl BitSource) -> Result<(), Error> {
+        if reader.position() - self.start > self.limits.max_bits {
+            return Err(Error::BitLimit(self.limits.max_bits));
+        }
+        Ok(())
+    }
+}
 
-            let mut subpackets = Vec::new();
-            while length_type.continue_looping(
-                reader.position() - pre_subpacket_index,
-                subpackets.len() as u64,
-                target,
-            ) {
-                subpackets.push(Packet::read(reader)?);
+impl Payload {
+    fn read_literal(reader: &mut impl BitSource, budget: &Budget) -> Result<Self, Error> {
+        let offset = reader.position();
+        let mut groups = Vec::new();
+        let mut is_last = false;
+
+        while !is_last {
+            if !cfg!(feature = "wide_literals") && groups.len() as u32 == Value::BITS / 4 {
+                return Err(Error::OversizeLiteral(budget.locate(offset)));
             }
+            let group = budget.read(reader, 5, Error::LiteralGroup)? as u8;
+            groups.push(group & 0xf);
 
-            Ok(Payload::SubPackets(subpackets))
+            is_last = group & (1 << 4) == 0;
+            budget.check_bits(reader)?;
         }
+
+        Ok(Payload::from_groups(groups))
+    }
+
+    /// Interpret the 4-bit groups of a literal, most significant first.
+    ///
+    /// Groups which do not fit into a [`Value`] are kept as they are.
+    fn from_groups(groups: Vec<u8>) -> Self {
+        if significant_groups(&groups) > (Value::BITS / 4) as usize {
+            return Payload::WideLiteral(groups);
+        }
+        Payload::Literal(
+            groups
+                .iter()
+                .fold(0, |value, &group| (value << 4) | group as Value),
+        )
     }
 
-    pub fn as_literal(&self) -> Option<u64> {
+    pub fn as_literal(&self) -> Option<Value> {
         match self {
             Payload::Literal(value) => Some(*value),
-            Payload::SubPackets(_) => None,
+            Payload::WideLiteral(_) | Payload::SubPackets(_) => None,
+        }
+    }
+
+    pub fn as_wide_literal(&self) -> Option<&[u8]> {
+        match self {
+            Payload::WideLiteral(groups) => Some(groups),
+            Payload::Literal(_) | Payload::SubPackets(_) => None,
         }
     }
 
     pub fn as_subpackets(&self) -> Option<&Vec<Packet>> {
         match self {
-            Payload::Literal(_) => None,
+            Payload::Literal(_) | Payload::WideLiteral(_) => None,
             Payload::SubPackets(ref packets) => Some(packets),
         }
     }
 }
 
+/// The number of groups of a wide literal, disregarding leading zeros.
+fn significant_groups(groups: &[u8]) -> usize {
+    groups.len() - groups.iter().take_while(|&&group| group == 0).count()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Packet {
     pub header: Header,
     pub payload: Payload,
 }
 
+/// Dropping a packet doesn't recurse either, so that deep packets can be discarded.
+impl Drop for Packet {
+    fn drop(&mut self) {
+        let mut doomed = match &mut self.payload {
+            Payload::SubPackets(subpackets) => std::mem::take(subpackets),
+            Payload::Literal(_) | Payload::WideLiteral(_) => return,
+        };
+        while let Some(mut packet) = doomed.pop() {
+            if let Payload::SubPackets(subpackets) = &mut packet.payload {
+                doomed.append(subpackets);
+            }
+        }
+    }
+}
+
+/// An operator packet whose subpackets are still being read.
+struct Frame {
+    header: Header,
+    length_type: LengthType,
+    target: u64,
+    /// Where the subpackets begin.
+    start: u64,
+    subpackets: Vec<Packet>,
+}
+
+impl Frame {
+    fn read(header: Header, reader: &mut impl BitSource, budget: &Budget) -> Result<Self, Error> {
+        let length_type: LengthType = (budget.read(reader, 1, Error::LengthType)? as u8).into();
+        let target = match length_type {
+            LengthType::TotalBits => budget.read(reader, 15, Error::LengthTarget)?,
+            LengthType::NumberSubPackets => budget.read(reader, 11, Error::LengthTarget)?,
+        };
+        Ok(Frame {
+            header,
+            length_type,
+            target,
+            start: reader.position(),
+            subpackets: Vec::new(),
+        })
+    }
+
+    fn is_complete(&self, reader: &impl BitSource) -> bool {
+        !self.length_type.continue_looping(
+            reader.position() - self.start,
+            self.subpackets.len() as u64,
+            self.target,
+        )
+    }
+
+    fn finish(self) -> Packet {
+        Packet {
+            header: self.header,
+            payload: Payload::SubPackets(self.subpackets),
+        }
+    }
+}
+
 impl Packet {
-    fn read(reader: &mut BitReader) -> Result<Self, Error> {
-        let header = Header::read(reader)?;
-        let payload = Payload::read(header.type_id, reader)?;
+    /// Read a complete packet.
+    ///
+    /// Operators whose subpackets are still being read wait on an explicit stack,
+    /// so arbitrarily deep packets cannot overflow the call stack.
+    fn read(reader: &mut impl BitSource, budget: &mut Budget) -> Result<Self, Error> {
+        let mut open: Vec<Frame> = Vec::new();
+        loop {
+            // begin the packet at `budget.path`
+            if budget.path.len() > budget.limits.max_depth {
+                return Err(Error::DepthLimit(budget.limits.max_depth));
+            }
+            budget.packets += 1;
+            if budget.packets > budget.limits.max_packets {
+                return Err(Error::PacketLimit(budget.limits.max_packets));
+            }
+
+            let header = Header::read(reader, budget)?;
+            let mut complete = if header.type_id == Type::Literal {
+                let payload = Payload::read_literal(reader, budget)?;
+                Some(Packet { header, payload })
+            } else {
+                open.push(Frame::read(header, reader, budget)?);
+                None
+            };
 
-        Ok(Packet { header, payload })
+            // hand each complete packet to its parent, until some parent wants more
+            loop {
+                let Some(frame) = open.last_mut() else {
+                    return Ok(complete.expect("the outermost packet is complete"));
+                };
+                if let Some(packet) = complete.take() {
+                    frame.subpackets.push(packet);
+                    budget.path.pop();
+                    budget.check_bits(reader)?;
+                }
+                if !frame.is_complete(reader) {
+                    budget.path.push(frame.subpackets.len());
+                    break;
+                }
+                complete = open.pop().map(Frame::finish);
+            }
+        }
     }
 
-    /// Parse a slice of data as a packet.
+    /// Read a top-level packet within the specified limits.
+    fn read_limited(reader: &mut impl BitSource, limits: Limits) -> Result<Self, Error> {
+        let mut budget = Budget::new(limits, reader);
+        Self::read(reader, &mut budget)
+    }
+
+    /// Parse a slice of data as a packet, within the default [`Limits`].
     pub fn parse(data: &[u8]) -> Result<Self, Error> {
-        Self::read(&mut BitReader::new(data))
+        Self::parse_with_limits(data, Limits::default())
+    }
+
+    /// Parse a slice of data as a packet, within the specified limits.
+    pub fn parse_with_limits(data: &[u8], limits: Limits) -> Result<Self, Error> {
+        Self::read_limited(&mut BitReader::new(data), limits)
     }
 
-    /// Parse a hex string as a packet.
+    /// Parse a hex string as a packet, within the default [`Limits`].
     pub fn parse_hex(data: &str) -> Result<Self, Error> {
         Self::parse(&hex::decode(data)?)
     }
 
+    /// Parse a hex string as a packet, within the specified limits.
+    pub fn parse_hex_with_limits(data: &str, limits: Limits) -> Result<Self, Error> {
+        Self::parse_with_limits(&hex::decode(data)?, limits)
+    }
+
+    /// Parse a string of binary digits as a packet, within the default [`Limits`].
+    ///
+    /// ```
+    /// # use day16::bits::Packet;
+    /// let packet = Packet::parse_bin("110100101111111000101000").unwrap();
+    /// assert_eq!(packet, Packet::parse_hex("D2FE28").unwrap());
+    /// ```
+    pub fn parse_bin(data: &str) -> Result<Self, Error> {
+        Self::parse(&decode_bin(data)?)
+    }
+
+    /// Parse a string of binary digits as a packet, within the specified limits.
+    pub fn parse_bin_with_limits(data: &str, limits: Limits) -> Result<Self, Error> {
+        Self::parse_with_limits(&decode_bin(data)?, limits)
+    }
+
+    /// Decode a packet from a stream of bytes, within the default [`Limits`].
+    ///
+    /// Only as much of the stream as the packet needs is read. Reads are a byte at
+    /// a time, so buffer unbuffered streams.
+    pub fn read_from(reader: impl Read) -> Result<Self, Error> {
+        Self::read_limited(&mut StreamBits::new(reader, false), Limits::default())
+    }
+
+    /// Decode a packet from a stream of hex digits, within the default [`Limits`].
+    ///
+    /// Whitespace between digits is ignored.
+    pub fn read_hex_from(reader: impl Read) -> Result<Self, Error> {
+        Self::read_limited(&mut StreamBits::new(reader, true), Limits::default())
+    }
+
+    /// Parse every top-level packet from a slice of data.
+    ///
+    /// Each packet begins on a byte boundary; the bits between the end of one packet
+    /// and the start of the next byte are padding and are skipped. Iteration ends
+    /// when the remaining data is all zero padding, or after the first error.
+    ///
+    /// Each top-level packet is parsed within the default [`Limits`]; use
+    /// [`Packets::with_limits`] to change them.
+    pub fn parse_all<D: AsRef<[u8]>>(data: D) -> Packets<D> {
+        Packets {
+            data,
+            position: 0,
+            done: false,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Parse every top-level packet from a hex string.
+    pub fn parse_all_hex(data: &str) -> Result<Packets<Vec<u8>>, Error> {
+        Ok(Self::parse_all(hex::decode(data)?))
+    }
+
+    /// Parse every top-level packet from a string of binary digits.
+    pub fn parse_all_bin(data: &str) -> Result<Packets<Vec<u8>>, Error> {
+        Ok(Self::parse_all(decode_bin(data)?))
+    }
+
+    /// Iterate over this packet and all its descendants in pre-order.
+    ///
+    /// Traversal uses an explicit stack, so arbitrarily deep packets cannot overflow
+    /// the call stack.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { stack: vec![self] }
+    }
+
     /// Compute the value of the packet.
-    pub fn value(&self) -> u64 {
-        fn subpacket_values<'a>(packet: &'a Packet) -> impl 'a + Iterator<Item = u64> {
+    ///
+    /// Panics on a [`Payload::WideLiteral`]; see [`Packet::try_value`].
+    pub fn value(&self) -> Value {
+        fn subpacket_values<'a>(packet: &'a Packet) -> impl 'a + Iterator<Item = Value> {
             packet
                 .payload
                 .as_subpackets()
@@ -147,13 +637,13 @@ impl Packet {
                 .map(|packet| packet.value())
         }
 
-        fn compare_two(packet: &Packet, comparitor: std::cmp::Ordering) -> u64 {
+        fn compare_two(packet: &Packet, comparitor: std::cmp::Ordering) -> Value {
             let subpackets = packet.payload.as_subpackets().unwrap();
             if subpackets.len() != 2 {
-                eprintln!(
-                    "WARN: {:?} packet had {} subpackets; expected 2",
-                    packet.header.type_id,
-                    subpackets.len()
+                tracing::warn!(
+                    type_id = ?packet.header.type_id,
+                    subpackets = subpackets.len(),
+                    "comparison packet should have 2 subpackets"
                 );
                 return 0;
             }
@@ -165,7 +655,10 @@ impl Packet {
         }
 
         match self.header.type_id {
-            Type::Literal => self.payload.as_literal().unwrap(),
+            Type::Literal => self
+                .payload
+                .as_literal()
+                .expect("literal does not fit into Value"),
             Type::Sum => subpacket_values(self).sum(),
             Type::Product => subpacket_values(self).product(),
             Type::Minimum => subpacket_values(self).min().unwrap_or_default(),
@@ -176,6 +669,597 @@ impl Packet {
             Type::UnknownOperator => panic!("unknown operator has no value"),
         }
     }
+
+    /// Ensure that every packet in this tree is well-formed: literal payloads belong to
+    /// literal packets, and each operator has an acceptable number of subpackets.
+    ///
+    /// This does not detect arithmetic overflow; see [`Packet::try_value`]. To find every
+    /// malformed packet rather than the first, see [`Packet::violations`].
+    pub fn validate(&self) -> Result<(), EvalError> {
+        self.iter().try_for_each(Packet::check)
+    }
+
+    /// Find every malformed packet in this tree, in pre-order.
+    ///
+    /// Each violation is checked as for [`Packet::validate`].
+    pub fn violations(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut stack = vec![(Vec::new(), self)];
+        while let Some((path, packet)) = stack.pop() {
+            if let Err(error) = packet.check() {
+                violations.push(Violation {
+                    path: path.clone(),
+                    error,
+                });
+            }
+            if let Payload::SubPackets(subpackets) = &packet.payload {
+                for (idx, subpacket) in subpackets.iter().enumerate().rev() {
+                    let mut path = path.clone();
+                    path.push(idx);
+                    stack.push((path, subpacket));
+                }
+            }
+        }
+        violations
+    }
+
+    /// Ensure that this packet, disregarding its subpackets, is well-formed.
+    fn check(&self) -> Result<(), EvalError> {
+        match (&self.payload, self.header.type_id) {
+            (Payload::Literal(_) | Payload::WideLiteral(_), Type::Literal) => Ok(()),
+            (Payload::SubPackets(subpackets), type_id) if type_id != Type::Literal => {
+                type_id.check_arity(subpackets.len())
+            }
+            (_, type_id) => Err(EvalError::MismatchedPayload(type_id)),
+        }
+    }
+
+    /// Compute the value of the packet, reporting malformed packets and overflow as errors.
+    pub fn try_value(&self) -> Result<Value, EvalError> {
+        match (&self.payload, self.header.type_id) {
+            (Payload::Literal(value), Type::Literal) => Ok(*value),
+            (Payload::WideLiteral(groups), Type::Literal) => {
+                Err(EvalError::OversizeLiteral(significant_groups(groups)))
+            }
+            (Payload::SubPackets(subpackets), type_id) if type_id != Type::Literal => {
+                type_id.check_arity(subpackets.len())?;
+                let values = subpackets
+                    .iter()
+                    .map(Packet::try_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                type_id.fold(&values).ok_or(EvalError::Overflow(type_id))
+            }
+            (_, type_id) => Err(EvalError::MismatchedPayload(type_id)),
+        }
+    }
+
+    /// Compute the value of the packet like [`Packet::try_value`], recording how each
+    /// operator was applied.
+    pub fn value_with_trace(&self) -> Result<Step, EvalError> {
+        match (&self.payload, self.header.type_id) {
+            (Payload::Literal(value), Type::Literal) => Ok(Step {
+                type_id: Type::Literal,
+                operands: Vec::new(),
+                result: *value,
+            }),
+            (Payload::WideLiteral(groups), Type::Literal) => {
+                Err(EvalError::OversizeLiteral(significant_groups(groups)))
+            }
+            (Payload::SubPackets(subpackets), type_id) if type_id != Type::Literal => {
+                type_id.check_arity(subpackets.len())?;
+                let operands = subpackets
+                    .iter()
+                    .map(Packet::value_with_trace)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let values: Vec<Value> = operands.iter().map(|operand| operand.result).collect();
+                let result = type_id.fold(&values).ok_or(EvalError::Overflow(type_id))?;
+                Ok(Step {
+                    type_id,
+                    operands,
+                    result,
+                })
+            }
+            (_, type_id) => Err(EvalError::MismatchedPayload(type_id)),
+        }
+    }
+
+    /// Produce an equivalent packet with constant subtrees evaluated and trivial operators
+    /// collapsed.
+    ///
+    /// - Operators whose subpackets all simplify to literals are replaced by a literal
+    ///   of the same version.
+    /// - Nested sums, products, minimums, and maximums are flattened into their parent,
+    ///   and their literal operands are combined.
+    /// - A sum, product, minimum, or maximum of a single subpacket is replaced by that subpacket.
+    ///
+    /// Subtrees without a well-defined value, such as comparisons which do not have exactly
+    /// two subpackets, empty minimums and maximums, unknown operators, and arithmetic which
+    /// overflows, are left in place.
+    pub fn simplify(&self) -> Packet {
+        let subpackets = match &self.payload {
+            Payload::Literal(_) | Payload::WideLiteral(_) => return self.clone(),
+            Payload::SubPackets(subpackets) => subpackets,
+        };
+        let type_id = self.header.type_id;
+        let associative = type_id.is_associative();
+
+        let mut simplified = Vec::with_capacity(subpackets.len());
+        for mut subpacket in subpackets.iter().map(Packet::simplify) {
+            match &mut subpacket.payload {
+                // an empty minimum or maximum has no value, so its parent mustn't gain one
+                Payload::SubPackets(nested)
+                    if associative
+                        && subpacket.header.type_id == type_id
+                        && type_id.check_arity(nested.len()).is_ok() =>
+                {
+                    simplified.append(nested)
+                }
+                _ => simplified.push(subpacket),
+            }
+        }
+
+        let (literals, mut operators): (Vec<_>, Vec<_>) = simplified
+            .into_iter()
+            .partition(|subpacket| subpacket.payload.as_literal().is_some());
+        let values: Vec<Value> = literals
+            .iter()
+            .filter_map(|literal| literal.payload.as_literal())
+            .collect();
+
+        let literal = |value| Packet {
+            header: Header {
+                version: self.header.version,
+                type_id: Type::Literal,
+            },
+            payload: Payload::Literal(value),
+        };
+
+        if operators.is_empty() {
+            if let Some(value) = type_id.fold(&values) {
+                return literal(value);
+            }
+        }
+
+        let mut subpackets = literals;
+        if associative && subpackets.len() > 1 {
+            if let Some(value) = type_id.fold(&values) {
+                subpackets = vec![literal(value)];
+            }
+        }
+        subpackets.append(&mut operators);
+
+        if associative && subpackets.len() == 1 {
+            return subpackets.pop().expect("length checked");
+        }
+
+        Packet {
+            header: self.header,
+            payload: Payload::SubPackets(subpackets),
+        }
+    }
+}
+
+/// One step in the evaluation of a packet.
+///
+/// Literals are steps without operands. Created by [`Packet::value_with_trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step {
+    pub type_id: Type,
+    /// The evaluation of each subpacket, in order.
+    pub operands: Vec<Step>,
+    pub result: Value,
+}
+
+/// An indented listing of the evaluation, one step per line, operators before their operands.
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(0, self)];
+        while let Some((depth, step)) = stack.pop() {
+            write!(f, "{:indent$}", "", indent = 2 * depth)?;
+            if step.type_id == Type::Literal {
+                writeln!(f, "{}", step.result)?;
+                continue;
+            }
+            write!(f, "{:?}(", step.type_id)?;
+            for (idx, operand) in step.operands.iter().enumerate() {
+                let separator = if idx == 0 { "" } else { ", " };
+                write!(f, "{}{}", separator, operand.result)?;
+            }
+            writeln!(f, ") = {}", step.result)?;
+            stack.extend(
+                step.operands
+                    .iter()
+                    .rev()
+                    .map(|operand| (depth + 1, operand)),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Pre-order iterator over a packet tree.
+///
+/// Created by [`Packet::iter`].
+pub struct Iter<'a> {
+    stack: Vec<&'a Packet>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Packet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let packet = self.stack.pop()?;
+        if let Payload::SubPackets(subpackets) = &packet.payload {
+            self.stack.extend(subpackets.iter().rev());
+        }
+        Some(packet)
+    }
+}
+
+impl<'a> IntoIterator for &'a Packet {
+    type Item = &'a Packet;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over concatenated top-level packets.
+///
+/// Created by [`Packet::parse_all`].
+pub struct Packets<D> {
+    data: D,
+    /// bit offset of the next packet
+    position: u64,
+    done: bool,
+    limits: Limits,
+}
+
+impl<D> Packets<D> {
+    /// Parse each top-level packet within the specified limits.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl<D: AsRef<[u8]>> Iterator for Packets<D> {
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.data.as_ref();
+        let byte_offset = (self.position / 8) as usize;
+        if self.done
+            || data[byte_offset.min(data.len())..]
+                .iter()
+                .all(|&byte| byte == 0)
+        {
+            self.done = true;
+            return None;
+        }
+
+        let mut reader = BitReader::new(data);
+        let packet = reader
+            .skip(self.position)
+            .map_err(|err| Error::Header(Location::top_level(self.position), err.into()))
+            .and_then(|_| Packet::read_limited(&mut reader, self.limits));
+
+        match packet {
+            Ok(_) => {
+                // round up to the next byte boundary
+                self.position = reader.position().div_ceil(8) * 8;
+            }
+            Err(_) => self.done = true,
+        }
+        Some(packet)
+    }
+}
+
+/// Iterator decoding concatenated top-level packets from a stream.
+///
+/// Like [`Packets`], each packet begins on a byte boundary, and iteration ends when
+/// the rest of the stream is zero padding, or after the first error. Each packet is
+/// yielded as soon as its last bit has been read.
+pub struct Decoder<R> {
+    bits: StreamBits<R>,
+    done: bool,
+    limits: Limits,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Decode packets from a stream of bytes.
+    pub fn new(reader: R) -> Self {
+        Self::with_bits(StreamBits::new(reader, false))
+    }
+
+    /// Decode packets from a stream of hex digits; whitespace between them is ignored.
+    pub fn hex(reader: R) -> Self {
+        Self::with_bits(StreamBits::new(reader, true))
+    }
+
+    fn with_bits(bits: StreamBits<R>) -> Self {
+        Decoder {
+            bits,
+            done: false,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Decode each top-level packet within the specified limits.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let packet = match self.bits.skip_padding() {
+            Ok(true) => Packet::read_limited(&mut self.bits, self.limits),
+            Ok(false) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => Err(Error::Header(
+                Location::top_level(self.bits.position()),
+                err,
+            )),
+        };
+        self.done = packet.is_err();
+        Some(packet)
+    }
+}
+
+/// Programmatic construction.
+///
+/// Each constructor produces a packet of version 0; chain [`Packet::version`] to change it,
+/// or number the whole tree at once with [`Packet::with_versions`].
+///
+/// ```
+/// # use day16::bits::Packet;
+/// let packet = Packet::sum(vec![Packet::literal(1), Packet::literal(2).version(5)]).version(3);
+/// assert_eq!(packet.value(), 3);
+/// ```
+impl Packet {
+    /// Construct a literal packet.
+    pub fn literal(value: Value) -> Self {
+        Packet {
+            header: Header {
+                version: 0,
+                type_id: Type::Literal,
+            },
+            payload: Payload::Literal(value),
+        }
+    }
+
+    /// Construct an operator packet.
+    pub fn operator(type_id: Type, subpackets: Vec<Packet>) -> Self {
+        Packet {
+            header: Header {
+                version: 0,
+                type_id,
+            },
+            payload: Payload::SubPackets(subpackets),
+        }
+    }
+
+    pub fn sum(subpackets: Vec<Packet>) -> Self {
+        Self::operator(Type::Sum, subpackets)
+    }
+
+    pub fn product(subpackets: Vec<Packet>) -> Self {
+        Self::operator(Type::Product, subpackets)
+    }
+
+    pub fn minimum(subpackets: Vec<Packet>) -> Self {
+        Self::operator(Type::Minimum, subpackets)
+    }
+
+    pub fn maximum(subpackets: Vec<Packet>) -> Self {
+        Self::operator(Type::Maximum, subpackets)
+    }
+
+    pub fn greater_than(left: Packet, right: Packet) -> Self {
+        Self::operator(Type::GreaterThan, vec![left, right])
+    }
+
+    pub fn less_than(left: Packet, right: Packet) -> Self {
+        Self::operator(Type::LessThan, vec![left, right])
+    }
+
+    pub fn equal_to(left: Packet, right: Packet) -> Self {
+        Self::operator(Type::EqualTo, vec![left, right])
+    }
+
+    /// Set the version of this packet.
+    pub fn version(mut self, version: u8) -> Self {
+        self.header.version = version;
+        self
+    }
+
+    /// Number every packet in this tree sequentially in pre-order, starting from 0.
+    ///
+    /// Versions wrap around after 7, so that they always fit in the 3-bit header field.
+    pub fn with_versions(mut self) -> Self {
+        let mut version = 0;
+        let mut stack = vec![&mut self];
+        while let Some(packet) = stack.pop() {
+            packet.header.version = version;
+            version = (version + 1) % 8;
+            if let Payload::SubPackets(subpackets) = &mut packet.payload {
+                stack.extend(subpackets.iter_mut().rev());
+            }
+        }
+        self
+    }
+}
+
+/// Accumulates a sequence of bits, most significant first.
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    /// Write the low `bits` bits of `value`.
+    fn write(&mut self, value: u64, bits: u8) {
+        for shift in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> shift) & 1 != 0 {
+                *self.bytes.last_mut().expect("pushed above") |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Write every bit written to `other`.
+    fn append(&mut self, other: &BitWriter) {
+        let mut remaining = other.len;
+        for byte in &other.bytes {
+            let bits = remaining.min(8);
+            self.write((*byte >> (8 - bits)) as u64, bits as u8);
+            remaining -= bits;
+        }
+    }
+}
+
+/// Pack a string of binary digits into bytes, padding the last byte with zeros.
+fn decode_bin(data: &str) -> Result<Vec<u8>, Error> {
+    let mut writer = BitWriter::default();
+    for (index, digit) in data.chars().enumerate() {
+        let bit = digit.to_digit(2).ok_or(Error::BinaryDecode {
+            index,
+            found: digit,
+        })?;
+        writer.write(bit as u64, 1);
+    }
+    Ok(writer.bytes)
+}
+
+impl Packet {
+    fn write(&self, writer: &mut BitWriter) -> Result<(), Error> {
+        let type_id: u8 = self.header.type_id.into();
+        if self.header.version >= 1 << 3 {
+            return Err(Error::Unencodable("version must fit in 3 bits"));
+        }
+        if type_id >= 1 << 3 {
+            return Err(Error::Unencodable("type id must fit in 3 bits"));
+        }
+
+        match (&self.payload, self.header.type_id) {
+            (Payload::Literal(value), Type::Literal) => {
+                writer.write(self.header.version as u64, 3);
+                writer.write(type_id as u64, 3);
+
+                let groups = (Value::BITS - value.leading_zeros()).div_ceil(4).max(1);
+                for group in (0..groups).rev() {
+                    let continuation = if group == 0 { 0 } else { 1 << 4 };
+                    let nibble = ((value >> (group * 4)) & 0xf) as u8;
+                    writer.write(continuation | nibble as u64, 5);
+                }
+            }
+            (Payload::WideLiteral(groups), Type::Literal) => {
+                if groups.is_empty() {
+                    return Err(Error::Unencodable("wide literal has no groups"));
+                }
+                writer.write(self.header.version as u64, 3);
+                writer.write(type_id as u64, 3);
+
+                for (idx, &nibble) in groups.iter().enumerate() {
+                    let continuation = if idx + 1 == groups.len() { 0 } else { 1 << 4 };
+                    writer.write(continuation | (nibble & 0xf) as u64, 5);
+                }
+            }
+            (Payload::SubPackets(subpackets), type_id) if type_id != Type::Literal => {
+                let mut payload = BitWriter::default();
+                for subpacket in subpackets {
+                    subpacket.write(&mut payload)?;
+                }
+
+                writer.write(self.header.version as u64, 3);
+                writer.write(u8::from(type_id) as u64, 3);
+
+                // the subpacket count is the shorter encoding, so prefer it when possible
+                if subpackets.len() < 1 << 11 {
+                    writer.write(LengthType::NumberSubPackets as u64, 1);
+                    writer.write(subpackets.len() as u64, 11);
+                } else if payload.len < 1 << 15 {
+                    writer.write(LengthType::TotalBits as u64, 1);
+                    writer.write(payload.len as u64, 15);
+                } else {
+                    return Err(Error::Unencodable("too many subpackets"));
+                }
+                writer.append(&payload);
+            }
+            _ => return Err(Error::Unencodable("payload does not match packet type")),
+        }
+
+        Ok(())
+    }
+
+    /// Encode this packet as BITS data.
+    ///
+    /// Operators are encoded with whichever length type is shorter, so the output
+    /// is not necessarily identical to the data this packet was parsed from.
+    /// Trailing bits of the final byte are zero.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = BitWriter::default();
+        self.write(&mut writer)?;
+        Ok(writer.bytes)
+    }
+
+    /// Encode this packet as an uppercase hex string.
+    pub fn encode_hex(&self) -> Result<String, Error> {
+        self.encode().map(hex::encode_upper)
+    }
+}
+
+/// An indented listing of the packet tree, one packet per line, like a disassembly.
+///
+/// ```
+/// # use day16::bits::Packet;
+/// let packet: Packet = "38006F45291200".parse().unwrap();
+/// assert_eq!(packet.to_string(), "v1 LessThan\n  v6 Literal 10\n  v2 Literal 20\n");
+/// ```
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(0, self)];
+        while let Some((depth, packet)) = stack.pop() {
+            write!(
+                f,
+                "{:indent$}v{} {:?}",
+                "",
+                packet.header.version,
+                packet.header.type_id,
+                indent = 2 * depth
+            )?;
+            match &packet.payload {
+                Payload::Literal(value) => writeln!(f, " {}", value)?,
+                Payload::WideLiteral(groups) => {
+                    write!(f, " 0x")?;
+                    for group in groups {
+                        write!(f, "{:x}", group)?;
+                    }
+                    writeln!(f)?;
+                }
+                Payload::SubPackets(subpackets) => {
+                    writeln!(f)?;
+                    stack.extend(subpackets.iter().rev().map(|sub| (depth + 1, sub)));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl FromStr for Packet {
@@ -188,23 +1272,101 @@ impl FromStr for Packet {
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("header")]
-    Header(#[source] bitreader::BitReaderError),
-    #[error("literal group")]
-    LiteralGroup(#[source] bitreader::BitReaderError),
-    #[error("length type")]
-    LengthType(#[source] bitreader::BitReaderError),
-    #[error("length target")]
-    LengthTarget(#[source] bitreader::BitReaderError),
+    #[error("header at {0}")]
+    Header(Location, #[source] ReadError),
+    #[error("literal group at {0}")]
+    LiteralGroup(Location, #[source] ReadError),
+    #[error("length type at {0}")]
+    LengthType(Location, #[source] ReadError),
+    #[error("length target at {0}")]
+    LengthTarget(Location, #[source] ReadError),
     #[error("parsing hex")]
     HexDecode(#[from] hex::FromHexError),
-    #[error("literal does not fit into u64")]
-    OversizeLiteral,
+    #[error("parsing binary: invalid digit {found:?} at {index}")]
+    BinaryDecode { index: usize, found: char },
+    #[error("literal at {0} does not fit into {}", std::any::type_name::<Value>())]
+    OversizeLiteral(Location),
+    #[error("packets nested deeper than {0}")]
+    DepthLimit(usize),
+    #[error("more than {0} packets")]
+    PacketLimit(usize),
+    #[error("packet longer than {0} bits")]
+    BitLimit(u64),
+    #[error("cannot encode packet: {0}")]
+    Unencodable(&'static str),
+}
+
+/// Where decoding failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The bit offset of the field which could not be decoded, from the start of the data.
+    pub offset: u64,
+    /// The index of each subpacket on the way from the outermost packet to the one
+    /// being decoded.
+    pub path: Vec<usize>,
+}
+
+impl Location {
+    fn top_level(offset: u64) -> Self {
+        Location {
+            offset,
+            path: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bit {} (packet path {:?})", self.offset, self.path)
+    }
+}
+
+/// Why bits could not be read.
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    #[error(transparent)]
+    Bits(#[from] BitReaderError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+}
+
+/// A malformed packet, and where it is.
+///
+/// Created by [`Packet::violations`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("packet at {path:?}: {error}")]
+pub struct Violation {
+    /// The index of each subpacket on the way from the outermost packet to this one.
+    pub path: Vec<usize>,
+    pub error: EvalError,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EvalError {
+    #[error("{type_id:?} packet had {actual} subpackets; expected {expected}")]
+    Arity {
+        type_id: Type,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("{0:?} packet had no subpackets")]
+    NoOperands(Type),
+    #[error("{0:?} packet had the wrong kind of payload")]
+    MismatchedPayload(Type),
+    #[error("unknown operator has no value")]
+    UnknownOperator,
+    #[error("{0:?} packet overflowed")]
+    Overflow(Type),
+    #[error("literal of {0} hex digits does not fit into {}", std::any::type_name::<Value>())]
+    OversizeLiteral(usize),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn example_literal() {
@@ -248,4 +1410,670 @@ mod tests {
         assert_eq!(packet.header.version, 6);
         assert!(packet.payload.as_literal().is_some());
     }
+
+    #[rstest]
+    #[case("110100101111111000101000", "D2FE28")]
+    #[case(
+        "00111000000000000110111101000101001010010001001000000000",
+        "38006F45291200"
+    )]
+    #[case(
+        "11101110000000001101010000001100100000100011000001100000",
+        "EE00D40C823060"
+    )]
+    fn example_binary(#[case] bin: &str, #[case] hex: &str) {
+        assert_eq!(
+            Packet::parse_bin(bin).unwrap(),
+            Packet::parse_hex(hex).unwrap()
+        );
+    }
+
+    #[test]
+    fn binary_rejects_other_digits() {
+        assert!(matches!(
+            Packet::parse_bin("1101002"),
+            Err(Error::BinaryDecode {
+                index: 6,
+                found: '2'
+            })
+        ));
+    }
+
+    #[test]
+    fn encode_literal() {
+        let packet = Packet::parse_hex("D2FE28").unwrap();
+        assert_eq!(packet.encode_hex().unwrap(), "D2FE28");
+    }
+
+    #[test]
+    fn encode_operator_subpacket_type() {
+        let packet = Packet::parse_hex("EE00D40C823060").unwrap();
+        assert_eq!(packet.encode_hex().unwrap(), "EE00D40C823060");
+    }
+
+    #[test]
+    fn encode_prefers_shorter_length_type() {
+        // parsed with the total bits length type; re-encoded with the subpacket count
+        let packet = Packet::parse_hex("38006F45291200").unwrap();
+        let encoded = packet.encode().unwrap();
+        assert!(encoded.len() <= hex::decode("38006F45291200").unwrap().len());
+        assert_eq!(Packet::parse(&encoded).unwrap(), packet);
+    }
+
+    #[test]
+    fn encode_zero_literal() {
+        let packet = Packet::literal(0);
+        assert_eq!(Packet::parse(&packet.encode().unwrap()).unwrap(), packet);
+    }
+
+    #[test]
+    fn encode_rejects_wide_version() {
+        let packet = Packet::literal(1).version(8);
+        assert!(matches!(packet.encode(), Err(Error::Unencodable(_))));
+    }
+
+    #[rstest]
+    #[case("8A004A801A8002F478")]
+    #[case("620080001611562C8802118E34")]
+    #[case("C0015000016115A2E0802F182340")]
+    #[case("A0016C880162017C3686B18A3D4780")]
+    #[case("C200B40A82")]
+    #[case("04005AC33890")]
+    #[case("880086C3E88112")]
+    #[case("CE00C43D881120")]
+    #[case("D8005AC2A8F0")]
+    #[case("F600BC2D8F")]
+    #[case("9C005AC2F8F0")]
+    #[case("9C0141080250320F1802104A08")]
+    fn round_trip(#[case] hex: &str) {
+        let packet = Packet::parse_hex(hex).unwrap();
+        let encoded = packet.encode_hex().unwrap();
+        assert_eq!(Packet::parse_hex(&encoded).unwrap(), packet);
+        assert_eq!(Packet::parse_hex(&encoded).unwrap().value(), packet.value());
+    }
+
+    #[rstest]
+    #[case("C200B40A82")]
+    #[case("04005AC33890")]
+    #[case("880086C3E88112")]
+    #[case("CE00C43D881120")]
+    #[case("D8005AC2A8F0")]
+    #[case("F600BC2D8F")]
+    #[case("9C005AC2F8F0")]
+    #[case("9C0141080250320F1802104A08")]
+    fn simplify_examples_to_literals(#[case] hex: &str) {
+        let packet = Packet::parse_hex(hex).unwrap();
+        let simplified = packet.simplify();
+        assert_eq!(simplified.payload, Payload::Literal(packet.value()));
+        assert_eq!(simplified.header.version, packet.header.version);
+    }
+
+    #[test]
+    fn simplify_flattens_and_combines() {
+        let malformed = |version| {
+            Packet::operator(Type::GreaterThan, vec![Packet::literal(1)]).version(version)
+        };
+        let packet = Packet::sum(vec![
+            Packet::literal(3).version(2),
+            Packet::sum(vec![malformed(4), Packet::literal(4).version(5)]).version(3),
+            Packet::product(vec![Packet::literal(2).version(7), Packet::literal(5)]).version(6),
+        ])
+        .version(1);
+        assert_eq!(
+            packet.simplify(),
+            Packet::sum(vec![Packet::literal(17).version(1), malformed(4)]).version(1)
+        );
+    }
+
+    #[test]
+    fn simplify_collapses_single_subpacket() {
+        let malformed = Packet::operator(Type::EqualTo, vec![]).version(2);
+        let packet = Packet::minimum(vec![malformed.clone()]).version(1);
+        assert_eq!(packet.simplify(), malformed);
+    }
+
+    #[test]
+    fn simplify_keeps_overflow() {
+        let packet =
+            Packet::product(vec![Packet::literal(Value::MAX), Packet::literal(2)]).version(1);
+        assert_eq!(packet.simplify(), packet);
+    }
+
+    #[test]
+    fn simplify_keeps_empty_nested_extrema() {
+        for packet in [
+            Packet::minimum(vec![Packet::minimum(vec![]), Packet::literal(3)]),
+            Packet::maximum(vec![Packet::literal(3), Packet::maximum(vec![])]),
+        ] {
+            let simplified = packet.simplify();
+            assert!(simplified.payload.as_literal().is_none());
+            assert_eq!(simplified.validate(), packet.validate());
+            assert_eq!(simplified.try_value(), packet.try_value());
+        }
+    }
+
+    #[test]
+    fn builder_matches_parsed() {
+        // 1 + 3 == 2 * 2
+        let packet = Packet::parse_hex("9C0141080250320F1802104A08").unwrap();
+        let built = Packet::equal_to(
+            Packet::sum(vec![
+                Packet::literal(1).version(2),
+                Packet::literal(3).version(4),
+            ])
+            .version(2),
+            Packet::product(vec![
+                Packet::literal(2).version(0),
+                Packet::literal(2).version(2),
+            ])
+            .version(6),
+        )
+        .version(4);
+        assert_eq!(built, packet);
+        assert_eq!(Packet::parse(&built.encode().unwrap()).unwrap(), packet);
+    }
+
+    #[test]
+    fn with_versions() {
+        let packet = Packet::sum(vec![
+            Packet::product(vec![Packet::literal(1), Packet::literal(2).version(7)]),
+            Packet::literal(3),
+            Packet::maximum((4..10).map(Packet::literal).collect()),
+        ])
+        .version(5)
+        .with_versions();
+        let versions: Vec<_> = packet.iter().map(|packet| packet.header.version).collect();
+        assert_eq!(versions, [0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3]);
+        assert!(packet.encode().is_ok());
+    }
+
+    #[rstest]
+    #[case("C200B40A82")]
+    #[case("04005AC33890")]
+    #[case("880086C3E88112")]
+    #[case("CE00C43D881120")]
+    #[case("D8005AC2A8F0")]
+    #[case("F600BC2D8F")]
+    #[case("9C005AC2F8F0")]
+    #[case("9C0141080250320F1802104A08")]
+    fn try_value_examples(#[case] hex: &str) {
+        let packet = Packet::parse_hex(hex).unwrap();
+        assert_eq!(packet.validate(), Ok(()));
+        assert_eq!(packet.try_value(), Ok(packet.value()));
+    }
+
+    #[test]
+    fn trace_example() {
+        // 1 + 3 == 2 * 2
+        let packet = Packet::parse_hex("9C0141080250320F1802104A08").unwrap();
+        let trace = packet.value_with_trace().unwrap();
+        assert_eq!(trace.result, 1);
+        assert_eq!(trace.operands[0].type_id, Type::Sum);
+        assert_eq!(trace.operands[0].result, 4);
+        assert_eq!(trace.operands[1].type_id, Type::Product);
+        assert_eq!(trace.operands[1].operands[1].result, 2);
+        assert_eq!(
+            trace.to_string(),
+            "\
+EqualTo(4, 4) = 1
+  Sum(1, 3) = 4
+    1
+    3
+  Product(2, 2) = 4
+    2
+    2
+"
+        );
+    }
+
+    #[rstest]
+    #[case("C200B40A82")]
+    #[case("04005AC33890")]
+    #[case("880086C3E88112")]
+    #[case("CE00C43D881120")]
+    #[case("D8005AC2A8F0")]
+    #[case("F600BC2D8F")]
+    #[case("9C005AC2F8F0")]
+    fn trace_agrees_with_value(#[case] hex: &str) {
+        let packet = Packet::parse_hex(hex).unwrap();
+        assert_eq!(packet.value_with_trace().unwrap().result, packet.value());
+    }
+
+    #[test]
+    fn trace_reports_errors() {
+        let packet = Packet::sum(vec![Packet::literal(1), Packet::minimum(vec![])]);
+        assert_eq!(
+            packet.value_with_trace(),
+            Err(EvalError::NoOperands(Type::Minimum))
+        );
+    }
+
+    #[test]
+    fn comparison_arity() {
+        let packet = Packet::sum(vec![
+            Packet::literal(1),
+            Packet::less_than(Packet::literal(1), Packet::literal(2)),
+            Packet::operator(Type::GreaterThan, vec![Packet::literal(3)]),
+        ]);
+        let expect = EvalError::Arity {
+            type_id: Type::GreaterThan,
+            expected: 2,
+            actual: 1,
+        };
+        assert_eq!(packet.validate(), Err(expect.clone()));
+        assert_eq!(packet.try_value(), Err(expect));
+    }
+
+    #[test]
+    fn violations_lists_every_problem() {
+        let mut mismatched = Packet::literal(5);
+        mismatched.header.type_id = Type::Product;
+        let packet = Packet::sum(vec![
+            Packet::literal(1),
+            Packet::maximum(vec![]),
+            Packet::product(vec![
+                Packet::operator(Type::UnknownOperator, vec![]),
+                Packet::equal_to(Packet::literal(1), Packet::literal(1)),
+                Packet::operator(Type::LessThan, vec![mismatched]),
+            ]),
+        ]);
+        assert_eq!(
+            packet.violations(),
+            [
+                Violation {
+                    path: vec![1],
+                    error: EvalError::NoOperands(Type::Maximum),
+                },
+                Violation {
+                    path: vec![2, 0],
+                    error: EvalError::UnknownOperator,
+                },
+                Violation {
+                    path: vec![2, 2],
+                    error: EvalError::Arity {
+                        type_id: Type::LessThan,
+                        expected: 2,
+                        actual: 1,
+                    },
+                },
+                Violation {
+                    path: vec![2, 2, 0],
+                    error: EvalError::MismatchedPayload(Type::Product),
+                },
+            ]
+        );
+        assert_eq!(packet.validate(), Err(EvalError::NoOperands(Type::Maximum)));
+        assert!(Packet::parse_hex("9C0141080250320F1802104A08")
+            .unwrap()
+            .violations()
+            .is_empty());
+    }
+
+    #[test]
+    fn empty_minimum() {
+        let packet = Packet::minimum(vec![]);
+        assert_eq!(
+            packet.try_value(),
+            Err(EvalError::NoOperands(Type::Minimum))
+        );
+    }
+
+    #[test]
+    fn unknown_operator() {
+        let packet = Packet::operator(Type::UnknownOperator, vec![Packet::literal(1)]);
+        assert_eq!(packet.validate(), Err(EvalError::UnknownOperator));
+    }
+
+    #[test]
+    fn mismatched_payload() {
+        let mut packet = Packet::literal(1);
+        packet.header.type_id = Type::Sum;
+        assert_eq!(
+            packet.validate(),
+            Err(EvalError::MismatchedPayload(Type::Sum))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let packet = Packet::sum(vec![Packet::literal(Value::MAX), Packet::literal(1)]);
+        assert_eq!(packet.validate(), Ok(()));
+        assert_eq!(packet.try_value(), Err(EvalError::Overflow(Type::Sum)));
+    }
+
+    #[test]
+    fn oversize_literal() {
+        let max = Packet::literal(Value::MAX);
+        assert_eq!(Packet::parse(&max.encode().unwrap()).unwrap(), max);
+
+        let mut writer = BitWriter::default();
+        writer.write(0, 3);
+        writer.write(u8::from(Type::Literal) as u64, 3);
+        let groups = Value::BITS / 4 + 1;
+        for group in (0..groups).rev() {
+            let continuation = if group == 0 { 0 } else { 1 << 4 };
+            writer.write(continuation | 0xf, 5);
+        }
+        let packet = Packet::parse(&writer.bytes);
+        #[cfg(not(feature = "wide_literals"))]
+        assert!(matches!(packet, Err(Error::OversizeLiteral(_))));
+        #[cfg(feature = "wide_literals")]
+        assert_eq!(
+            packet.unwrap().try_value(),
+            Err(EvalError::OversizeLiteral(groups as usize))
+        );
+    }
+
+    /// A literal packet of version 0 made of `groups`.
+    #[cfg(feature = "wide_literals")]
+    fn literal_groups(groups: &[u8]) -> Vec<u8> {
+        let mut writer = BitWriter::default();
+        writer.write(0, 3);
+        writer.write(u8::from(Type::Literal) as u64, 3);
+        for (idx, &nibble) in groups.iter().enumerate() {
+            let continuation = if idx + 1 == groups.len() { 0 } else { 1 << 4 };
+            writer.write(continuation | nibble as u64, 5);
+        }
+        writer.bytes
+    }
+
+    #[cfg(feature = "wide_literals")]
+    #[test]
+    fn forty_group_literal() {
+        let groups: Vec<u8> = (0..40).map(|idx| (idx % 15 + 1) as u8).collect();
+        let packet = Packet::parse(&literal_groups(&groups)).unwrap();
+        assert_eq!(packet.payload.as_wide_literal(), Some(&groups[..]));
+        assert_eq!(packet.validate(), Ok(()));
+        assert_eq!(packet.try_value(), Err(EvalError::OversizeLiteral(40)));
+        assert_eq!(
+            packet.value_with_trace(),
+            Err(EvalError::OversizeLiteral(40))
+        );
+        assert_eq!(packet.simplify(), packet);
+        assert_eq!(Packet::parse(&packet.encode().unwrap()).unwrap(), packet);
+        assert_eq!(
+            packet.to_string(),
+            "v0 Literal 0x123456789abcdef123456789abcdef123456789a\n"
+        );
+
+        let sum = Packet::sum(vec![packet, Packet::literal(1)]);
+        assert_eq!(sum.try_value(), Err(EvalError::OversizeLiteral(40)));
+    }
+
+    #[cfg(feature = "wide_literals")]
+    #[test]
+    fn leading_zeros_fit() {
+        let mut groups = vec![0; 40];
+        groups[39] = 7;
+        let packet = Packet::parse(&literal_groups(&groups)).unwrap();
+        assert_eq!(packet.payload, Payload::Literal(7));
+    }
+
+    #[cfg(feature = "wide_literals")]
+    #[test]
+    fn wide_literal() {
+        let packet = Packet::sum(vec![Packet::literal(1 << 100), Packet::literal(1)]);
+        let packet = Packet::parse(&packet.encode().unwrap()).unwrap();
+        assert_eq!(packet.try_value(), Ok((1 << 100) + 1));
+    }
+
+    #[test]
+    fn iter_pre_order() {
+        let packet = Packet::parse_hex("8A004A801A8002F478").unwrap();
+        let versions: Vec<_> = packet.iter().map(|packet| packet.header.version).collect();
+        assert_eq!(versions, [4, 1, 5, 6]);
+
+        let packet = Packet::sum(vec![
+            Packet::product(vec![Packet::literal(1), Packet::literal(2)]),
+            Packet::literal(3),
+            Packet::maximum(vec![Packet::literal(4)]),
+        ]);
+        let visited: Vec<_> = packet
+            .iter()
+            .map(|packet| (packet.header.type_id, packet.payload.as_literal()))
+            .collect();
+        assert_eq!(
+            visited,
+            [
+                (Type::Sum, None),
+                (Type::Product, None),
+                (Type::Literal, Some(1)),
+                (Type::Literal, Some(2)),
+                (Type::Literal, Some(3)),
+                (Type::Maximum, None),
+                (Type::Literal, Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_all_concatenated() {
+        let transmissions = ["D2FE28", "38006F45291200", "EE00D40C823060"];
+        let concatenated = format!("{}0000", transmissions.concat());
+        let packets = Packet::parse_all_hex(&concatenated)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expect = transmissions
+            .iter()
+            .map(|hex| Packet::parse_hex(hex).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(packets, expect);
+    }
+
+    #[test]
+    fn parse_all_stops_after_error() {
+        // a literal whose last group has a continuation bit
+        let mut packets = Packet::parse_all([0b0001_0011, 0b1111_1111]);
+        assert!(matches!(packets.next(), Some(Err(Error::LiteralGroup(..)))));
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn errors_are_located() {
+        let packet = Packet::sum(vec![
+            Packet::literal(1),
+            Packet::product(vec![Packet::literal(2), Packet::literal(3)]),
+        ]);
+        let mut data = packet.encode().unwrap();
+        // drop the final literal group, which begins at bit 64
+        data.truncate(8);
+        let location = match Packet::parse(&data) {
+            Err(Error::LiteralGroup(location, _)) => location,
+            other => panic!("expected a literal group error; got {:?}", other),
+        };
+        assert_eq!(
+            location,
+            Location {
+                offset: 64,
+                path: vec![1, 1]
+            }
+        );
+        assert_eq!(location.to_string(), "bit 64 (packet path [1, 1])");
+
+        // offsets count from the start of the data, not of the second transmission
+        let data = [hex::decode("D2FE28").unwrap(), vec![0b0010_0000]].concat();
+        let results: Vec<_> = Packet::parse_all(data).collect();
+        assert!(matches!(
+            &results[1],
+            Err(Error::LengthTarget(Location { offset: 31, path }, _)) if path.is_empty()
+        ));
+    }
+
+    #[test]
+    fn parse_all_empty() {
+        assert!(Packet::parse_all([]).next().is_none());
+        assert!(Packet::parse_all([0, 0, 0]).next().is_none());
+    }
+
+    fn nested(depth: usize) -> Packet {
+        let mut packet = Packet::literal(1);
+        for _ in 0..depth {
+            packet = Packet::sum(vec![packet]);
+        }
+        packet
+    }
+
+    #[test]
+    fn depth_limit() {
+        let limits = Limits {
+            max_depth: 3,
+            ..Limits::default()
+        };
+        let ok = nested(3).encode().unwrap();
+        assert_eq!(Packet::parse_with_limits(&ok, limits).unwrap(), nested(3));
+        let too_deep = nested(4).encode().unwrap();
+        assert!(matches!(
+            Packet::parse_with_limits(&too_deep, limits),
+            Err(Error::DepthLimit(3))
+        ));
+    }
+
+    #[test]
+    fn packet_limit() {
+        let limits = Limits {
+            max_packets: 3,
+            ..Limits::default()
+        };
+        let packet = Packet::sum(vec![Packet::literal(1), Packet::literal(2)]);
+        let data = packet.encode().unwrap();
+        assert_eq!(Packet::parse_with_limits(&data, limits).unwrap(), packet);
+
+        let packet = Packet::sum(vec![Packet::literal(1); 3]);
+        let data = packet.encode().unwrap();
+        assert!(matches!(
+            Packet::parse_with_limits(&data, limits),
+            Err(Error::PacketLimit(3))
+        ));
+    }
+
+    #[test]
+    fn pathologically_deep() {
+        const DEPTH: usize = 100_000;
+        let mut writer = BitWriter::default();
+        for _ in 0..DEPTH {
+            writer.write(0, 3);
+            writer.write(u8::from(Type::Sum) as u64, 3);
+            writer.write(LengthType::NumberSubPackets as u64, 1);
+            writer.write(1, 11);
+        }
+        writer.write(0, 3);
+        writer.write(u8::from(Type::Literal) as u64, 3);
+        writer.write(7, 5);
+
+        assert!(matches!(
+            Packet::parse(&writer.bytes),
+            Err(Error::DepthLimit(_))
+        ));
+        let packet = Packet::parse_with_limits(&writer.bytes, Limits::unlimited()).unwrap();
+        assert_eq!(packet.iter().count(), DEPTH + 1);
+        assert_eq!(packet.iter().last().unwrap().payload, Payload::Literal(7));
+    }
+
+    #[test]
+    fn bit_limit() {
+        // 6 header bits + 3 literal groups
+        let data = hex::decode("D2FE28").unwrap();
+        let limits = |max_bits| Limits {
+            max_bits,
+            ..Limits::default()
+        };
+        assert!(Packet::parse_with_limits(&data, limits(21)).is_ok());
+        assert!(matches!(
+            Packet::parse_with_limits(&data, limits(20)),
+            Err(Error::BitLimit(20))
+        ));
+    }
+
+    #[test]
+    fn parse_all_with_limits() {
+        let transmissions = [nested(2), nested(3), nested(2)]
+            .iter()
+            .map(|packet| packet.encode_hex().unwrap())
+            .collect::<String>();
+        let limits = Limits {
+            max_depth: 2,
+            ..Limits::default()
+        };
+        let results: Vec<_> = Packet::parse_all_hex(&transmissions)
+            .unwrap()
+            .with_limits(limits)
+            .collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(Error::DepthLimit(2))));
+    }
+
+    #[test]
+    fn read_from_stream() {
+        let hex = "9C0141080250320F1802104A08";
+        let expect = Packet::parse_hex(hex).unwrap();
+        let data = hex::decode(hex).unwrap();
+        assert_eq!(Packet::read_from(data.as_slice()).unwrap(), expect);
+        assert_eq!(Packet::read_hex_from(hex.as_bytes()).unwrap(), expect);
+        assert!(matches!(
+            Packet::read_hex_from(&hex.as_bytes()[..10]),
+            Err(Error::Header(_, ReadError::Io(_)))
+        ));
+        assert!(matches!(
+            Packet::read_hex_from("9G".as_bytes()),
+            Err(Error::Header(_, ReadError::Hex(_)))
+        ));
+    }
+
+    #[test]
+    fn decoder_matches_parse_all() {
+        let transmissions = ["D2FE28", "38006F45291200", "EE00D40C823060"];
+        let concatenated = format!("{}0000", transmissions.concat());
+        let expect = Packet::parse_all_hex(&concatenated)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(expect.len(), 3);
+
+        let data = hex::decode(&concatenated).unwrap();
+        let packets = Decoder::new(data.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(packets, expect);
+
+        let lines = transmissions.join("\n");
+        let packets = Decoder::hex(lines.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(packets, expect);
+    }
+
+    #[test]
+    fn decoder_stops_after_error() {
+        let mut packets = Decoder::new([0b0001_0011, 0b1111_1111].as_slice());
+        assert!(matches!(packets.next(), Some(Err(Error::LiteralGroup(..)))));
+        assert!(packets.next().is_none());
+        assert!(Decoder::new([0, 0, 0].as_slice()).next().is_none());
+    }
+
+    #[test]
+    fn display_nested() {
+        let packet = Packet::parse_hex("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(
+            packet.to_string(),
+            "\
+v4 EqualTo
+  v2 Sum
+    v2 Literal 1
+    v4 Literal 3
+  v6 Product
+    v0 Literal 2
+    v2 Literal 2
+"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let packet = Packet::parse_hex("9C0141080250320F1802104A08").unwrap();
+        let json = serde_json::to_string(&packet).unwrap();
+        assert_eq!(serde_json::from_str::<Packet>(&json).unwrap(), packet);
+    }
 }
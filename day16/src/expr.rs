@@ -0,0 +1,239 @@
+//! Packets as arithmetic expressions.
+
+use crate::bits::{EvalError, Packet, Payload, Type, Value};
+use std::fmt;
+
+/// The expression a packet evaluates, without versions or encoding details.
+///
+/// Displays in infix notation:
+///
+/// ```
+/// # use day16::{bits::Packet, expr::Expr};
+/// let packet = Packet::product(vec![
+///     Packet::sum(vec![Packet::literal(1), Packet::literal(2)]),
+///     Packet::maximum(vec![Packet::literal(3), Packet::literal(4)]),
+/// ]);
+/// let expr = Expr::try_from(&packet).unwrap();
+/// assert_eq!(expr.to_string(), "(1 + 2) * max(3, 4)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Literal(Value),
+    Sum(Vec<Expr>),
+    Product(Vec<Expr>),
+    Minimum(Vec<Expr>),
+    Maximum(Vec<Expr>),
+    GreaterThan(Box<Expr>, Box<Expr>),
+    LessThan(Box<Expr>, Box<Expr>),
+    EqualTo(Box<Expr>, Box<Expr>),
+}
+
+/// Packets which cannot be evaluated have no expression; see [`Packet::validate`].
+impl TryFrom<&Packet> for Expr {
+    type Error = EvalError;
+
+    fn try_from(packet: &Packet) -> Result<Self, Self::Error> {
+        let type_id = packet.header.type_id;
+        let subpackets = match (&packet.payload, type_id) {
+            (Payload::Literal(value), Type::Literal) => return Ok(Expr::Literal(*value)),
+            (Payload::WideLiteral(_), Type::Literal) => {
+                return packet.try_value().map(Expr::Literal)
+            }
+            (Payload::SubPackets(subpackets), type_id) if type_id != Type::Literal => subpackets,
+            _ => return Err(EvalError::MismatchedPayload(type_id)),
+        };
+        type_id.check_arity(subpackets.len())?;
+        let mut operands = subpackets
+            .iter()
+            .map(Expr::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut pair = || {
+            let right = Box::new(operands.pop().expect("arity checked"));
+            let left = Box::new(operands.pop().expect("arity checked"));
+            (left, right)
+        };
+        Ok(match type_id {
+            Type::Sum => Expr::Sum(operands),
+            Type::Product => Expr::Product(operands),
+            Type::Minimum => Expr::Minimum(operands),
+            Type::Maximum => Expr::Maximum(operands),
+            Type::GreaterThan => {
+                let (left, right) = pair();
+                Expr::GreaterThan(left, right)
+            }
+            Type::LessThan => {
+                let (left, right) = pair();
+                Expr::LessThan(left, right)
+            }
+            Type::EqualTo => {
+                let (left, right) = pair();
+                Expr::EqualTo(left, right)
+            }
+            Type::Literal | Type::UnknownOperator => unreachable!("arity checked"),
+        })
+    }
+}
+
+impl Expr {
+    /// How tightly this expression binds when displayed: operands which bind less
+    /// tightly than their operator are parenthesized.
+    ///
+    /// An empty sum or product displays as its identity, and a sum or product of a
+    /// single operand as that operand.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::GreaterThan(..) | Expr::LessThan(..) | Expr::EqualTo(..) => 0,
+            Expr::Sum(operands) | Expr::Product(operands) if operands.len() == 1 => {
+                operands[0].precedence()
+            }
+            Expr::Sum(operands) if operands.len() > 1 => 1,
+            Expr::Product(operands) if operands.len() > 1 => 2,
+            _ => 3,
+        }
+    }
+
+    fn write_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+
+    fn write_infix(
+        f: &mut fmt::Formatter<'_>,
+        operands: &[Expr],
+        operator: &str,
+        identity: Value,
+        precedence: u8,
+    ) -> fmt::Result {
+        match operands {
+            [] => return write!(f, "{}", identity),
+            [operand] => return write!(f, "{}", operand),
+            _ => {}
+        }
+        for (idx, operand) in operands.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " {} ", operator)?;
+            }
+            operand.write_operand(f, precedence)?;
+        }
+        Ok(())
+    }
+
+    fn write_call(f: &mut fmt::Formatter<'_>, name: &str, operands: &[Expr]) -> fmt::Result {
+        write!(f, "{}(", name)?;
+        for (idx, operand) in operands.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", operand)?;
+        }
+        write!(f, ")")
+    }
+
+    fn write_comparison(
+        f: &mut fmt::Formatter<'_>,
+        left: &Expr,
+        operator: &str,
+        right: &Expr,
+    ) -> fmt::Result {
+        left.write_operand(f, 1)?;
+        write!(f, " {} ", operator)?;
+        right.write_operand(f, 1)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(value) => write!(f, "{}", value),
+            Expr::Sum(operands) => Self::write_infix(f, operands, "+", 0, 1),
+            Expr::Product(operands) => Self::write_infix(f, operands, "*", 1, 2),
+            Expr::Minimum(operands) => Self::write_call(f, "min", operands),
+            Expr::Maximum(operands) => Self::write_call(f, "max", operands),
+            Expr::GreaterThan(left, right) => Self::write_comparison(f, left, ">", right),
+            Expr::LessThan(left, right) => Self::write_comparison(f, left, "<", right),
+            Expr::EqualTo(left, right) => Self::write_comparison(f, left, "==", right),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("C200B40A82", "1 + 2")]
+    #[case("04005AC33890", "6 * 9")]
+    #[case("880086C3E88112", "min(7, 8, 9)")]
+    #[case("CE00C43D881120", "max(7, 8, 9)")]
+    #[case("D8005AC2A8F0", "5 < 15")]
+    #[case("F600BC2D8F", "5 > 15")]
+    #[case("9C005AC2F8F0", "5 == 15")]
+    #[case("9C0141080250320F1802104A08", "1 + 3 == 2 * 2")]
+    fn examples(#[case] hex: &str, #[case] expect: &str) {
+        let packet = Packet::parse_hex(hex).unwrap();
+        assert_eq!(Expr::try_from(&packet).unwrap().to_string(), expect);
+    }
+
+    #[test]
+    fn parenthesizes_looser_operands() {
+        let packet = Packet::sum(vec![
+            Packet::product(vec![
+                Packet::sum(vec![Packet::literal(1), Packet::literal(2)]),
+                Packet::literal(3),
+            ]),
+            Packet::less_than(
+                Packet::literal(4),
+                Packet::greater_than(Packet::literal(5), Packet::literal(6)),
+            ),
+            Packet::minimum(vec![Packet::sum(vec![
+                Packet::literal(7),
+                Packet::literal(8),
+            ])]),
+        ]);
+        assert_eq!(
+            Expr::try_from(&packet).unwrap().to_string(),
+            "(1 + 2) * 3 + (4 < (5 > 6)) + min(7 + 8)"
+        );
+    }
+
+    #[test]
+    fn degenerate_sums_and_products() {
+        let packet = Packet::product(vec![
+            Packet::sum(vec![]),
+            Packet::sum(vec![Packet::product(vec![
+                Packet::literal(2),
+                Packet::literal(3),
+            ])]),
+            Packet::product(vec![Packet::sum(vec![
+                Packet::literal(4),
+                Packet::literal(5),
+            ])]),
+            Packet::product(vec![]),
+        ]);
+        assert_eq!(
+            Expr::try_from(&packet).unwrap().to_string(),
+            "0 * 2 * 3 * (4 + 5) * 1"
+        );
+    }
+
+    #[test]
+    fn malformed_packets_have_no_expression() {
+        let packet = Packet::sum(vec![Packet::operator(
+            Type::EqualTo,
+            vec![Packet::literal(1)],
+        )]);
+        assert_eq!(
+            Expr::try_from(&packet),
+            Err(EvalError::Arity {
+                type_id: Type::EqualTo,
+                expected: 2,
+                actual: 1,
+            })
+        );
+    }
+}
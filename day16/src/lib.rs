@@ -1,38 +1,130 @@
 pub mod bits;
+pub mod expr;
 
-use bits::{Packet, Payload};
+use answer::Answers;
+use bits::{Packet, Value};
+use expr::Expr;
 use std::path::Path;
 
 fn sum_versions(packet: &Packet) -> u64 {
-    let mut sum = packet.header.version as u64;
+    packet
+        .iter()
+        .map(|packet| packet.header.version as u64)
+        .sum()
+}
 
-    if let Payload::SubPackets(ref subpackets) = packet.payload {
-        sum += subpackets.iter().map(sum_versions).sum::<u64>();
+/// Parse every packet in the input.
+///
+/// Each line of the input is a transmission containing one or more packets. If the
+/// input contains only the digits 0 and 1, every transmission is in binary; otherwise,
+/// every transmission is in hex.
+fn parse_packets(data: &str) -> Result<Vec<Packet>, Error> {
+    let binary = data
+        .chars()
+        .all(|ch| ch.is_whitespace() || ch == '0' || ch == '1');
+    let mut packets = Vec::new();
+    for line in data.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let transmission = if binary {
+            Packet::parse_all_bin(line)?
+        } else {
+            Packet::parse_all_hex(line)?
+        };
+        for packet in transmission {
+            packets.push(packet?);
+        }
     }
+    Ok(packets)
+}
+
+pub fn solve_part1(input: &str) -> Result<Answers<u64>, Error> {
+    Ok(parse_packets(input)?.iter().map(sum_versions).collect())
+}
+
+pub fn solve_part2(input: &str) -> Result<Answers<Value>, Error> {
+    parse_packets(input)?
+        .iter()
+        .map(|packet| packet.try_value().map_err(Into::into))
+        .collect()
+}
+
+pub fn part1(input: &Path) -> Result<Answers<u64>, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn part2(input: &Path) -> Result<Answers<Value>, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
 
-    sum
+/// Render every packet in `input` as an indented tree.
+pub fn render_tree(input: &str) -> Result<String, Error> {
+    Ok(parse_packets(input)?
+        .iter()
+        .map(Packet::to_string)
+        .collect())
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let data = std::fs::read_to_string(input)?;
-    let packet = Packet::parse_hex(data.trim())?;
-    println!("version sum: {}", sum_versions(&packet));
-    Ok(())
+/// Render the expression each packet in `input` evaluates in infix notation, one per line.
+pub fn render_infix(input: &str) -> Result<String, Error> {
+    parse_packets(input)?
+        .iter()
+        .map(|packet| Ok(format!("{}\n", Expr::try_from(packet)?)))
+        .collect()
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let data = std::fs::read_to_string(input)?;
-    let packet = Packet::parse_hex(data.trim())?;
-    println!("packet value: {}", packet.value());
-    Ok(())
+/// Render the packets in `input` as pretty-printed JSON.
+#[cfg(feature = "serde")]
+pub fn render_json(input: &str) -> Result<String, Error> {
+    let packets = parse_packets(input)?;
+    let json = match packets.as_slice() {
+        [packet] => serde_json::to_string_pretty(packet)?,
+        packets => serde_json::to_string_pretty(packets)?,
+    };
+    Ok(json)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("parsing packet")]
-    Packet(#[from] bits::Error),
-    #[error("no solution found")]
-    NoSolution,
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("parsing packet")]
+        Packet(#[from] bits::Error),
+        #[error("evaluating packet")]
+        Eval(#[from] bits::EvalError),
+        #[cfg(feature = "serde")]
+        #[error("serializing json")]
+        Json(#[from] serde_json::Error),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("8A004A801A8002F478", 16)]
+    #[case("620080001611562C8802118E34", 12)]
+    #[case("C0015000016115A2E0802F182340", 23)]
+    #[case("A0016C880162017C3686B18A3D4780", 31)]
+    fn example_version_sums(#[case] hex: &str, #[case] expect: u64) {
+        assert_eq!(sum_versions(&Packet::parse_hex(hex).unwrap()), expect);
+    }
+
+    #[test]
+    fn detects_binary_input() {
+        let hex = parse_packets("D2FE28\n38006F45291200\n").unwrap();
+        let bin = parse_packets(
+            "110100101111111000101000\n00111000000000000110111101000101001010010001001000000000\n",
+        )
+        .unwrap();
+        assert_eq!(bin, hex);
+    }
+
+    #[test]
+    fn renders_infix_per_packet() {
+        assert_eq!(
+            render_infix("C200B40A82\n9C0141080250320F1802104A08\n").unwrap(),
+            "1 + 2\n1 + 3 == 2 * 2\n"
+        );
+    }
 }
@@ -1,18 +1,19 @@
 use aoclib::{config::Config, website::get_input};
-use day16::{part1, part2};
+use day16::{solve_part1, solve_part2};
 
 use color_eyre::eyre::Result;
+use input_source::InputSource;
 use structopt::StructOpt;
-use std::path::PathBuf;
+use tracing::Level;
 
 const YEAR: u32 = 2021;
 const DAY: u8 = 16;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
+    /// input file, or "-" to read from stdin
     #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    input: Option<InputSource>,
 
     /// skip part 1
     #[structopt(long)]
@@ -21,19 +22,45 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// show diagnostics; repeat for more detail
+    #[structopt(short, long, parse(from_occurrences))]
+    verbose: u8,
+
+    /// print the transmission as an indented packet tree instead of solving
+    #[structopt(long)]
+    dump: bool,
+
+    /// print the expression the transmission evaluates, in infix notation, instead of solving
+    #[structopt(long)]
+    infix: bool,
+
+    /// print the transmission as JSON instead of solving
+    #[cfg(feature = "serde")]
+    #[structopt(long)]
+    json: bool,
 }
 
 impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
+    fn log_level(&self) -> Level {
+        match self.verbose {
+            0 => Level::WARN,
+            1 => Level::INFO,
+            2 => Level::DEBUG,
+            _ => Level::TRACE,
+        }
+    }
+
+    fn input(&self) -> Result<InputSource> {
         match self.input {
             None => {
                 let config = Config::load()?;
                 // this does nothing if the input file already exists, but
                 // simplifies the workflow after cloning the repo on a new computer
                 get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                Ok(config.input_for(YEAR, DAY).into())
             }
-            Some(ref path) => Ok(path.clone()),
+            Some(ref source) => Ok(source.clone()),
         }
     }
 }
@@ -41,13 +68,33 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    tracing_subscriber::fmt()
+        .with_max_level(args.log_level())
+        .with_writer(std::io::stderr)
+        .init();
+    let input = args.input()?.read()?;
+
+    if args.dump {
+        print!("{}", day16::render_tree(&input)?);
+        return Ok(());
+    }
+
+    if args.infix {
+        print!("{}", day16::render_infix(&input)?);
+        return Ok(());
+    }
+
+    #[cfg(feature = "serde")]
+    if args.json {
+        println!("{}", day16::render_json(&input)?);
+        return Ok(());
+    }
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        println!("{}", solve_part1(&input)?);
     }
     if args.part2 {
-        part2(&input_path)?;
+        println!("{}", solve_part2(&input)?);
     }
     Ok(())
 }
@@ -1,5 +1,6 @@
-use aoclib::{geometry::Point, parse};
-use std::path::Path;
+use answer::Answers;
+use aoclib::{geometry::Point, input::parse_str};
+use std::{ops::RangeInclusive, path::Path};
 
 /// The triangular numbers compute the x position of a probe after n steps.
 ///
@@ -19,10 +20,45 @@ fn inverse_triangular_number(target: i32) -> i32 {
     ((-1.0 + (1.0 + 8.0 * target as f64).sqrt()) / 2.0).ceil() as i32
 }
 
+/// The ballistic model governing a probe's flight.
+///
+/// On each step, the probe's x velocity moves `drag` toward 0 without passing it,
+/// and its y velocity decreases by `gravity`. The puzzle uses 1 for both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Physics {
+    pub drag: i32,
+    pub gravity: i32,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Physics {
+            drag: 1,
+            gravity: 1,
+        }
+    }
+}
+
+impl Physics {
+    /// `true` for the puzzle's own physics, for which the closed-form solutions hold.
+    fn is_standard(self) -> bool {
+        self == Physics::default()
+    }
+
+    /// Without gravity, or with negative drag, a probe may never come back down.
+    fn validate(self) -> Result<(), Error> {
+        if self.drag < 0 || self.gravity < 1 {
+            return Err(Error::UnsupportedPhysics);
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 struct Probe {
     position: Point,
     velocity: Point,
+    physics: Physics,
 }
 
 impl Probe {
@@ -31,15 +67,26 @@ impl Probe {
         self
     }
 
+    fn with_physics(mut self, physics: Physics) -> Self {
+        self.physics = physics;
+        self
+    }
+
+    /// Every state of this probe's flight after the current one.
+    fn flight(self) -> impl Iterator<Item = Probe> {
+        std::iter::successors(Some(self), |probe| Some(probe.step())).skip(1)
+    }
+
     /// Adjust position by a single step.
     fn step(mut self) -> Self {
+        let Physics { drag, gravity } = self.physics;
         self.position += self.velocity;
         match self.velocity.x.cmp(&0) {
-            std::cmp::Ordering::Less => self.velocity.x += 1,
+            std::cmp::Ordering::Less => self.velocity.x = (self.velocity.x + drag).min(0),
             std::cmp::Ordering::Equal => {}
-            std::cmp::Ordering::Greater => self.velocity.x -= 1,
+            std::cmp::Ordering::Greater => self.velocity.x = (self.velocity.x - drag).max(0),
         }
-        self.velocity.y -= 1;
+        self.velocity.y -= gravity;
         self
     }
 
@@ -51,7 +98,7 @@ impl Probe {
             + self.position.y
     }
 
-    /// Find the min x velocity which lands the probe in the target area.
+    /// Find the min x velocity which can reach the target area.
     ///
     /// The probe's x position after `n` steps is a triangular number:
     ///
@@ -60,32 +107,41 @@ impl Probe {
     /// We therefore need the lowest n which causes n to be in range.
     ///
     /// We could math this, but this is pretty unlikely to be an efficiency problem.
-    fn find_min_x(low_x: i32, high_x: i32) -> i32 {
+    fn find_min_x(low_x: i32) -> i32 {
         let x = inverse_triangular_number(low_x);
         debug_assert!(triangular_number(x) >= low_x);
-        debug_assert!(triangular_number(x) <= high_x);
         x
     }
 
-    fn set_min_x(&mut self, low_x: i32, high_x: i32) {
-        self.velocity.x = Self::find_min_x(low_x, high_x)
-    }
-
-    /// Find the max y velocity which lands the probe in the target area.
+    /// Find the max y velocity which lands the probe in the target area, when
+    /// the y bounds alone are sufficient to determine it.
     ///
     /// The probe _always_ returns to the initial position at step
-    /// `(y_velocity * 2) + 1` for positive `y_velocity`. The maximum velocity
-    /// which falls within the target area is therefore the last for which
-    /// `y_position((y_velocity * 2) + 2)` falls within the target area.
+    /// `(y_velocity * 2) + 1` for positive `y_velocity`. For target areas below
+    /// the launcher, the maximum velocity which falls within the target area is
+    /// therefore the last for which `y_position((y_velocity * 2) + 2)` falls within
+    /// the target area.
     ///
     /// We can observe from this that for any given positive velocity,
     /// the position at `y_position(velocity * 2 + 2) == -velocity - 1`.
     /// It's therefore straightforward that `v_max_y = -(low_y + 1)`.
-    fn find_max_y(low_y: i32, _high_y: i32) -> i32 {
-        assert!(
-            low_y < 0,
-            "this formula is not known to work for positive target areas"
-        );
+    ///
+    /// For target areas above the launcher, the heights the probe passes on the way
+    /// down mirror those on the way up, so the lowest positive height it ever visits
+    /// is `y_velocity`, after the first step. Any greater velocity overshoots:
+    /// `v_max_y = high_y`.
+    ///
+    /// When the target area spans the launcher's height, every upward launch passes
+    /// through `y == 0` on the way down, so no y velocity is too great. In that case,
+    /// only the x constraint can bound the velocity, and this returns `None`.
+    fn find_max_y(low_y: i32, high_y: i32) -> Option<i32> {
+        if low_y > 0 {
+            return Some(high_y);
+        }
+        if high_y >= 0 {
+            return None;
+        }
+
         let v_max_y = -(low_y + 1);
 
         #[cfg(debug_assertions)]
@@ -97,18 +153,21 @@ impl Probe {
             }
 
             assert!(position(v_max_y) >= low_y);
-            assert!(position(v_max_y) <= _high_y);
+            assert!(position(v_max_y) <= high_y);
             assert!(position(v_max_y + 1) < low_y);
         }
 
-        v_max_y
+        Some(v_max_y)
     }
 
-    fn set_max_y(&mut self, low_y: i32, high_y: i32) {
-        self.velocity.y = Self::find_max_y(low_y, high_y)
-    }
+    fn max_height(mut self) -> i32 {
+        if !self.physics.is_standard() {
+            while self.velocity.y > 0 {
+                self = self.step();
+            }
+            return self.position.y;
+        }
 
-    fn max_height(self) -> i32 {
         self.position.y
             + if self.velocity.y > 0 {
                 triangular_number(self.velocity.y)
@@ -120,11 +179,31 @@ impl Probe {
 
 #[derive(Debug, Clone, Copy, parse_display::FromStr, parse_display::Display)]
 #[display("target area: x={low_x}..{high_x}, y={low_y}..{high_y}")]
-struct TargetArea {
-    low_x: i32,
-    high_x: i32,
-    low_y: i32,
-    high_y: i32,
+pub struct TargetArea {
+    pub low_x: i32,
+    pub high_x: i32,
+    pub low_y: i32,
+    pub high_y: i32,
+}
+
+/// An inclusive range of steps during which the probe is within the target area
+/// along a single axis.
+///
+/// `last` is `None` when the probe stays within range indefinitely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StepWindow {
+    first: i32,
+    last: Option<i32>,
+}
+
+impl StepWindow {
+    fn intersects(self, other: StepWindow) -> bool {
+        let first = self.first.max(other.first);
+        [self.last, other.last]
+            .into_iter()
+            .flatten()
+            .all(|last| first <= last)
+    }
 }
 
 impl TargetArea {
@@ -132,58 +211,798 @@ impl TargetArea {
         (self.low_x..=self.high_x).contains(&point.x)
             && (self.low_y..=self.high_y).contains(&point.y)
     }
+
+    /// This target area, reflected across the launcher's x position.
+    ///
+    /// All the velocity computations assume the target area lies rightward of
+    /// the launcher. Leftward targets are solved by mirroring them rightward, then
+    /// negating the x velocities of the result.
+    fn mirrored(self) -> Self {
+        TargetArea {
+            low_x: -self.high_x,
+            high_x: -self.low_x,
+            ..self
+        }
+    }
+
+    /// `true` when the target area lies leftward of the launcher.
+    fn is_leftward(&self) -> bool {
+        self.high_x < 0
+    }
+
+    /// Steps during which a probe launched with horizontal velocity `vx` is within
+    /// the target's x range.
+    ///
+    /// The probe's x velocity reaches 0 after `vx` steps, so there are at most
+    /// `vx` steps to consider. If the probe stalls within range, it stays there.
+    ///
+    /// A probe launched with no horizontal velocity never leaves `x == 0`, so it is
+    /// within range from the first step onward exactly when the target spans `x == 0`.
+    /// Leftward velocities are handled by mirroring.
+    fn x_window(&self, vx: i32) -> Option<StepWindow> {
+        match vx.cmp(&0) {
+            std::cmp::Ordering::Less => return self.mirrored().x_window(-vx),
+            std::cmp::Ordering::Equal => {
+                return (self.low_x..=self.high_x)
+                    .contains(&0)
+                    .then_some(StepWindow {
+                        first: 1,
+                        last: None,
+                    })
+            }
+            std::cmp::Ordering::Greater => {}
+        }
+
+        let mut first = None;
+        let mut x = 0;
+        for step in 1..=vx {
+            x += vx - step + 1;
+            let in_range = (self.low_x..=self.high_x).contains(&x);
+            match (first, in_range) {
+                (None, true) => first = Some(step),
+                (Some(first), false) => {
+                    return Some(StepWindow {
+                        first,
+                        last: Some(step - 1),
+                    })
+                }
+                (None, false) if x > self.high_x => return None,
+                _ => {}
+            }
+        }
+        first.map(|first| StepWindow { first, last: None })
+    }
+
+    /// The x velocities which might reach the target area.
+    ///
+    /// Any velocity beyond the far edge of the target overshoots on the first step.
+    /// When the target spans `x == 0`, the probe may be fired in either direction, or
+    /// not at all.
+    fn x_velocities(&self) -> RangeInclusive<i32> {
+        if self.low_x > 0 {
+            Probe::find_min_x(self.low_x)..=self.high_x
+        } else if self.high_x < 0 {
+            self.low_x..=-Probe::find_min_x(-self.high_x)
+        } else {
+            self.low_x..=self.high_x
+        }
+    }
+
+    /// Each x velocity which can reach the target area, with its step window.
+    fn x_windows(&self) -> impl '_ + Iterator<Item = (i32, StepWindow)> {
+        self.x_velocities()
+            .filter_map(move |vx| self.x_window(vx).map(|window| (vx, window)))
+    }
+
+    /// Steps during which a probe launched with vertical velocity `vy` is within
+    /// the target's y range.
+    ///
+    /// A probe can pass through the target's y range at most twice: once on the
+    /// way up, and once on the way down.
+    ///
+    /// When the target is entirely below the launcher, a probe launched upward
+    /// returns to `y == 0` at step `2 * vy + 1` with velocity `-(vy + 1)`, so we
+    /// skip directly there and only consider the descent.
+    fn y_windows(&self, vy: i32) -> Vec<StepWindow> {
+        let (mut step, mut y, mut velocity) = if vy > 0 && self.high_y < 0 {
+            (2 * vy + 1, 0, -(vy + 1))
+        } else {
+            (0, 0, vy)
+        };
+        let mut windows = Vec::new();
+        let mut first = None;
+        // once the probe is falling below the target, it can never return
+        while velocity >= 0 || y >= self.low_y {
+            step += 1;
+            y += velocity;
+            velocity -= 1;
+            let in_range = (self.low_y..=self.high_y).contains(&y);
+            match (first, in_range) {
+                (None, true) => first = Some(step),
+                (Some(window_first), false) => {
+                    windows.push(StepWindow {
+                        first: window_first,
+                        last: Some(step - 1),
+                    });
+                    first = None;
+                }
+                _ => {}
+            }
+        }
+        windows
+    }
+
+    /// The least y velocity worth considering.
+    fn min_y_velocity(&self) -> i32 {
+        self.low_y.min(0)
+    }
+
+    /// The greatest y velocity worth considering.
+    ///
+    /// When the y bounds alone don't limit the velocity, the x constraint must:
+    /// a probe launched upward faster than `high_y` doesn't come back down into the
+    /// target's y range until step `2 * vy`, so if every x window closes by step `n`,
+    /// no y velocity above `max(high_y, n)` can work. If some x velocity stalls
+    /// within the target area, there are infinitely many workable velocities.
+    fn max_y_velocity(&self) -> Result<i32, Error> {
+        if let Some(v_max_y) = Probe::find_max_y(self.low_y, self.high_y) {
+            return Ok(v_max_y);
+        }
+        let mut last_step = 0;
+        for (_, window) in self.x_windows() {
+            last_step = last_step.max(window.last.ok_or(Error::Unbounded)?);
+        }
+        Ok(self.high_y.max(last_step))
+    }
+
+    /// The x velocities which might reach the target area under arbitrary physics.
+    ///
+    /// Without knowing the drag, we can only rule out velocities which overshoot
+    /// on the first step.
+    fn x_velocities_with(&self) -> RangeInclusive<i32> {
+        self.low_x.min(1)..=self.high_x.max(-1)
+    }
+
+    /// The last step at which any x velocity keeps the probe within the target's
+    /// x range under `physics`.
+    ///
+    /// Fails if some x velocity stalls within range, as the probe then stays there.
+    fn last_x_step_with(&self, physics: Physics) -> Result<i32, Error> {
+        let mut last_step = 0;
+        for vx in self.x_velocities_with() {
+            let flight = Probe::default()
+                .with_physics(physics)
+                .with_velocity(Point::new(vx, 0))
+                .flight()
+                .zip(1..);
+            for (probe, step) in flight {
+                if (self.low_x..=self.high_x).contains(&probe.position.x) {
+                    if probe.velocity.x == 0 {
+                        return Err(Error::Unbounded);
+                    }
+                    last_step = step;
+                }
+                let overshot = (vx > 0 && probe.position.x > self.high_x)
+                    || (vx < 0 && probe.position.x < self.low_x);
+                if overshot || probe.velocity.x == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(last_step)
+    }
+
+    /// The greatest y velocity worth considering under arbitrary `physics`.
+    ///
+    /// This is looser than [`Self::max_y_velocity`], but holds for any positive gravity:
+    ///
+    /// - Below the launcher: the first height the probe visits below `y == 0` is
+    ///   lower than `-y_velocity / gravity`, so any velocity above `gravity * -low_y`
+    ///   overshoots.
+    /// - Above the launcher: the lowest positive height the probe visits is at least
+    ///   `y_velocity / gravity`, so any velocity above `gravity * (high_y + 1)` overshoots.
+    /// - Spanning the launcher: a probe launched faster than `high_y + gravity * n`
+    ///   stays above the target for at least `n` steps, so the x constraint binds.
+    fn max_y_velocity_with(&self, physics: Physics) -> Result<i32, Error> {
+        if self.high_y < 0 {
+            Ok(physics.gravity * -self.low_y)
+        } else if self.low_y > 0 {
+            Ok(physics.gravity * (self.high_y + 1))
+        } else {
+            Ok(self.high_y + physics.gravity * self.last_x_step_with(physics)?)
+        }
+    }
+
+    /// Find the probe launch which reaches the greatest height while still landing
+    /// in this target area under `physics`.
+    fn highest_launch_with(&self, physics: Physics) -> Result<Probe, Error> {
+        if physics.is_standard() {
+            return self.highest_launch();
+        }
+
+        self.workable_velocities_with(physics)?
+            .into_iter()
+            .map(|velocity| {
+                Probe::default()
+                    .with_physics(physics)
+                    .with_velocity(velocity)
+            })
+            .max_by_key(|probe| probe.max_height())
+            .ok_or(Error::NoSolution)
+    }
+
+    /// Find the probe launch which reaches the greatest height while still landing
+    /// in this target area.
+    fn highest_launch(&self) -> Result<Probe, Error> {
+        if self.is_leftward() {
+            let mut probe = self.mirrored().highest_launch()?;
+            probe.velocity.x = -probe.velocity.x;
+            return Ok(probe);
+        }
+
+        let x_windows: Vec<_> = self.x_windows().collect();
+        (self.min_y_velocity()..=self.max_y_velocity()?)
+            .rev()
+            .find_map(|vy| {
+                let y_windows = self.y_windows(vy);
+                x_windows
+                    .iter()
+                    .find(|(_, x_window)| {
+                        y_windows
+                            .iter()
+                            .any(|&y_window| x_window.intersects(y_window))
+                    })
+                    .map(|&(vx, _)| Probe::default().with_velocity(Point::new(vx, vy)))
+            })
+            .ok_or(Error::NoSolution)
+    }
+
+    /// Find the initial velocities which land the probe in this target area.
+    ///
+    /// Rather than simulating each velocity pair, we compute the windows of steps
+    /// during which each x velocity and each y velocity is in range, then keep
+    /// the pairs whose windows overlap.
+    fn workable_velocities(&self) -> Result<Vec<Point>, Error> {
+        if self.is_leftward() {
+            return self.mirrored().workable_velocities().map(unmirror);
+        }
+
+        let y_windows: Vec<_> = (self.min_y_velocity()..=self.max_y_velocity()?)
+            .map(|vy| (vy, self.y_windows(vy)))
+            .filter(|(_, windows)| !windows.is_empty())
+            .collect();
+
+        Ok(self
+            .x_windows()
+            .flat_map(|(vx, x_window)| {
+                y_windows
+                    .iter()
+                    .filter(move |(_, windows)| {
+                        windows
+                            .iter()
+                            .any(|&y_window| x_window.intersects(y_window))
+                    })
+                    .map(move |&(vy, _)| Point::new(vx, vy))
+            })
+            .collect())
+    }
+
+    /// Find the initial velocities which land the probe in this target area under `physics`.
+    ///
+    /// The step windows depend on the puzzle's own physics, so in general we
+    /// simulate every candidate velocity instead.
+    fn workable_velocities_with(&self, physics: Physics) -> Result<Vec<Point>, Error> {
+        if physics.is_standard() {
+            return self.workable_velocities();
+        }
+        physics.validate()?;
+        if self.is_leftward() {
+            return self
+                .mirrored()
+                .workable_velocities_with(physics)
+                .map(unmirror);
+        }
+
+        let low_y = self.min_y_velocity();
+        let high_y = self.max_y_velocity_with(physics)?;
+        Ok(self
+            .x_velocities_with()
+            .flat_map(|vx| (low_y..=high_y).map(move |vy| Point::new(vx, vy)))
+            .filter(|&velocity| hits_with(velocity, self, physics).is_some())
+            .collect())
+    }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (idx, target_area) in parse::<TargetArea>(input)?.enumerate() {
-        let mut probe = Probe::default();
-        probe.set_min_x(target_area.low_x, target_area.high_x);
-        probe.set_max_y(target_area.low_y, target_area.high_y);
+/// Restore velocities computed for a mirrored target area to the original orientation.
+fn unmirror(mut velocities: Vec<Point>) -> Vec<Point> {
+    for velocity in velocities.iter_mut() {
+        velocity.x = -velocity.x;
+    }
+    velocities.sort_unstable_by_key(|velocity| (velocity.x, velocity.y));
+    velocities
+}
 
-        println!(
-            "target area {}: max y position {} (initial velocity: {},{})",
-            idx,
-            probe.max_height(),
-            probe.velocity.x,
-            probe.velocity.y
-        );
+/// Every initial velocity which lands the probe in `target`.
+///
+/// Velocities are ordered by x, then by y.
+pub fn workable_velocities(target: &TargetArea) -> Result<Vec<Point>, Error> {
+    target.workable_velocities()
+}
+
+/// Every initial velocity which lands the probe in `target` under `physics`.
+///
+/// Velocities are ordered by x, then by y.
+pub fn workable_velocities_with(
+    target: &TargetArea,
+    physics: Physics,
+) -> Result<Vec<Point>, Error> {
+    target.workable_velocities_with(physics)
+}
+
+/// The greatest height reachable by a probe which lands in `target`, and the
+/// initial velocity which reaches it.
+pub fn max_height(target: &TargetArea) -> Result<(i32, Point), Error> {
+    let probe = target.highest_launch()?;
+    Ok((probe.max_height(), probe.velocity))
+}
+
+/// The number of distinct initial velocities which land the probe in `target`.
+pub fn count_velocities(target: &TargetArea) -> Result<usize, Error> {
+    target
+        .workable_velocities()
+        .map(|velocities| velocities.len())
+}
+
+/// The position of a probe launched with `velocity` after each step of its flight.
+///
+/// The launcher's own position is not included: the first item is the position
+/// after the first step. The trajectory never ends.
+pub fn trajectory(velocity: Point) -> impl Iterator<Item = Point> {
+    trajectory_with(velocity, Physics::default())
+}
+
+/// The position of a probe launched with `velocity` after each step of its flight
+/// under `physics`.
+pub fn trajectory_with(velocity: Point, physics: Physics) -> impl Iterator<Item = Point> {
+    Probe::default()
+        .with_physics(physics)
+        .with_velocity(velocity)
+        .flight()
+        .map(|probe| probe.position)
+}
+
+/// When and where a probe first lands within a target area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HitInfo {
+    /// The number of steps taken before landing in the target area; always at least 1.
+    pub step: usize,
+    pub position: Point,
+}
+
+/// Determine whether a probe launched with `velocity` ever lands in `target`.
+pub fn hits(velocity: Point, target: &TargetArea) -> Option<HitInfo> {
+    hits_with(velocity, target, Physics::default())
+}
+
+/// Determine whether a probe launched with `velocity` ever lands in `target` under `physics`.
+///
+/// # Panics
+///
+/// If `physics.gravity` is not positive, as the probe might never come back down.
+pub fn hits_with(velocity: Point, target: &TargetArea, physics: Physics) -> Option<HitInfo> {
+    assert!(physics.gravity > 0, "gravity must be positive");
+    Probe::default()
+        .with_physics(physics)
+        .with_velocity(velocity)
+        .flight()
+        .zip(1..)
+        // once the probe is falling below the target, it can never return
+        .take_while(|(probe, _)| probe.velocity.y >= 0 || probe.position.y >= target.low_y)
+        .find(|(probe, _)| target.contains(probe.position))
+        .map(|(probe, step)| HitInfo {
+            step,
+            position: probe.position,
+        })
+}
+
+pub fn solve_part1(input: &str) -> Result<Answers<i32>, Error> {
+    solve_part1_with(input, Physics::default())
+}
+
+/// The greatest height reachable, for each target area in the input.
+pub fn solve_part1_with(input: &str, physics: Physics) -> Result<Answers<i32>, Error> {
+    parse_str::<TargetArea>(input)?
+        .map(|target_area| {
+            target_area
+                .highest_launch_with(physics)
+                .map(Probe::max_height)
+        })
+        .collect()
+}
+
+pub fn solve_part2(input: &str) -> Result<Answers<usize>, Error> {
+    solve_part2_with(input, Physics::default())
+}
+
+/// The number of workable initial velocities, for each target area in the input.
+pub fn solve_part2_with(input: &str, physics: Physics) -> Result<Answers<usize>, Error> {
+    parse_str::<TargetArea>(input)?
+        .map(|target_area| {
+            target_area
+                .workable_velocities_with(physics)
+                .map(|velocities| velocities.len())
+        })
+        .collect()
+}
+
+pub fn part1(input: &Path) -> Result<Answers<i32>, Error> {
+    part1_with(input, Physics::default())
+}
+
+pub fn part1_with(input: &Path, physics: Physics) -> Result<Answers<i32>, Error> {
+    solve_part1_with(&std::fs::read_to_string(input)?, physics)
+}
+
+pub fn part2(input: &Path) -> Result<Answers<usize>, Error> {
+    part2_with(input, Physics::default())
+}
+
+pub fn part2_with(input: &Path, physics: Physics) -> Result<Answers<usize>, Error> {
+    solve_part2_with(&std::fs::read_to_string(input)?, physics)
+}
+
+/// Render every workable initial velocity for each target area, one per line.
+pub fn render_velocities(input: &str, physics: Physics) -> Result<String, Error> {
+    let mut out = String::new();
+    for (idx, target_area) in parse_str::<TargetArea>(input)?.enumerate() {
+        out.push_str(&format!("target area {}:\n", idx));
+        for velocity in workable_velocities_with(&target_area, physics)? {
+            out.push_str(&format!("{},{}\n", velocity.x, velocity.y));
+        }
     }
-    Ok(())
+    Ok(out)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, target_area) in parse::<TargetArea>(input)?.enumerate() {
-        let low_x = Probe::find_min_x(target_area.low_x, target_area.high_x);
-        let high_y = Probe::find_max_y(target_area.low_y, target_area.high_y);
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("infinitely many initial velocities land in the target area")]
+        Unbounded,
+        #[error("unsupported physics: drag must not be negative and gravity must be positive")]
+        UnsupportedPhysics,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "target area: x=20..30, y=-10..-5";
+
+    #[test]
+    fn example_part1() {
+        let target_area: TargetArea = EXAMPLE.parse().unwrap();
+        let (height, velocity) = max_height(&target_area).unwrap();
+        assert_eq!(height, 45);
+        assert_eq!(velocity.y, 9);
+        assert!(hits(velocity, &target_area).is_some());
+    }
+
+    #[test]
+    fn example_part2() {
+        let target_area: TargetArea = EXAMPLE.parse().unwrap();
+        assert_eq!(count_velocities(&target_area).unwrap(), 112);
+    }
+
+    /// Count workable velocities by simulating every candidate velocity pair.
+    fn count_workable_velocities_by_simulation(target_area: &TargetArea) -> usize {
+        let high_y = target_area.max_y_velocity().unwrap();
 
         let mut count_workable_velocities = 0;
-        for vx in low_x..=target_area.high_x {
-            for vy in target_area.low_y..=high_y {
-                let mut probe = Probe::default().with_velocity(Point::new(vx, vy));
-
-                for _ in 0.. {
-                    probe = probe.step();
-                    if target_area.contains(probe.position) {
-                        count_workable_velocities += 1;
-                        break;
-                    }
-                    if probe.position.y < target_area.low_y {
-                        break;
-                    }
+        for vx in target_area.x_velocities_with() {
+            for vy in target_area.min_y_velocity()..=high_y {
+                if hits(Point::new(vx, vy), target_area).is_some() {
+                    count_workable_velocities += 1;
                 }
             }
         }
+        count_workable_velocities
+    }
+
+    #[test]
+    fn analytic_count_matches_simulation() {
+        for target_area in [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=1..4, y=-3..-1",
+            "target area: x=50..60, y=-200..-150",
+            "target area: x=100..140, y=-90..-60",
+            "target area: x=20..30, y=5..10",
+            "target area: x=7..9, y=-3..4",
+            "target area: x=56..65, y=0..3",
+            "target area: x=0..5, y=-5..-1",
+            "target area: x=-5..5, y=-10..-3",
+            "target area: x=-12..0, y=-6..-6",
+            "target area: x=-9..-7, y=-3..4",
+        ] {
+            let target_area: TargetArea = target_area.parse().unwrap();
+            assert_eq!(
+                target_area.workable_velocities().unwrap().len(),
+                count_workable_velocities_by_simulation(&target_area),
+                "{}",
+                target_area,
+            );
+        }
+    }
+
+    #[test]
+    fn target_above_launcher() {
+        let target_area: TargetArea = "target area: x=20..30, y=5..10".parse().unwrap();
+        let probe = target_area.highest_launch().unwrap();
+        assert_eq!(probe.velocity.y, 10);
+        assert_eq!(probe.max_height(), 55);
+    }
 
-        println!(
-            "target area {}: workable velocities: {}",
-            idx, count_workable_velocities,
+    #[test]
+    fn target_spanning_launcher_bounded_by_x() {
+        // no x velocity stalls in 7..=9, so the probe must arrive within a few steps
+        let target_area: TargetArea = "target area: x=7..9, y=-3..4".parse().unwrap();
+        assert!(target_area.highest_launch().is_ok());
+    }
+
+    #[test]
+    fn target_spanning_launcher_unbounded() {
+        // x velocity 3 stalls at x == 6, after which every upward launch returns to y == 0
+        let target_area: TargetArea = "target area: x=5..7, y=-3..4".parse().unwrap();
+        assert!(matches!(
+            target_area.workable_velocities(),
+            Err(Error::Unbounded)
+        ));
+    }
+
+    #[test]
+    fn mirrored_example() {
+        let target_area: TargetArea = "target area: x=-30..-20, y=-10..-5".parse().unwrap();
+        let probe = target_area.highest_launch().unwrap();
+        assert!(probe.velocity.x < 0);
+        assert_eq!(probe.max_height(), 45);
+        assert_eq!(target_area.workable_velocities().unwrap().len(), 112);
+    }
+
+    #[test]
+    fn mirrored_launch_lands() {
+        let target_area: TargetArea = "target area: x=-30..-20, y=-10..-5".parse().unwrap();
+        let probe = target_area.highest_launch().unwrap();
+        assert!(hits(probe.velocity, &target_area).is_some());
+    }
+
+    #[test]
+    fn render_example_velocities() {
+        let rendered = render_velocities(EXAMPLE, Physics::default()).unwrap();
+        let mut lines = rendered.lines();
+        assert_eq!(lines.next(), Some("target area 0:"));
+        assert_eq!(lines.clone().count(), 112);
+        assert!(lines.any(|line| line == "6,9"));
+    }
+
+    #[test]
+    fn example_velocities() {
+        let target_area: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let velocities = workable_velocities(&target_area).unwrap();
+        assert_eq!(velocities.len(), 112);
+        for (x, y) in [
+            (23, -10),
+            (25, -9),
+            (27, -5),
+            (29, -6),
+            (22, -6),
+            (21, -7),
+            (9, 0),
+            (27, -7),
+            (24, -5),
+            (6, 9),
+            (7, 9),
+            (30, -10),
+        ] {
+            assert!(velocities.contains(&Point::new(x, y)), "{},{}", x, y);
+        }
+        assert!(!velocities.contains(&Point::new(6, 10)));
+        assert!(!velocities.contains(&Point::new(17, -4)));
+    }
+
+    #[test]
+    fn mirrored_velocities() {
+        let target_area: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let velocities = workable_velocities(&target_area).unwrap();
+        let mut mirrored = workable_velocities(&target_area.mirrored()).unwrap();
+        for velocity in mirrored.iter_mut() {
+            velocity.x = -velocity.x;
+        }
+        mirrored.sort_unstable_by_key(|velocity| (velocity.x, velocity.y));
+        assert_eq!(velocities, mirrored);
+    }
+
+    #[test]
+    fn example_trajectory() {
+        let positions: Vec<_> = trajectory(Point::new(7, 2)).take(7).collect();
+        let expect: Vec<_> = [
+            (7, 2),
+            (13, 3),
+            (18, 3),
+            (22, 2),
+            (25, 0),
+            (27, -3),
+            (28, -7),
+        ]
+        .into_iter()
+        .map(|(x, y)| Point::new(x, y))
+        .collect();
+        assert_eq!(positions, expect);
+    }
+
+    #[test]
+    fn example_hits() {
+        let target_area: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        assert_eq!(
+            hits(Point::new(7, 2), &target_area),
+            Some(HitInfo {
+                step: 7,
+                position: Point::new(28, -7),
+            })
+        );
+        assert_eq!(
+            hits(Point::new(9, 0), &target_area),
+            Some(HitInfo {
+                step: 4,
+                position: Point::new(30, -6),
+            })
         );
+        assert_eq!(hits(Point::new(17, -4), &target_area), None);
     }
-    Ok(())
-}
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+    #[test]
+    fn general_physics_agree_with_standard() {
+        // with the puzzle's physics, the simulation must agree with the step windows
+        for target_area in [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+            "target area: x=7..9, y=-3..4",
+        ] {
+            let target_area: TargetArea = target_area.parse().unwrap();
+            let low_y = target_area.min_y_velocity();
+            let high_y = target_area.max_y_velocity_with(Physics::default()).unwrap();
+            let (low_x, high_x) = if target_area.is_leftward() {
+                (target_area.low_x, -1)
+            } else {
+                (1, target_area.high_x)
+            };
+            let simulated: Vec<_> = (low_x..=high_x)
+                .flat_map(|vx| (low_y..=high_y).map(move |vy| Point::new(vx, vy)))
+                .filter(|&velocity| hits(velocity, &target_area).is_some())
+                .collect();
+            assert_eq!(
+                workable_velocities(&target_area).unwrap(),
+                simulated,
+                "{}",
+                target_area
+            );
+        }
+    }
+
+    #[test]
+    fn alternate_physics_hits_are_workable() {
+        for (drag, gravity) in [(0, 1), (1, 2), (2, 1), (2, 3), (3, 2)] {
+            alternate_physics_hits_are_workable_with(drag, gravity);
+        }
+    }
+
+    fn alternate_physics_hits_are_workable_with(drag: i32, gravity: i32) {
+        let physics = Physics { drag, gravity };
+        for target_area in [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+        ] {
+            let target_area: TargetArea = target_area.parse().unwrap();
+            let velocities = workable_velocities_with(&target_area, physics).unwrap();
+            assert!(!velocities.is_empty());
+            // exhaustively check a generous region around the computed bounds
+            for vx in -40..=40 {
+                for vy in -20..=80 {
+                    let velocity = Point::new(vx, vy);
+                    assert_eq!(
+                        velocities.contains(&velocity),
+                        hits_with(velocity, &target_area, physics).is_some(),
+                        "{} {:?} {},{}",
+                        target_area,
+                        physics,
+                        vx,
+                        vy,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heavier_gravity_lowers_max_height() {
+        let target_area: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let standard = target_area.highest_launch_with(Physics::default()).unwrap();
+        let heavy = target_area
+            .highest_launch_with(Physics {
+                drag: 1,
+                gravity: 2,
+            })
+            .unwrap();
+        assert!(heavy.max_height() < standard.max_height());
+    }
+
+    #[test]
+    fn zero_gravity_is_unsupported() {
+        let target_area: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        assert!(matches!(
+            workable_velocities_with(
+                &target_area,
+                Physics {
+                    drag: 1,
+                    gravity: 0
+                }
+            ),
+            Err(Error::UnsupportedPhysics)
+        ));
+    }
+
+    #[test]
+    fn target_spanning_x_axis_includes_vertical_launch() {
+        let target_area: TargetArea = "target area: x=0..5, y=-5..-1".parse().unwrap();
+        let velocities = workable_velocities(&target_area).unwrap();
+        assert!(velocities.contains(&Point::new(0, 0)));
+        assert!(velocities.contains(&Point::new(0, 1)));
+        assert!(!velocities.iter().any(|velocity| velocity.x < 0));
+    }
+
+    #[test]
+    fn target_spanning_both_directions() {
+        let target_area: TargetArea = "target area: x=-5..5, y=-10..-3".parse().unwrap();
+        let velocities = workable_velocities(&target_area).unwrap();
+        let mut mirrored = velocities.clone();
+        for velocity in mirrored.iter_mut() {
+            velocity.x = -velocity.x;
+        }
+        mirrored.sort_unstable_by_key(|velocity| (velocity.x, velocity.y));
+        assert_eq!(velocities, mirrored);
+        assert_eq!(max_height(&target_area).unwrap().0, 45);
+    }
+
+    #[test]
+    fn target_containing_launcher() {
+        // every upward vertical launch returns to the origin
+        let target_area: TargetArea = "target area: x=-2..2, y=-2..2".parse().unwrap();
+        assert!(matches!(max_height(&target_area), Err(Error::Unbounded)));
+        assert!(matches!(
+            count_velocities(&target_area),
+            Err(Error::Unbounded)
+        ));
+        assert!(matches!(
+            workable_velocities_with(
+                &target_area,
+                Physics {
+                    drag: 2,
+                    gravity: 2
+                }
+            ),
+            Err(Error::Unbounded)
+        ));
+    }
+
+    #[test]
+    fn target_touching_launcher_height() {
+        // x velocity 6 stalls at x == 21, after which every upward launch returns to y == 0
+        let target_area: TargetArea = "target area: x=20..30, y=-10..0".parse().unwrap();
+        assert!(matches!(
+            count_velocities(&target_area),
+            Err(Error::Unbounded)
+        ));
+    }
 }
@@ -1,18 +1,18 @@
 use aoclib::{config::Config, website::get_input};
-use day17::{part1, part2};
+use day17::{solve_part1_with, solve_part2_with, Physics};
 
 use color_eyre::eyre::Result;
+use input_source::InputSource;
 use structopt::StructOpt;
-use std::path::PathBuf;
 
 const YEAR: u32 = 2021;
 const DAY: u8 = 17;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
+    /// input file, or "-" to read from stdin
     #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    input: Option<InputSource>,
 
     /// skip part 1
     #[structopt(long)]
@@ -21,19 +21,31 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// list every workable initial velocity instead of solving
+    #[structopt(long)]
+    velocities: bool,
+
+    /// how much the probe's x velocity moves toward 0 each step
+    #[structopt(long, default_value = "1")]
+    drag: i32,
+
+    /// how much the probe's y velocity decreases each step
+    #[structopt(long, default_value = "1")]
+    gravity: i32,
 }
 
 impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
+    fn input(&self) -> Result<InputSource> {
         match self.input {
             None => {
                 let config = Config::load()?;
                 // this does nothing if the input file already exists, but
                 // simplifies the workflow after cloning the repo on a new computer
                 get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                Ok(config.input_for(YEAR, DAY).into())
             }
-            Some(ref path) => Ok(path.clone()),
+            Some(ref source) => Ok(source.clone()),
         }
     }
 }
@@ -41,13 +53,22 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    let input = args.input()?.read()?;
+    let physics = Physics {
+        drag: args.drag,
+        gravity: args.gravity,
+    };
+
+    if args.velocities {
+        print!("{}", day17::render_velocities(&input, physics)?);
+        return Ok(());
+    }
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        println!("{}", solve_part1_with(&input, physics)?);
     }
     if args.part2 {
-        part2(&input_path)?;
+        println!("{}", solve_part2_with(&input, physics)?);
     }
     Ok(())
 }
@@ -0,0 +1,64 @@
+use aoclib::{config::Config, website::get_input};
+use criterion::{criterion_group, criterion_main, Criterion};
+use day18::{
+    list_impl, max_pairwise_magnitude, sum_magnitude, zipper_impl, Backend, Snailfish,
+    SnailfishNumber,
+};
+
+const YEAR: u32 = 2021;
+const DAY: u8 = 18;
+
+/// Lines of the real puzzle input, downloaded if necessary.
+fn real_input() -> Vec<String> {
+    let config = Config::load().expect("benchmarks need the aoclib config to find the input");
+    get_input(&config, YEAR, DAY).expect("downloading input");
+    std::fs::read_to_string(config.input_for(YEAR, DAY))
+        .expect("reading input")
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn bench_backend<S: Snailfish>(c: &mut Criterion, backend: Backend, input: &[String]) {
+    c.benchmark_group("parse")
+        .bench_function(backend.to_string(), |b| {
+            b.iter(|| {
+                input
+                    .iter()
+                    .map(|line| line.parse::<S>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+        });
+
+    let numbers: Vec<S> = input
+        .iter()
+        .map(|line| line.parse().expect("input must be valid snailfish numbers"))
+        .collect();
+
+    c.benchmark_group("addition")
+        .bench_function(backend.to_string(), |b| {
+            b.iter(|| numbers[0].clone() + numbers[1].clone())
+        });
+
+    c.benchmark_group("reduction")
+        .bench_function(backend.to_string(), |b| {
+            b.iter(|| sum_magnitude(numbers.iter().cloned()))
+        });
+
+    c.benchmark_group("part2")
+        .sample_size(10)
+        .bench_function(backend.to_string(), |b| {
+            b.iter(|| max_pairwise_magnitude(&numbers))
+        });
+}
+
+fn bench_backends(c: &mut Criterion) {
+    let input = real_input();
+    bench_backend::<Box<SnailfishNumber>>(c, Backend::Tree, &input);
+    bench_backend::<list_impl::SnailfishNumber>(c, Backend::List, &input);
+    bench_backend::<zipper_impl::SnailfishNumber>(c, Backend::Zipper, &input);
+}
+
+criterion_group!(benches, bench_backends);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Differential tests: every implementation must agree on every operation.
+
+use crate::{list_impl, zipper_impl, Error};
+use proptest::{collection::vec, prelude::*};
+
+/// Box the tree implementation's number so all the backends parse from `&str` alike.
+type Tree = Box<crate::SnailfishNumber>;
+type List = list_impl::SnailfishNumber;
+type Zipper = zipper_impl::SnailfishNumber;
+
+/// Generate a reduced snailfish number in canonical notation.
+///
+/// Like the puzzle inputs, generated numbers are always pairs, nest no more
+/// than four pairs deep, and contain only single-digit values.
+fn snailfish() -> impl Strategy<Value = String> {
+    let leaf = (0_u8..10).prop_map(|value| value.to_string());
+    let element = leaf.prop_recursive(3, 16, 2, |inner| {
+        (inner.clone(), inner).prop_map(|(left, right)| format!("[{},{}]", left, right))
+    });
+    (element.clone(), element).prop_map(|(left, right)| format!("[{},{}]", left, right))
+}
+
+/// Generate an unreduced snailfish number, like the sum of two numbers before reduction.
+///
+/// Pairs nest up to five deep, and values may be large enough to split.
+fn unreduced() -> impl Strategy<Value = String> {
+    let leaf = (0_u8..20).prop_map(|value| value.to_string());
+    let element = leaf.prop_recursive(4, 32, 2, |inner| {
+        (inner.clone(), inner).prop_map(|(left, right)| format!("[{},{}]", left, right))
+    });
+    (element.clone(), element).prop_map(|(left, right)| format!("[{},{}]", left, right))
+}
+
+/// Arbitrary text over the snailfish alphabet, plus whitespace and a stray character.
+fn noise() -> impl Strategy<Value = String> {
+    vec(
+        prop_oneof![
+            Just('['),
+            Just(']'),
+            Just(','),
+            Just(' '),
+            Just(';'),
+            (0_u8..10).prop_map(|digit| (b'0' + digit) as char),
+        ],
+        0..16,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+fn parse_all(input: &str) -> (Tree, List, Zipper) {
+    (
+        input.parse().unwrap(),
+        input.parse().unwrap(),
+        input.parse().unwrap(),
+    )
+}
+
+proptest! {
+    #[test]
+    fn parse_agrees(input in snailfish()) {
+        let (tree, list, zipper) = parse_all(&input);
+        prop_assert_eq!(&tree.to_string(), &input);
+        prop_assert_eq!(&list.to_string(), &input);
+        prop_assert_eq!(&zipper.to_string(), &input);
+        prop_assert_eq!(tree.magnitude(), list.magnitude());
+        prop_assert_eq!(tree.magnitude(), zipper.magnitude());
+    }
+
+    #[test]
+    fn addition_agrees(left in snailfish(), right in snailfish()) {
+        let (left_tree, left_list, left_zipper) = parse_all(&left);
+        let (right_tree, right_list, right_zipper) = parse_all(&right);
+        let tree = left_tree + right_tree;
+        let list = left_list + right_list;
+        let zipper = left_zipper + right_zipper;
+        prop_assert_eq!(tree.to_string(), list.to_string());
+        prop_assert_eq!(tree.to_string(), zipper.to_string());
+        prop_assert_eq!(tree.magnitude(), list.magnitude());
+        prop_assert_eq!(tree.magnitude(), zipper.magnitude());
+    }
+
+    #[test]
+    fn sum_agrees(inputs in vec(snailfish(), 1..12)) {
+        let trees: Vec<Tree> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let lists: Vec<List> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let zippers: Vec<Zipper> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let tree: Tree = trees.iter().sum();
+        let list: List = lists.iter().sum();
+        let zipper: Zipper = zippers.iter().sum();
+        prop_assert_eq!(&trees.into_iter().sum::<Tree>(), &tree);
+        prop_assert_eq!(&lists.into_iter().sum::<List>(), &list);
+        prop_assert_eq!(&zippers.into_iter().sum::<Zipper>(), &zipper);
+        prop_assert_eq!(tree.to_string(), list.to_string());
+        prop_assert_eq!(tree.to_string(), zipper.to_string());
+        prop_assert_eq!(tree.magnitude(), list.magnitude());
+        prop_assert_eq!(tree.magnitude(), zipper.magnitude());
+    }
+
+    #[test]
+    fn max_pairwise_magnitude_agrees(inputs in vec(snailfish(), 0..8)) {
+        let tree: Vec<Tree> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let list: Vec<List> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let zipper: Vec<Zipper> = inputs.iter().map(|input| input.parse().unwrap()).collect();
+        let expect = crate::max_pairwise_magnitude(&tree);
+        prop_assert_eq!(crate::max_pairwise_magnitude(&list), expect);
+        prop_assert_eq!(crate::max_pairwise_magnitude(&zipper), expect);
+    }
+
+    #[test]
+    fn conversions_round_trip(input in snailfish()) {
+        let (tree, list, _) = parse_all(&input);
+        prop_assert_eq!(&List::from(&*tree), &list);
+        prop_assert_eq!(&Tree::try_from(&list).unwrap(), &tree);
+    }
+
+    #[test]
+    fn addition_agrees_across_conversion(left in snailfish(), right in snailfish()) {
+        let (left_tree, left_list, _) = parse_all(&left);
+        let (right_tree, right_list, _) = parse_all(&right);
+        let tree = left_tree.clone() + right_tree.clone();
+        let list = left_list.clone() + right_list.clone();
+        prop_assert_eq!(&List::from(&*tree), &list);
+        prop_assert_eq!(&Tree::try_from(&list).unwrap(), &tree);
+
+        // adding converted numbers must match adding natively
+        let converted = Tree::try_from(&left_list).unwrap() + Tree::try_from(&right_list).unwrap();
+        prop_assert_eq!(&converted, &tree);
+        let converted = List::from(&*left_tree) + List::from(&*right_tree);
+        prop_assert_eq!(&converted, &list);
+    }
+
+    #[test]
+    fn reduction_traces_agree(left in snailfish(), right in snailfish()) {
+        let sum = format!("[{},{}]", left, right);
+        let mut tree: Tree = sum.parse().unwrap();
+        let mut list: List = sum.parse().unwrap();
+        prop_assert_eq!(tree.reduce_with_trace(), list.reduce_with_trace());
+    }
+
+    #[test]
+    fn reduction_is_idempotent(input in unreduced()) {
+        let mut tree: Tree = input.parse().unwrap();
+        let mut list: List = input.parse().unwrap();
+        tree.reduce_with_trace();
+        list.reduce_with_trace();
+        prop_assert_eq!(tree.reduce_with_trace(), vec![]);
+        prop_assert_eq!(list.reduce_with_trace(), vec![]);
+        prop_assert_eq!(tree.to_string(), list.to_string());
+    }
+
+    #[test]
+    fn reduced_numbers_are_shallow(input in unreduced()) {
+        let mut tree: Tree = input.parse().unwrap();
+        tree.reduce_with_trace();
+        for leaf in tree.leaves() {
+            prop_assert!(leaf.depth <= 4, "{} is nested too deeply", tree);
+            prop_assert!(*leaf.value < 10, "{} has a value to split", tree);
+        }
+    }
+
+    #[test]
+    fn errors_agree(input in noise()) {
+        let tree = input.parse::<Tree>();
+        let list = input.parse::<List>();
+        match (tree, list) {
+            (Ok(tree), Ok(list)) => prop_assert_eq!(tree.to_string(), list.to_string()),
+            (Err(Error::Syntax(tree)), Err(Error::Syntax(list))) => {
+                prop_assert_eq!(tree.offset(), list.offset());
+            }
+            (tree, list) => prop_assert!(false, "disagreement: {:?} vs {:?}", tree, list),
+        }
+    }
+}
@@ -1,375 +1,808 @@
-use lalrpop_util::lalrpop_mod;
-lalrpop_mod!(parser);
+// Every backend links its nodes by index, by position, or by `Arc`; keep it that way.
+#![deny(unsafe_code)]
 
-#[cfg(feature = "list_impl")]
+#[cfg(test)]
+mod differential;
 pub mod list_impl;
+mod parser;
+mod syntax;
+pub mod zipper_impl;
+
+use std::{
+    fmt,
+    iter::Sum,
+    ops::Add,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use aoclib::input::parse_str;
+#[cfg(feature = "parallelism")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use syntax::check_brackets;
+pub use syntax::SyntaxError;
 
-use std::{fmt, path::Path, str::FromStr};
+/// Index of a node within its tree's arena.
+type NodeId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Branch {
+    left: NodeId,
+    right: NodeId,
+}
 
-use aoclib::parse;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn opposite(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
 
-#[derive(PartialEq)]
-struct Branch<T> {
-    left: Box<Node<T>>,
-    right: Box<Node<T>>,
+impl Branch {
+    fn child(self, side: Side) -> NodeId {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone)]
 enum Contents<T> {
     Leaf(T),
-    Branch(Branch<T>),
+    Branch(Branch),
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    contents: Contents<T>,
+    up: Option<NodeId>,
+}
+
+/// A binary tree whose nodes live in a single arena, linked by index.
+///
+/// Nodes are never shared between trees: combining two trees moves their nodes
+/// into a fresh arena. Nodes removed from the tree are recycled by later insertions.
+#[derive(Clone)]
+pub struct Node<T> {
+    arena: Vec<Slot<T>>,
+    free: Vec<NodeId>,
+    root: NodeId,
+}
+
+/// A reference to a particular node within a tree, for formatting and comparison.
+struct NodeRef<'a, T> {
+    tree: &'a Node<T>,
+    id: NodeId,
 }
 
-impl<T: fmt::Debug> fmt::Debug for Contents<T> {
+impl<'a, T: fmt::Debug> fmt::Debug for NodeRef<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Leaf(leaf) => write!(f, "{:?}", leaf),
-            Self::Branch(branch) => f
+        match &self.tree.arena[self.id].contents {
+            Contents::Leaf(leaf) => write!(f, "{:?}", leaf),
+            Contents::Branch(branch) => f
                 .debug_list()
-                .entry(&branch.left)
-                .entry(&branch.right)
+                .entry(&self.tree.node_ref(branch.left))
+                .entry(&self.tree.node_ref(branch.right))
                 .finish(),
         }
     }
 }
 
-pub struct Node<T> {
-    contents: Contents<T>,
-    up: Option<*const Node<T>>,
+impl<'a, T: fmt::Display> fmt::Display for NodeRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tree.arena[self.id].contents {
+            Contents::Leaf(leaf) => write!(f, "{}", leaf),
+            Contents::Branch(branch) => write!(
+                f,
+                "[{},{}]",
+                self.tree.node_ref(branch.left),
+                self.tree.node_ref(branch.right)
+            ),
+        }
+    }
+}
+
+impl<'a, T: PartialEq> PartialEq for NodeRef<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (
+            &self.tree.arena[self.id].contents,
+            &other.tree.arena[other.id].contents,
+        ) {
+            (Contents::Leaf(left), Contents::Leaf(right)) => left == right,
+            (Contents::Branch(left), Contents::Branch(right)) => {
+                self.tree.node_ref(left.left) == other.tree.node_ref(right.left)
+                    && self.tree.node_ref(left.right) == other.tree.node_ref(right.right)
+            }
+            _ => false,
+        }
+    }
 }
 
 impl<T: fmt::Debug> fmt::Debug for Node<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.contents)
+        write!(f, "{:?}", self.node_ref(self.root))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Node<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.node_ref(self.root))
     }
 }
 
 impl<T: PartialEq> PartialEq for Node<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.contents == other.contents
+        self.node_ref(self.root) == other.node_ref(other.root)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T: Serialize> Serialize for NodeRef<'a, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.tree.arena[self.id].contents {
+            Contents::Leaf(leaf) => leaf.serialize(serializer),
+            Contents::Branch(branch) => (
+                self.tree.node_ref(branch.left),
+                self.tree.node_ref(branch.right),
+            )
+                .serialize(serializer),
+        }
+    }
+}
+
+/// Snailfish notation is JSON: pairs are two-element arrays.
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Node<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.node_ref(self.root).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::de::DeserializeOwned> serde::Deserialize<'de> for Box<Node<T>> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        value.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> From<&Node<T>> for serde_json::Value {
+    fn from(node: &Node<T>) -> Self {
+        serde_json::to_value(node).expect("leaf values must be representable as JSON")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned> TryFrom<serde_json::Value> for Box<Node<T>> {
+    type Error = serde_json::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Array(elements) => {
+                let [left, right]: [serde_json::Value; 2] =
+                    elements.try_into().map_err(|elements: Vec<_>| {
+                        <serde_json::Error as serde::de::Error>::invalid_length(
+                            elements.len(),
+                            &"a pair",
+                        )
+                    })?;
+                Ok(Node::new_pair(left.try_into()?, right.try_into()?))
+            }
+            value => serde_json::from_value(value).map(Node::new_orphan_value),
+        }
+    }
+}
+
+/// Move the subtree rooted at `id` from `from` to the end of `into`, returning its new id.
+fn transplant<T>(
+    from: &mut [Option<Slot<T>>],
+    id: NodeId,
+    into: &mut Vec<Slot<T>>,
+    up: Option<NodeId>,
+) -> NodeId {
+    let slot = from[id]
+        .take()
+        .expect("each node is reachable from exactly one parent");
+    let new_id = into.len();
+    into.push(Slot {
+        contents: slot.contents,
+        up,
+    });
+    if let Contents::Branch(branch) = into[new_id].contents {
+        let left = transplant(from, branch.left, into, Some(new_id));
+        let right = transplant(from, branch.right, into, Some(new_id));
+        into[new_id].contents = Contents::Branch(Branch { left, right });
     }
+    new_id
 }
 
 impl<T> Node<T> {
     /// Construct a new value node without a parent.
     pub fn new_orphan_value(value: T) -> Box<Self> {
         Box::new(Self {
-            contents: Contents::Leaf(value),
-            up: None,
-        })
-    }
-
-    /// Construct a new value node which has a parent.
-    pub fn new_value(value: T, parent: &Box<Self>) -> Box<Self> {
-        Box::new(Self {
-            contents: Contents::Leaf(value),
-            up: Some(&**parent as _),
+            arena: vec![Slot {
+                contents: Contents::Leaf(value),
+                up: None,
+            }],
+            free: Vec::new(),
+            root: 0,
         })
     }
 
-    /// Use pointer trickery to enable internal mutation.
+    /// Construct a new value node destined for `parent`.
     ///
-    /// Super unsafe, particularly if threads are involved. No checks are performed.
-    unsafe fn force_mutable(&self) -> &mut Self {
-        &mut *(self as *const Self as *mut Self)
+    /// Nodes can't be linked across arenas, so this is equivalent to
+    /// [`Self::new_orphan_value`]: the parent link is established when the node
+    /// is attached to a tree with [`Self::new_pair`].
+    pub fn new_value(value: T, _parent: &Self) -> Box<Self> {
+        Self::new_orphan_value(value)
     }
 
     /// Construct a new pair node without a parent.
     ///
-    /// If either child had a parent or an external reference, this function will return `None`.
+    /// The nodes of both children are moved into the new tree's arena.
     pub fn new_pair(left: Box<Node<T>>, right: Box<Node<T>>) -> Box<Self> {
-        let root = Box::new(Self {
-            contents: Contents::Branch(Branch { left, right }),
+        let mut arena = Vec::with_capacity(left.arena.len() + right.arena.len() + 1);
+        arena.push(Slot {
+            contents: Contents::Branch(Branch { left: 0, right: 0 }),
             up: None,
         });
+        let left = left.transplant_into(&mut arena, 0);
+        let right = right.transplant_into(&mut arena, 0);
+        arena[0].contents = Contents::Branch(Branch { left, right });
 
-        // we have to encapsulate these pointers so the borrow checker doesn't complain
-        {
-            // We have to mess with the nodes to create appropriate up pointers,
-            // even though at this point we don't have write access.
-            // That's ok; we know that we have unique access to each of these, so it's ok to reach in
-            // with unsafe sorcery and modify the item anyway.
-            let left_ptr = &*root.branch().unwrap().left as *const Self as *mut Self;
-            let right_ptr = &*root.branch().unwrap().right as *const Self as *mut Self;
-            for ptr in [left_ptr, right_ptr] {
-                unsafe {
-                    (*ptr).up = Some(&*root as _);
-                }
+        Box::new(Self {
+            arena,
+            free: Vec::new(),
+            root: 0,
+        })
+    }
+
+    /// Move every reachable node of this tree into `arena`, beneath `up`.
+    ///
+    /// Returns the new id of this tree's root.
+    fn transplant_into(self, arena: &mut Vec<Slot<T>>, up: NodeId) -> NodeId {
+        let mut slots: Vec<_> = self.arena.into_iter().map(Some).collect();
+        transplant(&mut slots, self.root, arena, Some(up))
+    }
+
+    fn node_ref(&self, id: NodeId) -> NodeRef<'_, T> {
+        NodeRef { tree: self, id }
+    }
+
+    /// Insert a new node into the arena, reusing a released slot if available.
+    fn alloc(&mut self, contents: Contents<T>, up: Option<NodeId>) -> NodeId {
+        let slot = Slot { contents, up };
+        match self.free.pop() {
+            Some(id) => {
+                self.arena[id] = slot;
+                id
+            }
+            None => {
+                self.arena.push(slot);
+                self.arena.len() - 1
             }
         }
+    }
 
-        root
+    /// Mark a node as no longer part of the tree, so its slot can be reused.
+    fn release(&mut self, id: NodeId) {
+        self.free.push(id);
     }
 
     /// Return the value of this node if this is a value node.
-    fn value(&self) -> Option<&T> {
-        match &self.contents {
+    fn value(&self, id: NodeId) -> Option<&T> {
+        match &self.arena[id].contents {
+            Contents::Leaf(value) => Some(value),
+            Contents::Branch(_) => None,
+        }
+    }
+
+    /// Return a mutable reference to the value of this node if this is a value node.
+    fn value_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        match &mut self.arena[id].contents {
             Contents::Leaf(value) => Some(value),
             Contents::Branch(_) => None,
         }
     }
 
     /// Return the branch of this node if this is a branch node.
-    fn branch(&self) -> Option<&Branch<T>> {
-        match &self.contents {
+    fn branch(&self, id: NodeId) -> Option<Branch> {
+        match &self.arena[id].contents {
             Contents::Leaf(_) => None,
-            Contents::Branch(branch) => Some(branch),
+            Contents::Branch(branch) => Some(*branch),
         }
     }
 
-    /// Return the leftmost grandchild of this node.
-    ///
-    /// The returned node will always be a leaf.
-    ///
-    /// Returns `self` if `self` is already a leaf.
-    fn leftmost_grandchild(&self) -> *const Self {
-        match &self.contents {
-            Contents::Leaf(_) => self as _,
-            Contents::Branch(branch) => branch.left.leftmost_grandchild(),
-        }
+    /// Return the parent of this node.
+    fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.arena[id].up
     }
 
-    /// Return the rightmost grandchild of this node.
-    ///
-    /// The returned node will always be a leaf.
-    ///
-    /// Returns `self` if `self` is already a leaf.
-    fn rightmost_grandchild(&self) -> *const Self {
-        match &self.contents {
-            Contents::Leaf(_) => self as _,
-            Contents::Branch(branch) => branch.right.rightmost_grandchild(),
+    /// Iterate over the leaves of this tree in left-to-right order.
+    pub fn leaves(&self) -> Leaves<'_, T> {
+        Leaves {
+            tree: self,
+            stack: vec![(self.root, 0)],
         }
     }
 
-    /// Return the parent of this node.
-    fn parent<'a>(&'a self) -> Option<&'a Self> {
-        // safe because we only ever access a node via the root, and without concurrency.
-        // if we have access to a node, its parent pointer is valid.
-        self.up.map(|ptr| unsafe { &*ptr })
+    /// Place a cursor on the leftmost leaf of this tree.
+    pub fn cursor(&mut self) -> LeafCursor<'_, T> {
+        let (id, depth) = self.outermost_leaf(self.root, 0, Side::Left);
+        LeafCursor {
+            tree: self,
+            id,
+            depth,
+        }
     }
 
-    /// Return `Some(true)` when this node is its parent's left branch.
-    ///
-    /// `None` when this node is the root.
-    fn is_left(&self) -> Option<bool> {
-        let parent = self.parent()?;
-        let left_child = &parent.branch().expect("parenthood implies branch").left;
-        Some(std::ptr::eq(self as _, &**left_child as _))
+    /// Descend from `id`, which is at `depth`, to its leftmost or rightmost leaf.
+    fn outermost_leaf(&self, mut id: NodeId, mut depth: usize, side: Side) -> (NodeId, usize) {
+        while let Some(branch) = self.branch(id) {
+            id = branch.child(side);
+            depth += 1;
+        }
+        (id, depth)
     }
 
-    /// Return `Some(true)` when this node is its parent's right branch.
-    ///
-    /// `None` when this node is the root.
-    fn is_right(&self) -> Option<bool> {
-        self.is_left().map(|left| !left)
+    /// Check that all legs of this node have valid up pointers
+    #[cfg(test)]
+    fn check_legs(&self, id: NodeId) {
+        if let Some(branch) = self.branch(id) {
+            assert_eq!(self.parent(branch.left), Some(id));
+            assert_eq!(self.parent(branch.right), Some(id));
+            self.check_legs(branch.left);
+            self.check_legs(branch.right);
+        }
     }
+}
 
-    /// Return the parent or grandparent of the next left-most node.
-    ///
-    /// This always produces a branch node of depth less than this node's.
-    /// If this node is on the right, this produces the node's imediate parent.
-    /// Otherwise, it will step upward arbitrarily far, seeking an ancestor
-    /// whose direct descendent is on the right. It then returns that ancestor.
-    fn left_parent<'a>(&'a self) -> Option<&'a Node<T>> {
-        let parent = self.parent()?;
-        if self.is_right()? {
-            Some(parent)
-        } else {
-            parent.left_parent()
+/// A leaf of a tree, with its depth: the number of pairs which enclose it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leaf<'a, T> {
+    pub value: &'a T,
+    pub depth: usize,
+}
+
+/// Iterator over the leaves of a tree in left-to-right order.
+///
+/// Produced by [`Node::leaves`].
+pub struct Leaves<'a, T> {
+    tree: &'a Node<T>,
+    /// Subtrees yet to be visited, with their depths; the next is on top.
+    stack: Vec<(NodeId, usize)>,
+}
+
+impl<'a, T> Leaves<'a, T> {
+    /// Advance to the next leaf, producing its id and depth.
+    fn next_id(&mut self) -> Option<(NodeId, usize)> {
+        let (mut id, mut depth) = self.stack.pop()?;
+        while let Some(branch) = self.tree.branch(id) {
+            self.stack.push((branch.right, depth + 1));
+            id = branch.left;
+            depth += 1;
         }
+        Some((id, depth))
     }
+}
 
-    /// Return the parent or grandparent of the next right-most node.
-    ///
-    /// This always produces a branch node of depth less than this node's.
-    /// If this node is on the left, this produces the node's immediate parent.
-    /// Otherwise, it will step upwards arbitrarily far, seeking an ancestor
-    /// whose direct descendent is on the left. It then returns that ancestor.
-    fn right_parent<'a>(&'a self) -> Option<&'a Node<T>> {
-        let parent = self.parent()?;
-
-        if self.is_left()? {
-            Some(&parent)
-        } else {
-            parent.right_parent()
-        }
+impl<'a, T> Iterator for Leaves<'a, T> {
+    type Item = Leaf<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, depth) = self.next_id()?;
+        let value = self.tree.value(id).expect("leaves only produces leaves");
+        Some(Leaf { value, depth })
+    }
+}
+
+/// A position among the leaves of a tree, which can move to neighboring leaves
+/// and modify the leaf it's on.
+///
+/// Produced by [`Node::cursor`].
+pub struct LeafCursor<'a, T> {
+    tree: &'a mut Node<T>,
+    id: NodeId,
+    depth: usize,
+}
+
+impl<'a, T> LeafCursor<'a, T> {
+    pub fn value(&self) -> &T {
+        self.tree
+            .value(self.id)
+            .expect("cursors only rest on leaves")
     }
 
-    /// Return the next leaf left from this node.
-    fn left_leaf(&self) -> Option<*const Self> {
-        let parent = self.left_parent()?;
-        Some(parent.branch()?.left.rightmost_grandchild())
+    pub fn value_mut(&mut self) -> &mut T {
+        self.tree
+            .value_mut(self.id)
+            .expect("cursors only rest on leaves")
     }
 
-    /// Return the next leaf right from this node.
-    fn right_leaf(&self) -> Option<*const Self> {
-        let parent = self.right_parent()?;
-        Some(parent.branch()?.right.leftmost_grandchild())
+    /// The number of pairs which enclose the current leaf.
+    pub fn depth(&self) -> usize {
+        self.depth
     }
 
-    /// Check that all legs of this node have valid up pointers
-    #[cfg(test)]
-    fn check_legs(&self) {
-        if let Some(branch) = self.branch() {
-            assert!(std::ptr::eq(self as _, branch.left.parent().unwrap() as _));
-            assert!(std::ptr::eq(self as _, branch.right.parent().unwrap() as _));
-            branch.left.check_legs();
-            branch.right.check_legs();
-        }
+    /// Move to the next leaf to the left, if there is one.
+    pub fn move_left(&mut self) -> bool {
+        self.step(Side::Left)
+    }
+
+    /// Move to the next leaf to the right, if there is one.
+    pub fn move_right(&mut self) -> bool {
+        self.step(Side::Right)
+    }
+
+    /// Move to the neighboring leaf on `side`.
+    ///
+    /// Climbs until the path can turn towards `side`, then descends to the leaf
+    /// nearest the starting point. Stays put if there is no such leaf.
+    fn step(&mut self, side: Side) -> bool {
+        let mut id = self.id;
+        let mut depth = self.depth;
+        let sibling = loop {
+            let parent = match self.tree.parent(id) {
+                Some(parent) => parent,
+                None => return false,
+            };
+            depth -= 1;
+            let branch = self.tree.branch(parent).expect("parents are branches");
+            if branch.child(side.opposite()) == id {
+                break branch.child(side);
+            }
+            id = parent;
+        };
+        let (id, depth) = self
+            .tree
+            .outermost_leaf(sibling, depth + 1, side.opposite());
+        self.id = id;
+        self.depth = depth;
+        true
     }
 }
 
-type SnailfishNumber = Node<u8>;
+pub type SnailfishNumber = Node<u8>;
 
-impl SnailfishNumber {
-    pub fn add(self: Box<Self>, other: Box<Self>) -> Box<Self> {
-        let sfn = SnailfishNumber::new_pair(self, other);
+impl Add for Box<SnailfishNumber> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut sfn = SnailfishNumber::new_pair(self, other);
         sfn.reduce();
         sfn
     }
+}
 
-    fn reduce(self: &Box<Self>) {
-        let mut operation_applied = true;
-        while operation_applied {
-            operation_applied = false;
-            for operation in [
-                Box::new(Self::try_explode) as Box<dyn Fn(&Box<Self>) -> bool>,
-                Box::new(Self::try_split),
-            ] {
-                operation_applied |= operation(self);
-                if operation_applied {
-                    break;
-                }
-            }
-        }
+impl Sum for Box<SnailfishNumber> {
+    /// Add every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty: snailfish numbers have no additive identity.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+            .expect("cannot sum an empty sequence of snailfish numbers")
     }
+}
 
-    fn try_explode(self: &Box<Self>) -> bool {
-        self.explode_inner(0)
+impl<'a> Sum<&'a Box<SnailfishNumber>> for Box<SnailfishNumber> {
+    /// Add a copy of every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty.
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
     }
+}
 
-    fn explode_inner(&self, depth: usize) -> bool {
-        // left branch first
-        if let Some(branch) = self.branch() {
-            if branch.left.explode_inner(depth + 1) {
-                return true;
-            }
-        }
+impl SnailfishNumber {
+    fn reduce(&mut self) {
+        while self.reduce_once().is_some() {}
+    }
 
-        // oops, what if it's time for _us_ to explode?
-        let mut did_explode = false;
-        if depth == 4 {
-            if let Some(branch) = self.branch() {
-                did_explode = true;
-                debug_assert!(
-                    branch.left.value().is_some() && branch.right.value().is_some(),
-                    "problem statement promises that exploding values are always simple values"
-                );
-
-                if let Some(left) = self.left_leaf() {
-                    // left reference must always be valid
-                    let left = unsafe { (&*left).force_mutable() };
-                    let new_value = *branch.left.value().expect(
-                        "problem statement promises that explosions only hit simple numbers",
-                    ) + *left.value().expect("left_leaf always produces a leaf");
-                    left.contents = Contents::Leaf(new_value);
-                }
-                if let Some(right) = self.right_leaf() {
-                    // right reference must always be valid
-                    let right = unsafe { (&*right).force_mutable() };
-                    let new_value = *branch.right.value().expect(
-                        "problem statement promises that explosions only hit simple numbers",
-                    ) + *right.value().expect("right_leaf always produces a leaf");
-                    right.contents = Contents::Leaf(new_value);
-                }
-            }
-            if did_explode {
-                unsafe { self.force_mutable() }.contents = Contents::Leaf(0);
-                return true;
-            }
-        }
+    /// Apply the first reduction action which applies, if any.
+    ///
+    /// Returns the action and the index of the leaf it fired on.
+    fn reduce_once(&mut self) -> Option<(Action, usize)> {
+        self.try_explode()
+            .map(|leaf| (Action::Explode, leaf))
+            .or_else(|| self.try_split().map(|leaf| (Action::Split, leaf)))
+    }
 
-        // right branch
-        self.branch()
-            .map(|branch| branch.right.explode_inner(depth + 1))
-            .unwrap_or_default()
+    /// Reduce this number, recording every action taken.
+    pub fn reduce_with_trace(&mut self) -> Vec<TraceStep> {
+        trace_reduction(self, Self::reduce_once)
     }
 
-    fn try_split(self: &Box<Self>) -> bool {
-        // left branch
-        if let Some(branch) = self.branch() {
-            if branch.left.try_split() {
-                return true;
+    /// Explode the leftmost pair nested inside four pairs.
+    ///
+    /// Reduced numbers are never nested more deeply than that, so the first leaf
+    /// at depth 5 is the left half of the exploding pair, and the next leaf is its
+    /// right half. Their values are added to their neighbors in leaf order.
+    ///
+    /// Returns the index of the left leaf.
+    fn try_explode(&mut self) -> Option<usize> {
+        let mut leaves = self.leaves();
+        let mut prior = None;
+        let mut index = 0;
+        let left = loop {
+            match leaves.next_id()? {
+                (id, depth) if depth > 4 => break id,
+                (id, _) => {
+                    prior = Some(id);
+                    index += 1;
+                }
             }
+        };
+        let (right, _) = leaves
+            .next_id()
+            .expect("problem statement promises that explosions only hit simple numbers");
+        let subsequent = leaves.next_id().map(|(id, _)| id);
+
+        let pair = self.parent(left).expect("deep leaves have parents");
+        debug_assert_eq!(
+            self.branch(pair),
+            Some(Branch { left, right }),
+            "problem statement promises that explosions only hit simple numbers"
+        );
+
+        let left_value = *self.value(left).expect("leaves only produces leaves");
+        let right_value = *self.value(right).expect("leaves only produces leaves");
+        if let Some(prior) = prior {
+            *self.value_mut(prior).expect("leaves only produces leaves") += left_value;
+        }
+        if let Some(subsequent) = subsequent {
+            *self
+                .value_mut(subsequent)
+                .expect("leaves only produces leaves") += right_value;
         }
 
-        // try this value
-        let value = self.value().map(|ref_leaf| *ref_leaf);
-        if let Some(value) = value {
+        self.release(left);
+        self.release(right);
+        self.arena[pair].contents = Contents::Leaf(0);
+        Some(index)
+    }
+
+    /// Split the leftmost value of 10 or more, returning the index of its leaf.
+    fn try_split(&mut self) -> Option<usize> {
+        let mut leaves = self.leaves();
+        let mut index = 0;
+        let (id, value) = loop {
+            let (id, _) = leaves.next_id()?;
+            let value = *self.value(id).expect("leaves only produces leaves");
             if value >= 10 {
-                let left = Self::new_value(value / 2, self);
-                let right = Self::new_value(value / 2 + value % 2, self);
-                unsafe { self.force_mutable() }.contents = Contents::Branch(Branch { left, right });
-                return true;
+                break (id, value);
             }
-        }
+            index += 1;
+        };
 
-        self.branch()
-            .map(|branch| branch.right.try_split())
-            .unwrap_or_default()
+        let left = self.alloc(Contents::Leaf(value / 2), Some(id));
+        let right = self.alloc(Contents::Leaf(value / 2 + value % 2), Some(id));
+        self.arena[id].contents = Contents::Branch(Branch { left, right });
+        Some(index)
     }
 
-    fn magnitude(&self) -> u64 {
-        match &self.contents {
+    pub fn magnitude(&self) -> u64 {
+        self.magnitude_inner(self.root)
+    }
+
+    fn magnitude_inner(&self, id: NodeId) -> u64 {
+        match &self.arena[id].contents {
             Contents::Leaf(value) => *value as u64,
             Contents::Branch(branch) => {
-                (branch.left.magnitude() * 3) + (branch.right.magnitude() * 2)
+                (self.magnitude_inner(branch.left) * 3) + (self.magnitude_inner(branch.right) * 2)
             }
         }
     }
 }
 
-impl FromStr for Box<SnailfishNumber> {
+impl<T: FromStr> FromStr for Box<Node<T>> {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parser::SnailfishParser::new()
-            .parse(s)
-            .map_err(|err| err.map_token(|t| t.to_string()).into())
+        check_brackets(s)?;
+        Ok(parser::parse(s)?)
+    }
+}
+
+/// A reduction action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display)]
+#[display(style = "lowercase")]
+pub enum Action {
+    Explode,
+    Split,
+}
+
+/// A single action taken while reducing a number, for comparing reductions step by step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub action: Action,
+    /// The index, in left-to-right order, of the leaf the action fired on.
+    ///
+    /// Explosions fire on the left leaf of the exploding pair.
+    pub leaf: usize,
+    pub before: String,
+    pub after: String,
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at leaf {}: {} -> {}",
+            self.action, self.leaf, self.before, self.after
+        )
+    }
+}
+
+/// Apply `reduce_once` to `number` until it no longer applies, recording each action.
+fn trace_reduction<S: fmt::Display>(
+    number: &mut S,
+    reduce_once: impl Fn(&mut S) -> Option<(Action, usize)>,
+) -> Vec<TraceStep> {
+    let mut trace = Vec::new();
+    loop {
+        let before = number.to_string();
+        match reduce_once(number) {
+            Some((action, leaf)) => trace.push(TraceStep {
+                action,
+                leaf,
+                before,
+                after: number.to_string(),
+            }),
+            None => return trace,
+        }
+    }
+}
+
+/// Operations every snailfish number representation supports.
+pub trait Snailfish:
+    Sized + Clone + Send + Sync + FromStr<Err = Error> + Add<Output = Self> + Sum
+{
+    fn magnitude(&self) -> u64;
+}
+
+impl Snailfish for Box<SnailfishNumber> {
+    fn magnitude(&self) -> u64 {
+        SnailfishNumber::magnitude(self)
+    }
+}
+
+/// Which snailfish number representation to compute with.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Backend {
+    /// A binary tree of pairs, stored in an arena.
+    Tree,
+    /// A flat list of values, each annotated with its depth.
+    #[default]
+    List,
+    /// An immutable tree sharing structure between states, navigated by zipper.
+    Zipper,
+}
+
+/// Magnitude of the sum of every number, in order.
+pub fn sum_magnitude<S: Snailfish>(numbers: impl IntoIterator<Item = S>) -> Option<u64> {
+    numbers
+        .into_iter()
+        .reduce(Add::add)
+        .map(|sum| sum.magnitude())
+}
+
+/// The greatest magnitude of any reduced snailfish number: sixteen 9s, each nested in four pairs.
+const MAX_MAGNITUDE: u64 = 9 * 5_u64.pow(4);
+
+/// Find the greatest magnitude of the sum of any two distinct numbers, in either order.
+///
+/// Each addition is independent, so with the `parallelism` feature they're distributed
+/// across threads, sharing a running maximum.
+///
+/// No single addition can be skipped in advance: explodes and splits can raise a sum's
+/// magnitude far above `3 * a + 2 * b`, so its operands' magnitudes don't bound it. The only
+/// bound is the one every reduced number obeys, so the search stops once some sum reaches that.
+pub fn max_pairwise_magnitude<S: Snailfish>(numbers: &[S]) -> Option<u64> {
+    let count = numbers.len();
+    if count < 2 {
+        return None;
+    }
+    #[cfg(not(feature = "parallelism"))]
+    let pairs = (0..count).flat_map(|a| (0..count).map(move |b| (a, b)));
+    #[cfg(feature = "parallelism")]
+    let pairs = (0..count)
+        .into_par_iter()
+        .flat_map_iter(|a| (0..count).map(move |b| (a, b)));
+
+    let best = AtomicU64::new(0);
+    // `Err` only stops the search early; `best` holds the answer either way
+    let _ = pairs.filter(|(a, b)| a != b).try_for_each(|(a, b)| {
+        let magnitude = (numbers[a].clone() + numbers[b].clone()).magnitude();
+        if best.fetch_max(magnitude, Ordering::Relaxed).max(magnitude) >= MAX_MAGNITUDE {
+            return Err(());
+        }
+        Ok(())
+    });
+    Some(best.into_inner())
+}
+
+fn solve<S: Snailfish>(input: &str) -> Result<u64, Error> {
+    sum_magnitude(parse_str::<S>(input)?).ok_or(Error::NoSolution)
+}
+
+fn solve_pairwise<S: Snailfish>(input: &str) -> Result<u64, Error> {
+    let numbers: Vec<S> = parse_str(input)?.collect();
+    max_pairwise_magnitude(&numbers).ok_or(Error::NoSolution)
+}
+
+pub fn solve_part1(input: &str) -> Result<u64, Error> {
+    solve_part1_with(input, Backend::default())
+}
+
+pub fn solve_part1_with(input: &str, backend: Backend) -> Result<u64, Error> {
+    match backend {
+        Backend::Tree => solve::<Box<SnailfishNumber>>(input),
+        Backend::List => solve::<list_impl::SnailfishNumber>(input),
+        Backend::Zipper => solve::<zipper_impl::SnailfishNumber>(input),
+    }
+}
+
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
+    solve_part2_with(input, Backend::default())
+}
+
+pub fn solve_part2_with(input: &str, backend: Backend) -> Result<u64, Error> {
+    match backend {
+        Backend::Tree => solve_pairwise::<Box<SnailfishNumber>>(input),
+        Backend::List => solve_pairwise::<list_impl::SnailfishNumber>(input),
+        Backend::Zipper => solve_pairwise::<zipper_impl::SnailfishNumber>(input),
     }
 }
 
-// known wrong, too low: 1094
-// known wrong, too low: 2972
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let sum = parse::<Box<SnailfishNumber>>(input)?
-        .reduce(|acc, item| acc.add(item))
-        .ok_or(Error::NoSolution)?;
-    println!("magnitude of snailfish sum: {}", sum.magnitude());
-    Ok(())
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    part1_with(input, Backend::default())
+}
+
+pub fn part1_with(input: &Path, backend: Backend) -> Result<u64, Error> {
+    solve_part1_with(&std::fs::read_to_string(input)?, backend)
 }
 
-pub fn part2(_input: &Path) -> Result<(), Error> {
-    unimplemented!("the list-based implementation is much faster, so did part2 there")
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    part2_with(input, Backend::default())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("parse error")]
-    ParseError(#[from] lalrpop_util::ParseError<usize, String, &'static str>),
-    #[error("no solution found")]
-    NoSolution,
-    #[cfg(feature = "list_impl")]
-    #[error("failed to parse")]
-    ListParseError,
+pub fn part2_with(input: &Path, backend: Backend) -> Result<u64, Error> {
+    solve_part2_with(&std::fs::read_to_string(input)?, backend)
 }
 
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("invalid snailfish number: {0}")]
+        Syntax(#[from] SyntaxError),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aoclib::input::parse_str;
     use rstest::rstest;
 
     fn parse(s: &str) -> Box<SnailfishNumber> {
@@ -379,11 +812,146 @@ mod tests {
     #[test]
     fn simple_addition_example() {
         assert_eq!(
-            parse("[1,2]").add(parse("[[3,4],5]")),
+            parse("[1,2]") + parse("[[3,4],5]"),
             parse("[[1,2],[[3,4],5]]")
         );
     }
 
+    #[rstest]
+    #[case("7")]
+    #[case("[1,2]")]
+    #[case("[[1,2],3]")]
+    #[case("[9,[8,7]]")]
+    #[case("[[1,9],[8,5]]")]
+    #[case("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]")]
+    #[case("[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]")]
+    #[case("[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]")]
+    fn round_trip(#[case] input: &str) {
+        assert_eq!(parse(input).to_string(), input);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case("7")]
+    #[case("[1,2]")]
+    #[case("[[1,2],[[3,4],5]]")]
+    #[case("[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]")]
+    fn json_round_trip(#[case] input: &str) {
+        let sfn = parse(input);
+        assert_eq!(serde_json::to_string(&sfn).unwrap(), input);
+        assert_eq!(
+            serde_json::from_str::<Box<SnailfishNumber>>(input).unwrap(),
+            sfn
+        );
+
+        let value = serde_json::Value::from(&*sfn);
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(input).unwrap()
+        );
+        assert_eq!(Box::<SnailfishNumber>::try_from(value).unwrap(), sfn);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case("[1,2,3]")]
+    #[case("[[1],2]")]
+    #[case("[1,-2]")]
+    #[case("[1,256]")]
+    #[case("{\"left\":1,\"right\":2}")]
+    fn json_invalid(#[case] input: &str) {
+        assert!(serde_json::from_str::<Box<SnailfishNumber>>(input).is_err());
+    }
+
+    #[rstest]
+    #[case("[1, 2]", "[1,2]")]
+    #[case(" [ [1,2] ,\t3 ] ", "[[1,2],3]")]
+    #[case("[[1,9],\n[8,5]]", "[[1,9],[8,5]]")]
+    fn whitespace_tolerated(#[case] input: &str, #[case] expect: &str) {
+        assert_eq!(parse(input).to_string(), expect);
+    }
+
+    #[test]
+    fn other_leaf_types() {
+        let wide: Box<Node<u32>> = "[1,[256,3]]".parse().unwrap();
+        assert_eq!(wide.to_string(), "[1,[256,3]]");
+        assert_eq!(
+            wide.leaves().map(|leaf| *leaf.value).collect::<Vec<_>>(),
+            [1, 256, 3]
+        );
+    }
+
+    #[rstest]
+    #[case("[[1,2],3", SyntaxError::UnclosedBracket { offset: 0 })]
+    #[case("[1,2]]", SyntaxError::UnmatchedBracket { offset: 5 })]
+    #[case("[1;2]", SyntaxError::InvalidCharacter { offset: 2, found: ';' })]
+    #[case("[1,256]", SyntaxError::ValueOutOfRange { offset: 3, value: "256".into() })]
+    #[case(
+        "[1,2,3]",
+        SyntaxError::UnexpectedToken { offset: 4, found: ",".into(), expected: vec!["']'".into()] }
+    )]
+    #[case(
+        "[1 2]",
+        SyntaxError::UnexpectedToken { offset: 3, found: "2".into(), expected: vec!["','".into()] }
+    )]
+    #[case("[1,2] 3", SyntaxError::TrailingInput { offset: 6, found: "3".into() })]
+    fn syntax_errors(#[case] input: &str, #[case] expect: SyntaxError) {
+        match input.parse::<Box<SnailfishNumber>>() {
+            Err(Error::Syntax(err)) => assert_eq!(err, expect),
+            otherwise => panic!("expected a syntax error; got {:?}", otherwise),
+        }
+    }
+
+    #[rstest]
+    #[case("7", &[(7, 0)])]
+    #[case("[1,2]", &[(1, 1), (2, 1)])]
+    #[case("[[1,2],[[3,4],5]]", &[(1, 2), (2, 2), (3, 3), (4, 3), (5, 2)])]
+    #[case(
+        "[[[[[9,8],1],2],3],4]",
+        &[(9, 5), (8, 5), (1, 4), (2, 3), (3, 2), (4, 1)]
+    )]
+    fn leaves(#[case] input: &str, #[case] expect: &[(u8, usize)]) {
+        let sfn = parse(input);
+        let leaves: Vec<_> = sfn.leaves().map(|leaf| (*leaf.value, leaf.depth)).collect();
+        assert_eq!(leaves, expect);
+    }
+
+    #[rstest]
+    #[case("7")]
+    #[case("[1,2]")]
+    #[case("[[1,2],[[3,4],5]]")]
+    #[case("[[[[[9,8],1],2],3],4]")]
+    #[case("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]")]
+    fn cursor_visits_leaves(#[case] input: &str) {
+        let mut sfn = parse(input);
+        let expect: Vec<_> = sfn.leaves().map(|leaf| (*leaf.value, leaf.depth)).collect();
+
+        let mut cursor = sfn.cursor();
+        let mut rightward = vec![(*cursor.value(), cursor.depth())];
+        while cursor.move_right() {
+            rightward.push((*cursor.value(), cursor.depth()));
+        }
+        assert_eq!(rightward, expect);
+
+        let mut leftward = vec![(*cursor.value(), cursor.depth())];
+        while cursor.move_left() {
+            leftward.push((*cursor.value(), cursor.depth()));
+        }
+        leftward.reverse();
+        assert_eq!(leftward, expect);
+    }
+
+    #[test]
+    fn cursor_modifies_leaves() {
+        let mut sfn = parse("[[1,2],[[3,4],5]]");
+        let mut cursor = sfn.cursor();
+        *cursor.value_mut() *= 2;
+        while cursor.move_right() {
+            *cursor.value_mut() *= 2;
+        }
+        assert_eq!(sfn.to_string(), "[[2,4],[[6,8],10]]");
+    }
+
     #[rstest]
     #[case("[[[[[9,8],1],2],3],4]")]
     #[case("[7,[6,[5,[4,[3,2]]]]]")]
@@ -392,7 +960,23 @@ mod tests {
     #[case("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")]
     fn test_parent_links(#[case] input: &str) {
         let sfn = parse(input);
-        sfn.check_legs();
+        sfn.check_legs(sfn.root);
+    }
+
+    #[rstest]
+    #[case("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]")]
+    #[case("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")]
+    fn clone_is_independent(#[case] input: &str) {
+        let original = parse(input);
+        let mut copy = original.clone();
+        copy.check_legs(copy.root);
+        assert_eq!(copy, original);
+
+        copy.reduce();
+        *copy.cursor().value_mut() += 1;
+        copy.check_legs(copy.root);
+        assert_eq!(original.to_string(), input);
+        assert_ne!(copy, original);
     }
 
     #[rstest]
@@ -409,8 +993,8 @@ mod tests {
         "[[[[7,7],[7,0]],[[7,8],[8,7]]],[[[6,7],[12,0]],[[7,7],[17,0]]]]"
     )]
     fn explode(#[case] input: &str, #[case] expect: &str) {
-        let sfn = parse(input);
-        assert!(sfn.try_explode());
+        let mut sfn = parse(input);
+        assert!(sfn.try_explode().is_some());
         assert_eq!(sfn, parse(expect));
     }
 
@@ -419,15 +1003,36 @@ mod tests {
     #[case("11", "[5,6]")]
     #[case("12", "[6,6]")]
     fn split(#[case] input: &str, #[case] expect: &str) {
-        let sfn = parse(input);
-        assert!(sfn.try_split());
+        let mut sfn = parse(input);
+        assert!(sfn.try_split().is_some());
         assert_eq!(sfn, parse(expect));
     }
 
+    #[test]
+    fn reduction_trace_example() {
+        let mut sfn = parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        let trace: Vec<_> = sfn
+            .reduce_with_trace()
+            .into_iter()
+            .map(|step| (step.action, step.leaf, step.after))
+            .collect();
+        assert_eq!(
+            trace,
+            [
+                (Action::Explode, 0, "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]"),
+                (Action::Explode, 4, "[[[[0,7],4],[15,[0,13]]],[1,1]]"),
+                (Action::Split, 3, "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]"),
+                (Action::Split, 6, "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]"),
+                (Action::Explode, 6, "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"),
+            ]
+            .map(|(action, leaf, after)| (action, leaf, after.to_string()))
+        );
+    }
+
     #[test]
     fn multistage_addition_example() {
         assert_eq!(
-            parse("[[[[4,3],4],4],[7,[[8,4],9]]]").add(parse("[1,1]")),
+            parse("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse("[1,1]"),
             parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
         )
     }
@@ -464,8 +1069,7 @@ mod tests {
         assert_eq!(
             parse_str::<Box<SnailfishNumber>>(input)
                 .unwrap()
-                .reduce(|acc, item| acc.add(item))
-                .unwrap(),
+                .sum::<Box<SnailfishNumber>>(),
             parse(expect)
         );
     }
@@ -484,13 +1088,7 @@ mod tests {
         assert_eq!(parse(input).magnitude(), expect);
     }
 
-    #[test]
-    fn example_assignment() {
-        let expect = parse("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
-        const EXPECT_MAGNITUDE: u64 = 4140;
-        assert_eq!(expect.magnitude(), EXPECT_MAGNITUDE);
-
-        let assignment = "
+    const ASSIGNMENT: &str = "
 [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
 [[[5,[2,8]],4],[5,[[9,9],0]]]
 [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
@@ -501,16 +1099,30 @@ mod tests {
 [[9,3],[[9,9],[6,[4,9]]]]
 [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
 [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
-        "
-        .trim();
-        let sum = parse_str::<Box<SnailfishNumber>>(assignment)
+    ";
+
+    #[test]
+    fn example_assignment() {
+        let expect = parse("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
+        const EXPECT_MAGNITUDE: u64 = 4140;
+        assert_eq!(expect.magnitude(), EXPECT_MAGNITUDE);
+
+        let sum = parse_str::<Box<SnailfishNumber>>(ASSIGNMENT.trim())
             .unwrap()
-            .reduce(|acc, item| acc.add(item))
-            .unwrap();
+            .sum::<Box<SnailfishNumber>>();
         assert_eq!(sum, expect);
         assert_eq!(sum.magnitude(), EXPECT_MAGNITUDE);
     }
 
+    #[rstest]
+    #[case(Backend::Tree)]
+    #[case(Backend::List)]
+    #[case(Backend::Zipper)]
+    fn example_assignment_backends(#[case] backend: Backend) {
+        assert_eq!(solve_part1_with(ASSIGNMENT, backend).unwrap(), 4140);
+        assert_eq!(solve_part2_with(ASSIGNMENT, backend).unwrap(), 3993);
+    }
+
     #[rstest]
     #[case(
         "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]",
@@ -558,6 +1170,16 @@ mod tests {
         "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
     )]
     fn constructed_cases(#[case] acc: &str, #[case] elem: &str, #[case] expect: &str) {
-        assert_eq!(parse(acc).add(parse(elem)), parse(expect));
+        assert_eq!(parse(acc) + parse(elem), parse(expect));
+    }
+
+    #[test]
+    fn max_magnitude_is_reachable() {
+        let nines = parse("[[[9,9],[9,9]],[[9,9],[9,9]]]");
+        assert_eq!((nines.clone() + nines.clone()).magnitude(), MAX_MAGNITUDE);
+
+        let numbers = [parse("[1,2]"), nines.clone(), parse("[[3,4],5]"), nines];
+        assert_eq!(max_pairwise_magnitude(&numbers), Some(MAX_MAGNITUDE));
+        assert_eq!(max_pairwise_magnitude(&numbers[..1]), None);
     }
 }
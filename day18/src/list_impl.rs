@@ -1,7 +1,9 @@
-use crate::Error;
-use aoclib::parse;
-use itertools::Itertools;
-use std::{path::Path, str::FromStr};
+use crate::{
+    check_brackets, trace_reduction, Action, Error, NodeId, Snailfish, SyntaxError, TraceStep,
+};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::{fmt, iter::Sum, num::TryFromIntError, ops::Add, str::FromStr};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Position {
@@ -17,7 +19,7 @@ struct Item {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct SnailfishNumber {
+pub struct SnailfishNumber {
     items: Vec<Item>,
 }
 
@@ -25,54 +27,124 @@ impl FromStr for SnailfishNumber {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut depth = 0;
-        let mut number_start_idx = None;
-        let mut items = Vec::new();
+        check_brackets(s)?;
 
-        for (idx, ch) in s.char_indices() {
-            match ch {
-                '[' => depth += 1,
-                '0'..='9' => {
-                    if number_start_idx.is_none() {
-                        number_start_idx = Some(idx);
-                    }
+        // the position of the element being parsed within each open pair
+        let mut open = Vec::new();
+        let mut items = Vec::new();
+        // whether the previous token completed an element
+        let mut after_element = false;
+        // where the previous token ended
+        let mut last_end = 0;
+        let mut chars = s.char_indices().peekable();
+
+        while let Some((offset, ch)) = chars.next() {
+            if ch.is_whitespace() {
+                continue;
+            }
+            let mut end = offset + ch.len_utf8();
+            if ch.is_ascii_digit() {
+                while let Some((idx, _)) = chars.next_if(|(_, next)| next.is_ascii_digit()) {
+                    end = idx + 1;
+                }
+            } else if !matches!(ch, '[' | ',' | ']') {
+                return Err(SyntaxError::InvalidCharacter { offset, found: ch }.into());
+            }
+            let token = &s[offset..end];
+            last_end = end;
+            let depth = open.len() as u8;
+
+            match (ch, after_element, open.last_mut()) {
+                ('[', false, _) => open.push(Position::Left),
+                ('0'..='9', false, position) => {
+                    let value: u8 = token.parse().map_err(|_| SyntaxError::ValueOutOfRange {
+                        offset,
+                        value: token.to_string(),
+                    })?;
+                    items.push(Item {
+                        value: value.into(),
+                        depth,
+                        position: position.copied().unwrap_or(Position::Left),
+                    });
+                    after_element = true;
+                }
+                (',', true, Some(position @ Position::Left)) => {
+                    *position = Position::Right;
+                    after_element = false;
+                }
+                (']', true, Some(Position::Right)) => {
+                    open.pop();
                 }
-                ',' => {
-                    if let Some(number_start_idx) = number_start_idx {
-                        let value = s[number_start_idx..idx]
-                            .parse()
-                            .map_err(|_| Error::ListParseError)?;
-                        items.push(Item {
-                            value,
-                            depth,
-                            position: Position::Left,
-                        });
+                (_, true, None) => {
+                    return Err(SyntaxError::TrailingInput {
+                        offset,
+                        found: token.to_string(),
                     }
-                    number_start_idx = None;
+                    .into())
                 }
-                ']' => {
-                    if let Some(number_start_idx) = number_start_idx {
-                        let value = s[number_start_idx..idx]
-                            .parse()
-                            .map_err(|_| Error::ListParseError)?;
-                        items.push(Item {
-                            value,
-                            depth,
-                            position: Position::Right,
-                        });
+                (_, after_element, position) => {
+                    let expected = match (after_element, position) {
+                        (true, Some(Position::Left)) => vec!["','".into()],
+                        (true, _) => vec!["']'".into()],
+                        (false, _) => SyntaxError::element_expected(),
+                    };
+                    return Err(SyntaxError::UnexpectedToken {
+                        offset,
+                        found: token.to_string(),
+                        expected,
                     }
-                    number_start_idx = None;
-                    depth -= 1;
+                    .into());
                 }
-                _ => return Err(Error::ListParseError),
             }
         }
 
+        if !after_element {
+            return Err(SyntaxError::UnexpectedEnd {
+                offset: last_end,
+                expected: SyntaxError::element_expected(),
+            }
+            .into());
+        }
         Ok(SnailfishNumber { items })
     }
 }
 
-impl SnailfishNumber {
+impl fmt::Display for SnailfishNumber {
+    /// Reconstruct canonical notation from the items' depths.
+    ///
+    /// Before an item is written, enough pairs must be open to match its depth.
+    /// Writing an item completes the left half of the innermost open pair, or else
+    /// the whole pair, which in turn completes half of its parent.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the position expected next within each open pair
+        let mut open = Vec::new();
+        for item in &self.items {
+            while open.len() < item.depth as usize {
+                f.write_str("[")?;
+                open.push(Position::Left);
+            }
+            write!(f, "{}", item.value)?;
+            while let Some(position) = open.last_mut() {
+                match position {
+                    Position::Left => {
+                        *position = Position::Right;
+                        f.write_str(",")?;
+                        break;
+                    }
+                    Position::Right => {
+                        open.pop();
+                        f.write_str("]")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = Self;
+
     fn add(mut self, mut other: Self) -> Self {
         self.items.append(&mut other.items);
         for item in self.items.iter_mut() {
@@ -81,24 +153,208 @@ impl SnailfishNumber {
         self.reduce();
         self
     }
+}
+
+impl Sum for SnailfishNumber {
+    /// Add every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty: snailfish numbers have no additive identity.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+            .expect("cannot sum an empty sequence of snailfish numbers")
+    }
+}
 
-    fn reduce(&mut self) {
-        let mut operation_applied = true;
-        while operation_applied {
-            operation_applied = false;
-            for operation in [
-                Box::new(Self::try_explode) as Box<dyn Fn(&mut Self) -> bool>,
-                Box::new(Self::try_split),
-            ] {
-                operation_applied |= operation(self);
-                if operation_applied {
-                    break;
+impl<'a> Sum<&'a SnailfishNumber> for SnailfishNumber {
+    /// Add a copy of every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty.
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+impl From<&crate::SnailfishNumber> for SnailfishNumber {
+    fn from(tree: &crate::SnailfishNumber) -> Self {
+        fn push_items(
+            items: &mut Vec<Item>,
+            tree: &crate::SnailfishNumber,
+            id: NodeId,
+            depth: u8,
+            position: Position,
+        ) {
+            match tree.branch(id) {
+                Some(branch) => {
+                    push_items(items, tree, branch.left, depth + 1, Position::Left);
+                    push_items(items, tree, branch.right, depth + 1, Position::Right);
                 }
+                None => items.push(Item {
+                    value: (*tree.value(id).expect("non-branches are leaves")).into(),
+                    depth,
+                    position,
+                }),
             }
         }
+
+        let mut items = Vec::new();
+        push_items(&mut items, tree, tree.root, 0, Position::Left);
+        SnailfishNumber { items }
     }
+}
 
-    fn try_explode(&mut self) -> bool {
+impl TryFrom<&SnailfishNumber> for Box<crate::SnailfishNumber> {
+    type Error = TryFromIntError;
+
+    /// Rebuild the tree from the items' depths, as for `Display`.
+    ///
+    /// Fails if a value doesn't fit in a tree's leaf; reduced numbers always do.
+    fn try_from(number: &SnailfishNumber) -> Result<Self, Self::Error> {
+        // the elements collected so far for each open pair
+        let mut open: Vec<Vec<Self>> = Vec::new();
+        let mut root = None;
+        for item in &number.items {
+            while open.len() < item.depth as usize {
+                open.push(Vec::with_capacity(2));
+            }
+            let mut node = crate::SnailfishNumber::new_orphan_value(item.value.try_into()?);
+            loop {
+                match open.last_mut() {
+                    None => {
+                        root = Some(node);
+                        break;
+                    }
+                    Some(pair) => {
+                        pair.push(node);
+                        if pair.len() < 2 {
+                            break;
+                        }
+                        let right = pair.pop().expect("pair is full");
+                        let left = pair.pop().expect("pair is full");
+                        open.pop();
+                        node = crate::SnailfishNumber::new_pair(left, right);
+                    }
+                }
+            }
+        }
+        Ok(root.expect("snailfish numbers have at least one item"))
+    }
+}
+
+/// Snailfish notation is JSON: pairs are two-element arrays.
+#[cfg(feature = "serde")]
+impl From<&SnailfishNumber> for serde_json::Value {
+    /// Rebuild the nested arrays from the items' depths, as for `Display`.
+    fn from(number: &SnailfishNumber) -> Self {
+        use serde_json::Value;
+
+        // the elements collected so far for each open pair
+        let mut open: Vec<Vec<Value>> = Vec::new();
+        let mut root = None;
+        for item in &number.items {
+            while open.len() < item.depth as usize {
+                open.push(Vec::with_capacity(2));
+            }
+            let mut value = Value::from(item.value);
+            loop {
+                match open.last_mut() {
+                    None => {
+                        root = Some(value);
+                        break;
+                    }
+                    Some(pair) => {
+                        pair.push(value);
+                        if pair.len() < 2 {
+                            break;
+                        }
+                        value = Value::Array(open.pop().expect("pair is on the stack"));
+                    }
+                }
+            }
+        }
+        root.unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<serde_json::Value> for SnailfishNumber {
+    type Error = serde_json::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        fn push_items(
+            items: &mut Vec<Item>,
+            value: serde_json::Value,
+            depth: u8,
+            position: Position,
+        ) -> Result<(), serde_json::Error> {
+            match value {
+                serde_json::Value::Array(elements) => {
+                    let [left, right]: [serde_json::Value; 2] =
+                        elements.try_into().map_err(|elements: Vec<_>| {
+                            <serde_json::Error as serde::de::Error>::invalid_length(
+                                elements.len(),
+                                &"a pair",
+                            )
+                        })?;
+                    push_items(items, left, depth + 1, Position::Left)?;
+                    push_items(items, right, depth + 1, Position::Right)
+                }
+                value => {
+                    items.push(Item {
+                        value: serde_json::from_value(value)?,
+                        depth,
+                        position,
+                    });
+                    Ok(())
+                }
+            }
+        }
+
+        let mut items = Vec::new();
+        push_items(&mut items, value, 0, Position::Left)?;
+        Ok(SnailfishNumber { items })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SnailfishNumber {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_json::Value::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SnailfishNumber {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        value.try_into().map_err(serde::de::Error::custom)
+    }
+}
+
+impl SnailfishNumber {
+    fn reduce(&mut self) {
+        while self.reduce_once().is_some() {}
+    }
+
+    /// Apply the first reduction action which applies, if any.
+    ///
+    /// Returns the action and the index of the leaf it fired on.
+    fn reduce_once(&mut self) -> Option<(Action, usize)> {
+        self.try_explode()
+            .map(|leaf| (Action::Explode, leaf))
+            .or_else(|| self.try_split().map(|leaf| (Action::Split, leaf)))
+    }
+
+    /// Reduce this number, recording every action taken.
+    pub fn reduce_with_trace(&mut self) -> Vec<TraceStep> {
+        trace_reduction(self, Self::reduce_once)
+    }
+
+    /// Explode the leftmost pair nested inside four pairs, returning the index of its left leaf.
+    fn try_explode(&mut self) -> Option<usize> {
         if let Some(left_idx) = self
             .items
             .windows(2)
@@ -134,13 +390,14 @@ impl SnailfishNumber {
             self.items[left_idx].position = position;
             self.items.remove(right_idx);
 
-            true
+            Some(left_idx)
         } else {
-            false
+            None
         }
     }
 
-    fn try_split(&mut self) -> bool {
+    /// Split the leftmost value of 10 or more, returning the index of its leaf.
+    fn try_split(&mut self) -> Option<usize> {
         if let Some(idx) = self
             .items
             .iter()
@@ -163,13 +420,13 @@ impl SnailfishNumber {
 
             self.items.insert(idx + 1, new_item);
 
-            true
+            Some(idx)
         } else {
-            false
+            None
         }
     }
 
-    fn magnitude(&self) -> u64 {
+    pub fn magnitude(&self) -> u64 {
         let mut items = self.items.clone();
 
         for level in (1..=4).rev() {
@@ -211,25 +468,10 @@ impl SnailfishNumber {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let sum = parse::<SnailfishNumber>(input)?
-        .reduce(|acc, item| acc.add(item))
-        .ok_or(Error::NoSolution)?;
-    println!("magnitude of snailfish sum: {}", sum.magnitude());
-    Ok(())
-}
-
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let numbers: Vec<SnailfishNumber> = parse(input)?.collect();
-    let max_magnitude = numbers
-        .iter()
-        .cartesian_product(numbers.iter())
-        .filter(|(a, b)| a != b)
-        .flat_map(|(a, b)| [a.clone().add(b.clone()), b.clone().add(a.clone())].into_iter())
-        .max_by_key(|snailfish| snailfish.magnitude())
-        .ok_or(Error::NoSolution)?;
-    println!("max magnitude pairwise sum: {}", max_magnitude.magnitude());
-    Ok(())
+impl Snailfish for SnailfishNumber {
+    fn magnitude(&self) -> u64 {
+        SnailfishNumber::magnitude(self)
+    }
 }
 
 #[cfg(test)]
@@ -245,11 +487,72 @@ mod tests {
     #[test]
     fn simple_addition_example() {
         assert_eq!(
-            parse("[1,2]").add(parse("[[3,4],5]")),
+            parse("[1,2]") + parse("[[3,4],5]"),
             parse("[[1,2],[[3,4],5]]")
         );
     }
 
+    #[rstest]
+    #[case("[1,2]")]
+    #[case("[[1,2],3]")]
+    #[case("[9,[8,7]]")]
+    #[case("[[1,9],[8,5]]")]
+    #[case("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]")]
+    #[case("[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]")]
+    #[case("[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]")]
+    fn round_trip(#[case] input: &str) {
+        assert_eq!(parse(input).to_string(), input);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case("[1,2]")]
+    #[case("[[1,2],[[3,4],5]]")]
+    #[case("[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]")]
+    #[case("[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]")]
+    fn json_round_trip(#[case] input: &str) {
+        let sfn = parse(input);
+        assert_eq!(serde_json::to_string(&sfn).unwrap(), input);
+        assert_eq!(serde_json::from_str::<SnailfishNumber>(input).unwrap(), sfn);
+    }
+
+    #[rstest]
+    #[case("[1, 2]", "[1,2]")]
+    #[case(" [ [1,2] ,\t3 ] ", "[[1,2],3]")]
+    #[case("[[1,9],\n[8,5]]", "[[1,9],[8,5]]")]
+    fn whitespace_tolerated(#[case] input: &str, #[case] expect: &str) {
+        assert_eq!(parse(input).to_string(), expect);
+    }
+
+    #[rstest]
+    #[case("[[1,2],3", SyntaxError::UnclosedBracket { offset: 0 })]
+    #[case("[1,2]]", SyntaxError::UnmatchedBracket { offset: 5 })]
+    #[case("[1;2]", SyntaxError::InvalidCharacter { offset: 2, found: ';' })]
+    #[case("[1,256]", SyntaxError::ValueOutOfRange { offset: 3, value: "256".into() })]
+    #[case(
+        "[1,2,3]",
+        SyntaxError::UnexpectedToken { offset: 4, found: ",".into(), expected: vec!["']'".into()] }
+    )]
+    #[case(
+        "[1 2]",
+        SyntaxError::UnexpectedToken { offset: 3, found: "2".into(), expected: vec!["','".into()] }
+    )]
+    #[case("[1,2] 3", SyntaxError::TrailingInput { offset: 6, found: "3".into() })]
+    fn syntax_errors(#[case] input: &str, #[case] expect: SyntaxError) {
+        match input.parse::<SnailfishNumber>() {
+            Err(Error::Syntax(err)) => assert_eq!(err, expect),
+            otherwise => panic!("expected a syntax error; got {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn display_after_addition() {
+        assert_eq!(
+            (parse("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse("[1,1]")).to_string(),
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        );
+    }
+
     #[rstest]
     #[case("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]")]
     #[case("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]")]
@@ -261,7 +564,7 @@ mod tests {
     #[case("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]")]
     fn explode(#[case] input: &str, #[case] expect: &str) {
         let mut sfn = parse(input);
-        assert!(sfn.try_explode());
+        assert!(sfn.try_explode().is_some());
         assert_eq!(sfn, parse(expect));
     }
 
@@ -271,14 +574,14 @@ mod tests {
     #[case("[0,12]", "[0,[6,6]]")]
     fn split(#[case] input: &str, #[case] expect: &str) {
         let mut sfn = parse(input);
-        assert!(sfn.try_split());
+        assert!(sfn.try_split().is_some());
         assert_eq!(sfn, parse(expect));
     }
 
     #[test]
     fn multistage_addition_example() {
         assert_eq!(
-            parse("[[[[4,3],4],4],[7,[[8,4],9]]]").add(parse("[1,1]")),
+            parse("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse("[1,1]"),
             parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
         )
     }
@@ -315,8 +618,7 @@ mod tests {
         assert_eq!(
             parse_str::<SnailfishNumber>(input)
                 .unwrap()
-                .reduce(|acc, item| acc.add(item))
-                .unwrap(),
+                .sum::<SnailfishNumber>(),
             parse(expect)
         );
     }
@@ -335,6 +637,28 @@ mod tests {
         assert_eq!(parse(input).magnitude(), expect);
     }
 
+    #[test]
+    fn example_max_pairwise_magnitude() {
+        let numbers: Vec<SnailfishNumber> = parse_str(
+            "
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+            "
+            .trim(),
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(crate::max_pairwise_magnitude(&numbers), Some(3993));
+    }
+
     #[test]
     fn example_assignment() {
         let expect = parse("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
@@ -356,8 +680,7 @@ mod tests {
         .trim();
         let sum = parse_str::<SnailfishNumber>(assignment)
             .unwrap()
-            .reduce(|acc, item| acc.add(item))
-            .unwrap();
+            .sum::<SnailfishNumber>();
         assert_eq!(sum, expect);
         assert_eq!(sum.magnitude(), EXPECT_MAGNITUDE);
     }
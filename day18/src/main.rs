@@ -1,11 +1,8 @@
 use aoclib::{config::Config, website::get_input};
-#[cfg(feature = "list_impl")]
-use day18::list_impl::{part1, part2};
-#[cfg(not(feature = "list_impl"))]
-use day18::{part1, part2};
+use day18::{solve_part1_with, solve_part2_with, Backend};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
+use input_source::InputSource;
 use structopt::StructOpt;
 
 const YEAR: u32 = 2021;
@@ -13,9 +10,9 @@ const DAY: u8 = 18;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
+    /// input file, or "-" to read from stdin
     #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    input: Option<InputSource>,
 
     /// skip part 1
     #[structopt(long)]
@@ -24,19 +21,23 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// snailfish number representation: "tree", "list", or "zipper"
+    #[structopt(long, default_value)]
+    algo: Backend,
 }
 
 impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
+    fn input(&self) -> Result<InputSource> {
         match self.input {
             None => {
                 let config = Config::load()?;
                 // this does nothing if the input file already exists, but
                 // simplifies the workflow after cloning the repo on a new computer
                 get_input(&config, YEAR, DAY)?;
-                Ok(config.input_for(YEAR, DAY))
+                Ok(config.input_for(YEAR, DAY).into())
             }
-            Some(ref path) => Ok(path.clone()),
+            Some(ref source) => Ok(source.clone()),
         }
     }
 }
@@ -44,13 +45,13 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    let input = args.input()?.read()?;
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        println!("{}", solve_part1_with(&input, args.algo)?);
     }
     if args.part2 {
-        part2(&input_path)?;
+        println!("{}", solve_part2_with(&input, args.algo)?);
     }
     Ok(())
 }
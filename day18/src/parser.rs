@@ -0,0 +1,112 @@
+//! A recursive-descent parser for snailfish notation.
+//!
+//! ```text
+//! element := '[' element ',' element ']' | number
+//! number  := [0-9]+
+//! ```
+//!
+//! Whitespace between tokens is ignored.
+
+use crate::{Node, SyntaxError};
+use std::str::FromStr;
+
+/// Parse all of `input` as a single element whose regular values are `T`s.
+pub(crate) fn parse<T: FromStr>(input: &str) -> Result<Box<Node<T>>, SyntaxError> {
+    let mut parser = Parser {
+        input,
+        offset: 0,
+        last_end: 0,
+    };
+    let element = parser.element()?;
+    match parser.next_token()? {
+        None => Ok(element),
+        Some((offset, found)) => Err(SyntaxError::TrailingInput {
+            offset,
+            found: found.to_string(),
+        }),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Where to look for the next token.
+    offset: usize,
+    /// Where the previous token ended.
+    last_end: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Read the next token and its offset, or `None` at the end of the input.
+    fn next_token(&mut self) -> Result<Option<(usize, &'a str)>, SyntaxError> {
+        let rest = &self.input[self.offset..];
+        let (start, ch) = match rest.char_indices().find(|(_, ch)| !ch.is_whitespace()) {
+            Some((skipped, ch)) => (self.offset + skipped, ch),
+            None => {
+                self.offset = self.input.len();
+                return Ok(None);
+            }
+        };
+
+        let end = match ch {
+            '[' | ',' | ']' => start + 1,
+            '0'..='9' => self.input[start..]
+                .find(|ch: char| !ch.is_ascii_digit())
+                .map_or(self.input.len(), |len| start + len),
+            found => {
+                return Err(SyntaxError::InvalidCharacter {
+                    offset: start,
+                    found,
+                })
+            }
+        };
+        self.offset = end;
+        self.last_end = end;
+        Ok(Some((start, &self.input[start..end])))
+    }
+
+    /// Parse a pair or a regular value.
+    fn element<T: FromStr>(&mut self) -> Result<Box<Node<T>>, SyntaxError> {
+        match self.next_token()? {
+            Some((_, "[")) => {
+                let left = self.element()?;
+                self.expect(",")?;
+                let right = self.element()?;
+                self.expect("]")?;
+                Ok(Node::new_pair(left, right))
+            }
+            Some((offset, token)) if token.starts_with(|ch: char| ch.is_ascii_digit()) => token
+                .parse()
+                .map(Node::new_orphan_value)
+                .map_err(|_| SyntaxError::ValueOutOfRange {
+                    offset,
+                    value: token.to_string(),
+                }),
+            Some((offset, found)) => Err(SyntaxError::UnexpectedToken {
+                offset,
+                found: found.to_string(),
+                expected: SyntaxError::element_expected(),
+            }),
+            None => Err(SyntaxError::UnexpectedEnd {
+                offset: self.last_end,
+                expected: SyntaxError::element_expected(),
+            }),
+        }
+    }
+
+    /// Consume the punctuation `symbol`.
+    fn expect(&mut self, symbol: &str) -> Result<(), SyntaxError> {
+        let expected = || vec![format!("'{}'", symbol)];
+        match self.next_token()? {
+            Some((_, token)) if token == symbol => Ok(()),
+            Some((offset, found)) => Err(SyntaxError::UnexpectedToken {
+                offset,
+                found: found.to_string(),
+                expected: expected(),
+            }),
+            None => Err(SyntaxError::UnexpectedEnd {
+                offset: self.last_end,
+                expected: expected(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+/// Why some text is not a snailfish number.
+///
+/// Offsets count bytes from the start of the input.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SyntaxError {
+    #[error("'[' at byte {offset} is never closed")]
+    UnclosedBracket { offset: usize },
+    #[error("']' at byte {offset} closes nothing")]
+    UnmatchedBracket { offset: usize },
+    #[error("invalid character {found:?} at byte {offset}")]
+    InvalidCharacter { offset: usize, found: char },
+    #[error("unexpected {found:?} at byte {offset}; expected {}", .expected.join(" or "))]
+    UnexpectedToken {
+        offset: usize,
+        found: String,
+        expected: Vec<String>,
+    },
+    #[error("unexpected end of input at byte {offset}; expected {}", .expected.join(" or "))]
+    UnexpectedEnd {
+        offset: usize,
+        expected: Vec<String>,
+    },
+    #[error("unexpected {found:?} at byte {offset} after a complete number")]
+    TrailingInput { offset: usize, found: String },
+    #[error("value {value} at byte {offset} is out of range")]
+    ValueOutOfRange { offset: usize, value: String },
+}
+
+impl SyntaxError {
+    /// Byte offset into the input at which the problem was found.
+    pub fn offset(&self) -> usize {
+        match self {
+            SyntaxError::UnclosedBracket { offset }
+            | SyntaxError::UnmatchedBracket { offset }
+            | SyntaxError::InvalidCharacter { offset, .. }
+            | SyntaxError::UnexpectedToken { offset, .. }
+            | SyntaxError::UnexpectedEnd { offset, .. }
+            | SyntaxError::TrailingInput { offset, .. }
+            | SyntaxError::ValueOutOfRange { offset, .. } => *offset,
+        }
+    }
+
+    /// Tokens which may begin a number: a pair or a regular value.
+    pub(crate) fn element_expected() -> Vec<String> {
+        vec!["'['".into(), "a number".into()]
+    }
+}
+
+/// Ensure that every bracket in `input` is matched.
+///
+/// Imbalance is reported where the bracket is, instead of wherever the parser
+/// happens to notice that something is missing.
+pub(crate) fn check_brackets(input: &str) -> Result<(), SyntaxError> {
+    let mut open = Vec::new();
+    for (offset, ch) in input.char_indices() {
+        match ch {
+            '[' => open.push(offset),
+            ']' => {
+                open.pop().ok_or(SyntaxError::UnmatchedBracket { offset })?;
+            }
+            _ => {}
+        }
+    }
+    match open.pop() {
+        Some(offset) => Err(SyntaxError::UnclosedBracket { offset }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,416 @@
+//! An immutable snailfish number, navigated with a zipper.
+//!
+//! Every operation produces a new number which shares all untouched subtrees with
+//! its predecessor, so cloning is cheap and old states remain valid.
+
+use crate::{Error, Node, NodeId, Snailfish};
+use std::{fmt, iter::Sum, ops::Add, str::FromStr, sync::Arc};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Tree {
+    Leaf(u8),
+    Pair(Arc<Tree>, Arc<Tree>),
+}
+
+impl Tree {
+    fn pair(left: Arc<Tree>, right: Arc<Tree>) -> Arc<Tree> {
+        Arc::new(Tree::Pair(left, right))
+    }
+
+    fn magnitude(&self) -> u64 {
+        match self {
+            Tree::Leaf(value) => *value as u64,
+            Tree::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tree::Leaf(value) => write!(f, "{}", value),
+            Tree::Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+/// The way down from a pair, and the sibling which was not taken.
+#[derive(Debug, Clone)]
+enum Crumb {
+    Left { right: Arc<Tree> },
+    Right { left: Arc<Tree> },
+}
+
+/// A focus on some subtree, plus everything needed to rebuild the whole tree around it.
+#[derive(Debug, Clone)]
+struct Zipper {
+    focus: Arc<Tree>,
+    path: Vec<Crumb>,
+}
+
+impl Zipper {
+    fn new(root: Arc<Tree>) -> Self {
+        Zipper {
+            focus: root,
+            path: Vec::new(),
+        }
+    }
+
+    /// Number of pairs enclosing the focus.
+    fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    fn up(&mut self) -> bool {
+        let crumb = match self.path.pop() {
+            Some(crumb) => crumb,
+            None => return false,
+        };
+        let focus = self.focus.clone();
+        self.focus = match crumb {
+            Crumb::Left { right } => Tree::pair(focus, right),
+            Crumb::Right { left } => Tree::pair(left, focus),
+        };
+        true
+    }
+
+    fn down_left(&mut self) -> bool {
+        match &*self.focus.clone() {
+            Tree::Leaf(_) => false,
+            Tree::Pair(left, right) => {
+                self.path.push(Crumb::Left {
+                    right: right.clone(),
+                });
+                self.focus = left.clone();
+                true
+            }
+        }
+    }
+
+    fn down_right(&mut self) -> bool {
+        match &*self.focus.clone() {
+            Tree::Leaf(_) => false,
+            Tree::Pair(left, right) => {
+                self.path.push(Crumb::Right { left: left.clone() });
+                self.focus = right.clone();
+                true
+            }
+        }
+    }
+
+    /// Descend to the leftmost leaf beneath the focus.
+    fn first_leaf(&mut self) {
+        while self.down_left() {}
+    }
+
+    /// Descend to the rightmost leaf beneath the focus.
+    fn last_leaf(&mut self) {
+        while self.down_right() {}
+    }
+
+    /// The leaf immediately following the focus in left-to-right order, if any.
+    fn next_leaf(&self) -> Option<Zipper> {
+        let mut zipper = self.clone();
+        loop {
+            match zipper.path.last() {
+                None => return None,
+                Some(Crumb::Left { .. }) => {
+                    zipper.up();
+                    zipper.down_right();
+                    zipper.first_leaf();
+                    return Some(zipper);
+                }
+                Some(Crumb::Right { .. }) => {
+                    zipper.up();
+                }
+            }
+        }
+    }
+
+    /// The leaf immediately preceding the focus in left-to-right order, if any.
+    fn prev_leaf(&self) -> Option<Zipper> {
+        let mut zipper = self.clone();
+        loop {
+            match zipper.path.last() {
+                None => return None,
+                Some(Crumb::Right { .. }) => {
+                    zipper.up();
+                    zipper.down_left();
+                    zipper.last_leaf();
+                    return Some(zipper);
+                }
+                Some(Crumb::Left { .. }) => {
+                    zipper.up();
+                }
+            }
+        }
+    }
+
+    fn value(&self) -> Option<u8> {
+        match *self.focus {
+            Tree::Leaf(value) => Some(value),
+            Tree::Pair(..) => None,
+        }
+    }
+
+    fn replace(&mut self, tree: Arc<Tree>) {
+        self.focus = tree;
+    }
+
+    /// Rebuild the whole tree around the focus.
+    fn into_root(mut self) -> Arc<Tree> {
+        while self.up() {}
+        self.focus
+    }
+}
+
+/// A snailfish number whose subtrees are shared between copies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnailfishNumber {
+    root: Arc<Tree>,
+}
+
+impl SnailfishNumber {
+    /// The first leaf of this number, in left-to-right order.
+    fn first_leaf(&self) -> Zipper {
+        let mut zipper = Zipper::new(self.root.clone());
+        zipper.first_leaf();
+        zipper
+    }
+
+    fn try_explode(&self) -> Option<Self> {
+        let mut zipper = self.first_leaf();
+        while zipper.depth() <= 4 {
+            zipper = zipper.next_leaf()?;
+        }
+        // the leftmost leaf nested inside four pairs is the left half of the exploding pair
+        zipper.up();
+        let (left, right) = match &*zipper.focus {
+            Tree::Pair(left, right) => match (&**left, &**right) {
+                (Tree::Leaf(left), Tree::Leaf(right)) => (*left, *right),
+                _ => unreachable!("exploding pairs always consist of two regular numbers"),
+            },
+            Tree::Leaf(_) => unreachable!("we just moved up from a leaf"),
+        };
+        zipper.replace(Arc::new(Tree::Leaf(0)));
+
+        if let Some(mut prior) = zipper.prev_leaf() {
+            let value = prior.value().expect("prev_leaf focuses a leaf");
+            prior.replace(Arc::new(Tree::Leaf(value + left)));
+            zipper = prior
+                .next_leaf()
+                .expect("the exploded pair follows its prior leaf");
+        }
+        if let Some(mut subsequent) = zipper.next_leaf() {
+            let value = subsequent.value().expect("next_leaf focuses a leaf");
+            subsequent.replace(Arc::new(Tree::Leaf(value + right)));
+            zipper = subsequent;
+        }
+
+        Some(SnailfishNumber {
+            root: zipper.into_root(),
+        })
+    }
+
+    fn try_split(&self) -> Option<Self> {
+        let mut zipper = self.first_leaf();
+        loop {
+            let value = zipper.value().expect("zipper always focuses a leaf");
+            if value >= 10 {
+                let half = value / 2;
+                zipper.replace(Tree::pair(
+                    Arc::new(Tree::Leaf(half)),
+                    Arc::new(Tree::Leaf(value - half)),
+                ));
+                return Some(SnailfishNumber {
+                    root: zipper.into_root(),
+                });
+            }
+            zipper = zipper.next_leaf()?;
+        }
+    }
+
+    /// Apply a single reduction action, if any applies.
+    fn reduce_once(&self) -> Option<Self> {
+        self.try_explode().or_else(|| self.try_split())
+    }
+
+    /// Every state of `self + other`, from the unreduced pair to the reduced sum.
+    ///
+    /// States share structure with one another, so keeping all of them is cheap.
+    pub fn add_steps(self, other: Self) -> ReductionSteps {
+        ReductionSteps {
+            next: Some(SnailfishNumber {
+                root: Tree::pair(self.root, other.root),
+            }),
+        }
+    }
+
+    pub fn magnitude(&self) -> u64 {
+        self.root.magnitude()
+    }
+}
+
+/// Iterator over successive states of a reduction; see [`SnailfishNumber::add_steps`].
+pub struct ReductionSteps {
+    next: Option<SnailfishNumber>,
+}
+
+impl Iterator for ReductionSteps {
+    type Item = SnailfishNumber;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        self.next = current.reduce_once();
+        Some(current)
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+impl From<&Node<u8>> for SnailfishNumber {
+    fn from(tree: &Node<u8>) -> Self {
+        fn convert(tree: &Node<u8>, id: NodeId) -> Arc<Tree> {
+            match tree.branch(id) {
+                Some(branch) => Tree::pair(convert(tree, branch.left), convert(tree, branch.right)),
+                None => Arc::new(Tree::Leaf(
+                    *tree.value(id).expect("non-branch nodes are leaves"),
+                )),
+            }
+        }
+
+        SnailfishNumber {
+            root: convert(tree, tree.root),
+        }
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = Error;
+
+    /// Parse with the shared grammar, then freeze the result.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tree: Box<crate::SnailfishNumber> = s.parse()?;
+        Ok(SnailfishNumber::from(&*tree))
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.add_steps(other)
+            .last()
+            .expect("reduction always yields at least the unreduced sum")
+    }
+}
+
+impl Sum for SnailfishNumber {
+    /// Add every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty: snailfish numbers have no additive identity.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+            .expect("cannot sum an empty sequence of snailfish numbers")
+    }
+}
+
+impl<'a> Sum<&'a SnailfishNumber> for SnailfishNumber {
+    /// Add a copy of every number in the iterator, in order.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator is empty.
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+impl Snailfish for SnailfishNumber {
+    fn magnitude(&self) -> u64 {
+        SnailfishNumber::magnitude(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoclib::input::parse_str;
+    use rstest::rstest;
+
+    fn parse(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]")]
+    #[case("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]")]
+    #[case("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]")]
+    #[case(
+        "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+        "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"
+    )]
+    #[case("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]")]
+    fn explode(#[case] input: &str, #[case] expect: &str) {
+        let exploded = parse(input).try_explode().unwrap();
+        assert_eq!(exploded.to_string(), expect);
+    }
+
+    #[rstest]
+    #[case("[0,10]", "[0,[5,5]]")]
+    #[case("[0,11]", "[0,[5,6]]")]
+    #[case("[0,12]", "[0,[6,6]]")]
+    fn split(#[case] input: &str, #[case] expect: &str) {
+        assert_eq!(parse(input).try_split().unwrap().to_string(), expect);
+    }
+
+    #[test]
+    fn add_steps_snapshots_every_action() {
+        let steps: Vec<String> = parse("[[[[4,3],4],4],[7,[[8,4],9]]]")
+            .add_steps(parse("[1,1]"))
+            .map(|sfn| sfn.to_string())
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]",
+                "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+    }
+
+    #[test]
+    fn example_homework() {
+        let assignment = "
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+        "
+        .trim();
+        let numbers: Vec<SnailfishNumber> = parse_str(assignment).unwrap().collect();
+        let sum: SnailfishNumber = numbers.iter().cloned().sum();
+        assert_eq!(
+            sum.to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+        assert_eq!(sum.magnitude(), 4140);
+        assert_eq!(crate::max_pairwise_magnitude(&numbers), Some(3993));
+    }
+}
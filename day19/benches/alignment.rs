@@ -0,0 +1,178 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day19::{
+    index::BeaconIndex, orientation::Orientation, resolve_with, Matching, Scanner, Vector3,
+    MIN_OVERLAP,
+};
+
+/// How far a scanner can see along each axis.
+const RANGE: i32 = 1000;
+/// Beacons a scanner sees, on average.
+const BEACONS_PER_SCANNER: usize = 80;
+
+/// Deterministic pseudorandom number generator.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    /// A number in `low..=high`.
+    fn between(&mut self, low: i32, high: i32) -> i32 {
+        low + (self.next() % (high - low + 1) as u64) as i32
+    }
+
+    fn point(&mut self, low: Vector3, high: Vector3) -> Vector3 {
+        Vector3 {
+            x: self.between(low.x, high.x),
+            y: self.between(low.y, high.y),
+            z: self.between(low.z, high.z),
+        }
+    }
+}
+
+/// A row of scanners, each half its range beyond the last, all facing pseudorandom
+/// directions and reporting each beacon they see with up to `noise` error per axis.
+fn scanner_field(scanners: usize, noise: i32, seed: u64) -> Vec<Scanner> {
+    let mut rng = Lcg(seed);
+    let orientations: Vec<_> = Orientation::all().collect();
+    let low = Vector3 {
+        x: -RANGE,
+        y: -RANGE,
+        z: -RANGE,
+    };
+    let high = Vector3 {
+        x: RANGE * (scanners as i32 + 1) / 2,
+        y: RANGE,
+        z: RANGE,
+    };
+    let beacon_count = BEACONS_PER_SCANNER * (high.x - low.x) as usize / (2 * RANGE) as usize;
+    let beacons: Vec<_> = (0..beacon_count).map(|_| rng.point(low, high)).collect();
+
+    (0..scanners)
+        .map(|id| {
+            let position = Vector3 {
+                x: RANGE * id as i32 / 2,
+                y: 0,
+                z: 0,
+            };
+            let orientation = orientations[rng.next() as usize % orientations.len()];
+            let beacons = beacons
+                .iter()
+                .map(|&beacon| beacon - position)
+                .filter(|relative| relative.chebyshev() <= RANGE)
+                .map(|relative| {
+                    let error = Vector3 {
+                        x: rng.between(-noise, noise),
+                        y: rng.between(-noise, noise),
+                        z: rng.between(-noise, noise),
+                    };
+                    orientation.inverse().apply(relative) + error
+                })
+                .collect();
+            Scanner {
+                id,
+                beacons,
+                absolute_position: None,
+                orientation: None,
+            }
+        })
+        .collect()
+}
+
+/// The approach the index replaces: compare against every known beacon.
+fn nearest_linear(beacons: &[Vector3], radius: i32, point: Vector3) -> Option<Vector3> {
+    beacons
+        .iter()
+        .map(|&beacon| ((beacon - point).chebyshev(), beacon))
+        .filter(|&(distance, _)| distance <= radius)
+        .min()
+        .map(|(_, beacon)| beacon)
+}
+
+fn bench_nearest(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nearest beacon");
+    for radius in [0, 4] {
+        for beacon_count in [100, 1_000, 10_000] {
+            let mut rng = Lcg(beacon_count as u64);
+            let extent = Vector3 {
+                x: RANGE * 10,
+                y: RANGE * 10,
+                z: RANGE * 10,
+            };
+            let low = Vector3::default() - extent;
+            let beacons: Vec<_> = (0..beacon_count).map(|_| rng.point(low, extent)).collect();
+            // half the queries land near a known beacon
+            let queries: Vec<_> = (0..1_000)
+                .map(|idx| {
+                    if idx % 2 == 0 {
+                        beacons[idx % beacons.len()]
+                            + Vector3 {
+                                x: radius,
+                                y: 0,
+                                z: -radius,
+                            }
+                    } else {
+                        rng.point(low, extent)
+                    }
+                })
+                .collect();
+            let mut index = BeaconIndex::new(radius);
+            index.extend(beacons.iter().copied());
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("index within {}", radius), beacon_count),
+                &queries,
+                |b, queries| {
+                    b.iter(|| {
+                        queries
+                            .iter()
+                            .filter_map(|&query| index.nearest(query))
+                            .count()
+                    })
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("linear within {}", radius), beacon_count),
+                &queries,
+                |b, queries| {
+                    b.iter(|| {
+                        queries
+                            .iter()
+                            .filter_map(|&query| nearest_linear(&beacons, radius, query))
+                            .count()
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_resolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve");
+    group.sample_size(10);
+    for scanners in [4, 8, 16] {
+        for (name, noise, matching) in [
+            ("exact", 0, Matching::Exact),
+            ("noisy", 1, Matching::Within(2)),
+        ] {
+            let field = scanner_field(scanners, noise, scanners as u64);
+            group.bench_with_input(BenchmarkId::new(name, scanners), &field, |b, field| {
+                b.iter(|| {
+                    let mut field = field.clone();
+                    resolve_with(&mut field, matching, MIN_OVERLAP)
+                        .expect("generated fields are connected");
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_nearest, bench_resolve);
+criterion_main!(benches);
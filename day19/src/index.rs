@@ -0,0 +1,140 @@
+use crate::Vector3;
+use std::collections::HashMap;
+
+/// Beacon positions, bucketed into cubic cells so that the readings near a point
+/// can be found without scanning all of them.
+///
+/// Cells are as wide as the neighborhood searched around a point, so every beacon
+/// within the radius of a point lies in one of at most eight cells.
+#[derive(Debug, Clone)]
+pub struct BeaconIndex {
+    radius: i32,
+    cells: HashMap<Vector3, Vec<Vector3>>,
+    len: usize,
+}
+
+impl BeaconIndex {
+    /// An empty index which finds beacons within Chebyshev distance `radius` of a point.
+    pub fn new(radius: i32) -> Self {
+        BeaconIndex {
+            radius: radius.max(0),
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    fn cell(&self, point: Vector3) -> Vector3 {
+        let size = 2 * self.radius + 1;
+        Vector3 {
+            x: point.x.div_euclid(size),
+            y: point.y.div_euclid(size),
+            z: point.z.div_euclid(size),
+        }
+    }
+
+    pub fn insert(&mut self, beacon: Vector3) {
+        let cell = self.cell(beacon);
+        self.cells.entry(cell).or_default().push(beacon);
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every indexed beacon within the radius of `point`.
+    pub fn within(&self, point: Vector3) -> impl '_ + Iterator<Item = Vector3> {
+        let reach = Vector3 {
+            x: self.radius,
+            y: self.radius,
+            z: self.radius,
+        };
+        let low = self.cell(point - reach);
+        let high = self.cell(point + reach);
+        (low.x..=high.x)
+            .flat_map(move |x| {
+                (low.y..=high.y)
+                    .flat_map(move |y| (low.z..=high.z).map(move |z| Vector3 { x, y, z }))
+            })
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |&beacon| (beacon - point).chebyshev() <= self.radius)
+    }
+
+    /// The indexed beacon nearest `point`, if any is within the radius.
+    ///
+    /// Ties are broken by position, so the result doesn't depend on insertion order.
+    pub fn nearest(&self, point: Vector3) -> Option<Vector3> {
+        self.within(point)
+            .min_by_key(|&beacon| ((beacon - point).chebyshev(), beacon))
+    }
+}
+
+impl Extend<Vector3> for BeaconIndex {
+    fn extend<I: IntoIterator<Item = Vector3>>(&mut self, beacons: I) {
+        for beacon in beacons {
+            self.insert(beacon);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lattice() -> Vec<Vector3> {
+        let coords = [-9, -5, -1, 0, 3, 7];
+        coords
+            .into_iter()
+            .flat_map(|x| {
+                coords
+                    .into_iter()
+                    .flat_map(move |y| coords.into_iter().map(move |z| Vector3 { x, y, z }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exact_lookup() {
+        let mut index = BeaconIndex::new(0);
+        index.extend(lattice());
+        assert_eq!(index.len(), 216);
+        let point = Vector3 { x: -5, y: 0, z: 7 };
+        assert_eq!(index.nearest(point), Some(point));
+        assert_eq!(index.nearest(Vector3 { x: -4, y: 0, z: 7 }), None);
+    }
+
+    #[test]
+    fn agrees_with_linear_scan() {
+        let beacons = lattice();
+        for radius in 0..4 {
+            let mut index = BeaconIndex::new(radius);
+            index.extend(beacons.iter().copied());
+            for x in -12..=10 {
+                for y in [-6, -2, 1] {
+                    for z in [-10, -3, 2, 8] {
+                        let point = Vector3 { x, y, z };
+                        let expect = beacons
+                            .iter()
+                            .map(|&beacon| ((beacon - point).chebyshev(), beacon))
+                            .filter(|&(distance, _)| distance <= radius)
+                            .min()
+                            .map(|(_, beacon)| beacon);
+                        assert_eq!(
+                            index.nearest(point),
+                            expect,
+                            "radius {} at {}",
+                            radius,
+                            point
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
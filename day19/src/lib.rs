@@ -0,0 +1,1461 @@
+pub mod index;
+pub mod orientation;
+
+use index::BeaconIndex;
+use orientation::Orientation;
+use progress::Progress;
+#[cfg(feature = "parallelism")]
+use rayon::prelude::*;
+use std::{
+    collections::HashSet,
+    io::Write,
+    ops::{Add, Sub},
+    path::Path,
+    str::FromStr,
+    sync::OnceLock,
+};
+
+/// Point cloud colors: scanners stand out from the beacons around them.
+const SCANNER_COLOR: [u8; 3] = [255, 64, 64];
+const BEACON_COLOR: [u8; 3] = [255, 255, 255];
+
+/// By default, scanners are known to overlap when they detect at least this many beacons
+/// in common.
+pub const MIN_OVERLAP: usize = 12;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[display("{x},{y},{z}")]
+pub struct Vector3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Vector3 {
+    /// Manhattan length of this vector.
+    pub fn manhattan(self) -> i32 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    /// Chebyshev length of this vector: its greatest component, in magnitude.
+    pub fn chebyshev(self) -> i32 {
+        self.x.abs().max(self.y.abs()).max(self.z.abs())
+    }
+
+    /// Square of the Euclidean length of this vector.
+    pub fn squared_length(self) -> i64 {
+        let [x, y, z] = [self.x, self.y, self.z].map(i64::from);
+        x * x + y * y + z * z
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Vector3 {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Vector3 {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+/// How to decide that two readings are of the same beacon.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Matching {
+    /// Readings of the same beacon are identical.
+    #[default]
+    Exact,
+    /// Readings of the same beacon, once rounded to integers, may disagree by up to
+    /// this much along each axis.
+    Within(i32),
+}
+
+impl Matching {
+    fn tolerance(self) -> i32 {
+        match self {
+            Matching::Exact => 0,
+            Matching::Within(tolerance) => tolerance.max(0),
+        }
+    }
+
+    /// Greatest Chebyshev distance at which a placed reading matches a known one.
+    ///
+    /// Both readings carry noise, and so does the scanner position derived from them.
+    fn radius(self) -> i32 {
+        2 * self.tolerance()
+    }
+
+    /// Greatest difference between the distances separating the same two beacons,
+    /// as seen by different scanners.
+    fn distance_slack(self) -> f64 {
+        2.0 * 3f64.sqrt() * f64::from(self.tolerance())
+    }
+
+    /// Whether two squared distances may separate the same pair of beacons.
+    fn distances_match(self, a: i64, b: i64) -> bool {
+        a == b || ((a as f64).sqrt() - (b as f64).sqrt()).abs() <= self.distance_slack()
+    }
+}
+
+/// Absolute beacon positions, indexed to find the reading nearest a point.
+#[derive(Debug, Clone)]
+struct KnownBeacons {
+    beacons: Vec<Vector3>,
+    index: BeaconIndex,
+    matching: Matching,
+}
+
+impl KnownBeacons {
+    fn new(beacons: Vec<Vector3>, matching: Matching) -> Self {
+        let mut index = BeaconIndex::new(matching.radius());
+        index.extend(beacons.iter().copied());
+        KnownBeacons {
+            beacons,
+            index,
+            matching,
+        }
+    }
+
+    /// The known reading nearest `point`, if any is within the matching radius.
+    fn nearest(&self, point: Vector3) -> Option<Vector3> {
+        self.index.nearest(point)
+    }
+}
+
+/// Collapse readings of the same beacon into one.
+fn merge_readings(readings: impl Iterator<Item = Vector3>, matching: Matching) -> Vec<Vector3> {
+    let mut index = BeaconIndex::new(matching.radius());
+    let mut merged = Vec::new();
+    for reading in readings {
+        if index.nearest(reading).is_none() {
+            index.insert(reading);
+            merged.push(reading);
+        }
+    }
+    merged
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanner {
+    pub id: usize,
+    /// Beacon positions relative to this scanner, in its own orientation.
+    pub beacons: Vec<Vector3>,
+    /// Position of this scanner relative to scanner 0, once known.
+    pub absolute_position: Option<Vector3>,
+    /// Rotation from this scanner's orientation into scanner 0's, once known.
+    pub orientation: Option<Orientation>,
+}
+
+impl FromStr for Scanner {
+    type Err = Error;
+
+    /// Parse a single scanner block. Blank lines may surround it, but not split it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks = blocks(s).into_iter();
+        let block = blocks.next().unwrap_or_default();
+        if let Some(&(line, found)) = blocks.next().as_ref().and_then(|extra| extra.first()) {
+            return Err(Error::TrailingInput {
+                line,
+                found: found.to_string(),
+            });
+        }
+        Scanner::parse_block(0, &block)
+    }
+}
+
+/// Split `input` into blocks of non-blank lines, each line trimmed and numbered from 1.
+fn blocks(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push((idx + 1, line));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+impl Scanner {
+    /// Parse the `block`th scanner block of the input from its numbered lines.
+    fn parse_block(block: usize, lines: &[(usize, &str)]) -> Result<Self, Error> {
+        let (&(line, header), beacons) = lines
+            .split_first()
+            .ok_or(Error::EmptyBlock { block, line: 1 })?;
+        let id = header
+            .strip_prefix("--- scanner ")
+            .and_then(|header| header.strip_suffix(" ---"))
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| Error::BadHeader {
+                block,
+                line,
+                found: header.to_string(),
+            })?;
+        if beacons.is_empty() {
+            return Err(Error::EmptyBlock { block, line });
+        }
+        let beacons = beacons
+            .iter()
+            .map(|&(line, beacon)| {
+                beacon.parse().map_err(|source| Error::BadBeacon {
+                    block,
+                    line,
+                    found: beacon.to_string(),
+                    source,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Scanner {
+            id,
+            beacons,
+            absolute_position: None,
+            orientation: None,
+        })
+    }
+}
+
+impl Scanner {
+    fn is_resolved(&self) -> bool {
+        self.absolute_position.is_some() && self.orientation.is_some()
+    }
+
+    /// Beacon positions relative to scanner 0, if this scanner's position is known.
+    pub fn absolute_beacons(&self) -> Option<impl '_ + Iterator<Item = Vector3>> {
+        let position = self.absolute_position?;
+        let orientation = self.orientation?;
+        Some(
+            self.beacons
+                .iter()
+                .map(move |&beacon| orientation.apply(beacon) + position),
+        )
+    }
+
+    /// Find the orientation and position of this scanner relative to `reference`, such
+    /// that at least `min_overlap` of their beacons coincide.
+    ///
+    /// If `reference` is resolved, the result is relative to scanner 0, and can be
+    /// assigned to this scanner to resolve it. Otherwise it's relative to `reference`.
+    pub fn align_to(
+        &self,
+        reference: &Scanner,
+        min_overlap: usize,
+    ) -> Option<(Orientation, Vector3)> {
+        let known = match reference.absolute_beacons() {
+            Some(beacons) => beacons.collect(),
+            None => reference.beacons.clone(),
+        };
+        align_with(
+            &KnownBeacons::new(known, Matching::Exact),
+            &Fingerprint::new(reference),
+            self,
+            &Fingerprint::new(self),
+            &Rotations::default(),
+            Matching::Exact,
+            min_overlap,
+        )
+    }
+
+    /// Find the orientation and absolute position of this scanner, such that at least
+    /// `min_overlap` of its beacons coincide with `known` absolute beacon positions.
+    ///
+    /// Only translations which map some seed `(known, own)` beacon correspondence
+    /// onto each other are considered. Under tolerant matching, the position is then
+    /// refined to the rounded mean offset between matched readings.
+    ///
+    /// Every true correspondence implies nearly the same translation, so only those
+    /// translations implied by at least `min_overlap` seeds are checked.
+    fn align(
+        &self,
+        known: &KnownBeacons,
+        seeds: &[(usize, usize)],
+        rotations: &Rotations,
+        min_overlap: usize,
+    ) -> Option<(Orientation, Vector3)> {
+        let min_overlap = min_overlap.max(1);
+        Orientation::all()
+            .enumerate()
+            .find_map(|(idx, orientation)| {
+                let rotated = rotations.get(idx, orientation, &self.beacons);
+                let translations: Vec<_> = seeds
+                    .iter()
+                    .map(|&(known_idx, own_idx)| known.beacons[known_idx] - rotated[own_idx])
+                    .collect();
+                let mut votes = BeaconIndex::new(known.matching.radius());
+                votes.extend(translations.iter().copied());
+                let mut tried = HashSet::new();
+                translations.iter().find_map(|&translation| {
+                    if votes.within(translation).nth(min_overlap - 1).is_none()
+                        || !tried.insert(translation)
+                    {
+                        return None;
+                    }
+                    // give up as soon as too few beacons remain to reach the minimum overlap
+                    let mut misses_allowed = rotated.len().checked_sub(min_overlap)?;
+                    let mut offsets = Vec::with_capacity(rotated.len());
+                    for &beacon in rotated {
+                        match known.nearest(beacon + translation) {
+                            Some(matched) => offsets.push(matched - beacon),
+                            None => misses_allowed = misses_allowed.checked_sub(1)?,
+                        }
+                    }
+                    let position = match known.matching {
+                        Matching::Exact => translation,
+                        Matching::Within(_) => mean(&offsets),
+                    };
+                    Some((orientation, position))
+                })
+            })
+    }
+}
+
+/// A scanner's beacons rotated into each orientation, each computed on first use.
+///
+/// An unaligned scanner may be checked against several references before one fits.
+#[derive(Debug, Clone, Default)]
+struct Rotations([OnceLock<Vec<Vector3>>; 24]);
+
+impl Rotations {
+    /// `beacons` rotated into `orientation`, the `idx`th of `Orientation::all()`.
+    fn get(&self, idx: usize, orientation: Orientation, beacons: &[Vector3]) -> &[Vector3] {
+        self.0[idx].get_or_init(|| {
+            beacons
+                .iter()
+                .map(|&beacon| orientation.apply(beacon))
+                .collect()
+        })
+    }
+}
+
+/// Scanners with `overlap` beacons in common share the distances between every pair of them.
+fn shared_distances(overlap: usize) -> usize {
+    overlap * overlap.saturating_sub(1) / 2
+}
+
+/// The mean of some vectors, rounded to integers.
+fn mean(vectors: &[Vector3]) -> Vector3 {
+    let count = vectors.len() as f64;
+    let component = |get: fn(&Vector3) -> i32| {
+        let sum: i64 = vectors.iter().map(|vector| i64::from(get(vector))).sum();
+        (sum as f64 / count).round() as i32
+    };
+    Vector3 {
+        x: component(|vector| vector.x),
+        y: component(|vector| vector.y),
+        z: component(|vector| vector.z),
+    }
+}
+
+/// The squared distances between every pair of a scanner's beacons, in ascending order.
+///
+/// These don't depend on the scanner's position or orientation, so scanners
+/// which share too few of them cannot overlap.
+#[derive(Debug, Clone)]
+struct Fingerprint(Vec<(i64, (usize, usize))>);
+
+impl Fingerprint {
+    fn new(scanner: &Scanner) -> Self {
+        let mut distances = Vec::new();
+        for (a, &first) in scanner.beacons.iter().enumerate() {
+            for (b, &second) in scanner.beacons.iter().enumerate().skip(a + 1) {
+                distances.push(((first - second).squared_length(), (a, b)));
+            }
+        }
+        distances.sort_unstable();
+        Fingerprint(distances)
+    }
+
+    /// Size of the multiset intersection of the two fingerprints.
+    ///
+    /// Under tolerant matching, this is approximate: distances are paired greedily.
+    fn shared(&self, other: &Self, matching: Matching) -> usize {
+        let (mut idx, mut other_idx, mut shared) = (0, 0, 0);
+        while let (Some(&(distance, _)), Some(&(other_distance, _))) =
+            (self.0.get(idx), other.0.get(other_idx))
+        {
+            if matching.distances_match(distance, other_distance) {
+                shared += 1;
+                idx += 1;
+                other_idx += 1;
+            } else if distance < other_distance {
+                idx += 1;
+            } else {
+                other_idx += 1;
+            }
+        }
+        shared
+    }
+
+    /// Candidate `(self, other)` beacon correspondences implied by shared distances.
+    ///
+    /// If either beacon of a pair in `self` is really one of the beacons in `other`,
+    /// it must be one of the two endpoints of an equally long pair.
+    fn correspondences(&self, other: &Self, matching: Matching) -> Vec<(usize, usize)> {
+        let mut seen = HashSet::new();
+        let mut correspondences = Vec::new();
+        for &(distance, (a, b)) in &self.0 {
+            let start = other.0.partition_point(|&(other_distance, _)| {
+                other_distance < distance && !matching.distances_match(other_distance, distance)
+            });
+            let matches = other.0[start..].iter().take_while(|&&(other_distance, _)| {
+                other_distance <= distance || matching.distances_match(other_distance, distance)
+            });
+            for &(_, (c, d)) in matches {
+                for correspondence in [(a, c), (a, d), (b, c), (b, d)] {
+                    if seen.insert(correspondence) {
+                        correspondences.push(correspondence);
+                    }
+                }
+            }
+        }
+        correspondences
+    }
+}
+
+/// Align `scanner` against the absolute beacons `known` to a resolved scanner, if
+/// their fingerprints admit an overlap.
+fn align_with(
+    known: &KnownBeacons,
+    reference: &Fingerprint,
+    scanner: &Scanner,
+    fingerprint: &Fingerprint,
+    rotations: &Rotations,
+    matching: Matching,
+    min_overlap: usize,
+) -> Option<(Orientation, Vector3)> {
+    if reference.shared(fingerprint, matching) < shared_distances(min_overlap) {
+        return None;
+    }
+    scanner.align(
+        known,
+        &reference.correspondences(fingerprint, matching),
+        rotations,
+        min_overlap,
+    )
+}
+
+/// Fix every scanner's absolute position and orientation relative to scanner 0.
+///
+/// Each scanner, once fixed, serves as the reference frame against which the
+/// remaining unresolved scanners are aligned. With the `parallelism` feature,
+/// the alignment attempts within each round run concurrently.
+pub fn resolve(scanners: &mut [Scanner]) -> Result<(), Error> {
+    resolve_with(scanners, Matching::default(), MIN_OVERLAP)
+}
+
+/// Fix every scanner's absolute position and orientation relative to scanner 0,
+/// deciding which readings are of the same beacon according to `matching`.
+///
+/// Scanners overlap when they share at least `min_overlap` beacons. Lowering it
+/// tolerates scanners which miss some beacons, or report spurious ones.
+pub fn resolve_with(
+    scanners: &mut [Scanner],
+    matching: Matching,
+    min_overlap: usize,
+) -> Result<(), Error> {
+    let origin = scanners.first_mut().ok_or(Error::NoSolution)?;
+    origin.absolute_position = Some(Vector3::default());
+    origin.orientation = Some(Orientation::default());
+
+    #[cfg(not(feature = "parallelism"))]
+    let fingerprints = scanners.iter().map(Fingerprint::new);
+    #[cfg(feature = "parallelism")]
+    let fingerprints = scanners.par_iter().map(Fingerprint::new);
+    let fingerprints: Vec<_> = fingerprints.collect();
+    let rotations: Vec<Rotations> = scanners.iter().map(|_| Rotations::default()).collect();
+    let progress = Progress::new("scanners aligned", scanners.len() as u64);
+    progress.inc(1);
+
+    // scanners resolved in the previous round; only they can newly overlap unresolved ones
+    let mut frontier = vec![0];
+    while !frontier.is_empty() {
+        let references: Vec<(usize, KnownBeacons)> = frontier
+            .iter()
+            .map(|&reference| {
+                let known = scanners[reference]
+                    .absolute_beacons()
+                    .expect("frontier scanners are resolved")
+                    .collect();
+                (reference, KnownBeacons::new(known, matching))
+            })
+            .collect();
+        let unresolved: Vec<usize> = (0..scanners.len())
+            .filter(|&idx| !scanners[idx].is_resolved())
+            .collect();
+
+        let attempt = |&idx: &usize| {
+            references
+                .iter()
+                .find_map(|(reference, known)| {
+                    align_with(
+                        known,
+                        &fingerprints[*reference],
+                        &scanners[idx],
+                        &fingerprints[idx],
+                        &rotations[idx],
+                        matching,
+                        min_overlap,
+                    )
+                })
+                .map(|alignment| (idx, alignment))
+        };
+        #[cfg(not(feature = "parallelism"))]
+        let alignments = unresolved.iter().filter_map(attempt);
+        #[cfg(feature = "parallelism")]
+        let alignments = unresolved.par_iter().filter_map(attempt);
+        let alignments: Vec<_> = alignments.collect();
+
+        frontier.clear();
+        progress.inc(alignments.len() as u64);
+        for (idx, (orientation, position)) in alignments {
+            scanners[idx].orientation = Some(orientation);
+            scanners[idx].absolute_position = Some(position);
+            frontier.push(idx);
+        }
+    }
+
+    match scanners.iter().find(|scanner| !scanner.is_resolved()) {
+        Some(scanner) => Err(Error::Unaligned(scanner.id)),
+        None => Ok(()),
+    }
+}
+
+/// The set of distinct beacons seen by any resolved scanner, relative to scanner 0.
+pub fn unique_beacons(scanners: &[Scanner]) -> HashSet<Vector3> {
+    unique_beacons_with(scanners, Matching::default())
+        .into_iter()
+        .collect()
+}
+
+/// The distinct beacons seen by any resolved scanner, relative to scanner 0.
+///
+/// Readings which `matching` considers the same beacon are reported once.
+pub fn unique_beacons_with(scanners: &[Scanner], matching: Matching) -> Vec<Vector3> {
+    merge_readings(
+        scanners
+            .iter()
+            .filter_map(Scanner::absolute_beacons)
+            .flatten(),
+        matching,
+    )
+}
+
+/// The greatest Manhattan distance between any two resolved scanners.
+pub fn max_scanner_distance(scanners: &[Scanner]) -> Option<i32> {
+    let positions: Vec<_> = scanners
+        .iter()
+        .filter_map(|scanner| scanner.absolute_position)
+        .collect();
+    positions
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, &a)| {
+            positions[idx + 1..]
+                .iter()
+                .map(move |&b| (a - b).manhattan())
+        })
+        .max()
+}
+
+/// Where a scanner is, and which way it faces, relative to scanner 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Placement {
+    pub id: usize,
+    pub position: Vector3,
+    pub orientation: Orientation,
+}
+
+/// The outcome of adding a scanner to a [`World`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchStatus {
+    /// The scanner was placed, and so were these queued scanners, which overlap it
+    /// either directly or through one another.
+    Placed { also_placed: Vec<usize> },
+    /// The scanner overlaps no placed scanner yet, so it waits in the queue.
+    Queued,
+}
+
+/// A placed scanner, with what's needed to align others against it.
+#[derive(Debug, Clone)]
+struct Anchor {
+    placement: Placement,
+    known: KnownBeacons,
+    fingerprint: Fingerprint,
+}
+
+/// A map of scanners and beacons, relative to the first scanner added.
+///
+/// Scanners can be added one at a time, as their reports arrive.
+#[derive(Debug, Clone)]
+pub struct World {
+    anchors: Vec<Anchor>,
+    queue: Vec<(Scanner, Fingerprint, Rotations)>,
+    matching: Matching,
+    min_overlap: usize,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::with_matching(Matching::default(), MIN_OVERLAP)
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty world which decides which readings are of the same beacon according
+    /// to `matching`, and places scanners which share at least `min_overlap` beacons
+    /// with a placed scanner.
+    pub fn with_matching(matching: Matching, min_overlap: usize) -> Self {
+        World {
+            anchors: Vec::new(),
+            queue: Vec::new(),
+            matching,
+            min_overlap,
+        }
+    }
+
+    /// Resolve every scanner and collect the results.
+    pub fn resolve(scanners: Vec<Scanner>) -> Result<Self, Error> {
+        Self::resolve_with(scanners, Matching::default(), MIN_OVERLAP)
+    }
+
+    /// Resolve every scanner under `matching` and `min_overlap`, and collect the results.
+    pub fn resolve_with(
+        mut scanners: Vec<Scanner>,
+        matching: Matching,
+        min_overlap: usize,
+    ) -> Result<Self, Error> {
+        resolve_with(&mut scanners, matching, min_overlap)?;
+        let mut world = World::with_matching(matching, min_overlap);
+        for scanner in scanners {
+            let fingerprint = Fingerprint::new(&scanner);
+            world.place(scanner, fingerprint);
+        }
+        Ok(world)
+    }
+
+    /// Add a scanner's report to the world.
+    ///
+    /// The first scanner added defines the coordinate frame. Later scanners are placed
+    /// as soon as they overlap any placed scanner; until then, they wait in a queue,
+    /// and are retried whenever another scanner is placed.
+    ///
+    /// With the `parallelism` feature, the new scanner is aligned against the placed
+    /// scanners concurrently; it is still placed relative to the earliest which fits.
+    pub fn add_scanner(&mut self, mut scanner: Scanner) -> MatchStatus {
+        let fingerprint = Fingerprint::new(&scanner);
+        let rotations = Rotations::default();
+        let attempt = |anchor: &Anchor| {
+            align_with(
+                &anchor.known,
+                &anchor.fingerprint,
+                &scanner,
+                &fingerprint,
+                &rotations,
+                self.matching,
+                self.min_overlap,
+            )
+        };
+        let alignment = if self.anchors.is_empty() {
+            Some((Orientation::default(), Vector3::default()))
+        } else {
+            #[cfg(not(feature = "parallelism"))]
+            let alignment = self.anchors.iter().find_map(attempt);
+            #[cfg(feature = "parallelism")]
+            let alignment = self.anchors.par_iter().find_map_first(attempt);
+            alignment
+        };
+        let (orientation, position) = match alignment {
+            Some(alignment) => alignment,
+            None => {
+                self.queue.push((scanner, fingerprint, rotations));
+                return MatchStatus::Queued;
+            }
+        };
+        scanner.orientation = Some(orientation);
+        scanner.absolute_position = Some(position);
+        self.place(scanner, fingerprint);
+
+        // each newly placed scanner may be the missing link for queued ones
+        let mut also_placed = Vec::new();
+        let mut newly_placed = vec![self.anchors.len() - 1];
+        while let Some(anchor_idx) = newly_placed.pop() {
+            let mut queue_idx = 0;
+            while queue_idx < self.queue.len() {
+                let anchor = &self.anchors[anchor_idx];
+                let (queued, queued_fingerprint, queued_rotations) = &self.queue[queue_idx];
+                match align_with(
+                    &anchor.known,
+                    &anchor.fingerprint,
+                    queued,
+                    queued_fingerprint,
+                    queued_rotations,
+                    self.matching,
+                    self.min_overlap,
+                ) {
+                    Some((orientation, position)) => {
+                        let (mut queued, queued_fingerprint, _) = self.queue.remove(queue_idx);
+                        queued.orientation = Some(orientation);
+                        queued.absolute_position = Some(position);
+                        also_placed.push(queued.id);
+                        self.place(queued, queued_fingerprint);
+                        newly_placed.push(self.anchors.len() - 1);
+                    }
+                    None => queue_idx += 1,
+                }
+            }
+        }
+        MatchStatus::Placed { also_placed }
+    }
+
+    /// Record a resolved scanner.
+    fn place(&mut self, scanner: Scanner, fingerprint: Fingerprint) {
+        let placement = Placement {
+            id: scanner.id,
+            position: scanner
+                .absolute_position
+                .expect("placed scanners have a position"),
+            orientation: scanner
+                .orientation
+                .expect("placed scanners have an orientation"),
+        };
+        let known = scanner
+            .absolute_beacons()
+            .expect("placed scanners are resolved")
+            .collect();
+        let known = KnownBeacons::new(known, self.matching);
+        self.anchors.push(Anchor {
+            placement,
+            known,
+            fingerprint,
+        });
+    }
+
+    /// Every placed scanner, in the order it was placed.
+    pub fn placements(&self) -> impl '_ + Iterator<Item = Placement> {
+        self.anchors.iter().map(|anchor| anchor.placement)
+    }
+
+    /// Ids of the scanners still waiting for an overlap.
+    pub fn queued(&self) -> impl '_ + Iterator<Item = usize> {
+        self.queue.iter().map(|(scanner, _, _)| scanner.id)
+    }
+
+    /// Every distinct beacon seen by a placed scanner, sorted for stable output.
+    pub fn beacons(&self) -> Vec<Vector3> {
+        let mut beacons = merge_readings(
+            self.anchors
+                .iter()
+                .flat_map(|anchor| anchor.known.beacons.iter().copied()),
+            self.matching,
+        );
+        beacons.sort_unstable();
+        beacons
+    }
+
+    /// Write one row per scanner, then one row per beacon.
+    pub fn write_csv(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "kind,id,x,y,z,orientation")?;
+        for scanner in self.placements() {
+            let Vector3 { x, y, z } = scanner.position;
+            writeln!(
+                writer,
+                "scanner,{},{},{},{},{}",
+                scanner.id, x, y, z, scanner.orientation
+            )?;
+        }
+        for Vector3 { x, y, z } in self.beacons() {
+            writeln!(writer, "beacon,,{},{},{},", x, y, z)?;
+        }
+        Ok(())
+    }
+
+    /// Every scanner and beacon as a colored point, scanners first.
+    fn colored_points(&self) -> impl '_ + Iterator<Item = (Vector3, [u8; 3])> {
+        self.placements()
+            .map(|placement| (placement.position, SCANNER_COLOR))
+            .chain(
+                self.beacons()
+                    .into_iter()
+                    .map(|beacon| (beacon, BEACON_COLOR)),
+            )
+    }
+
+    /// Write an ASCII PLY point cloud with per-vertex colors.
+    pub fn write_ply(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let points: Vec<_> = self.colored_points().collect();
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "comment scanners are red; beacons are white")?;
+        writeln!(writer, "element vertex {}", points.len())?;
+        for property in ["x", "y", "z"] {
+            writeln!(writer, "property float {}", property)?;
+        }
+        for property in ["red", "green", "blue"] {
+            writeln!(writer, "property uchar {}", property)?;
+        }
+        writeln!(writer, "end_header")?;
+        for (Vector3 { x, y, z }, [r, g, b]) in points {
+            writeln!(writer, "{} {} {} {} {} {}", x, y, z, r, g, b)?;
+        }
+        Ok(())
+    }
+
+    /// Write a Wavefront OBJ point cloud.
+    ///
+    /// OBJ has no standard vertex colors, but most viewers accept trailing RGB
+    /// components in the range `0..=1`.
+    pub fn write_obj(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "# scanners are red; beacons are white")?;
+        let mut count = 0;
+        for (Vector3 { x, y, z }, color) in self.colored_points() {
+            let [r, g, b] = color.map(|channel| f32::from(channel) / 255.0);
+            writeln!(writer, "v {} {} {} {:.3} {:.3} {:.3}", x, y, z, r, g, b)?;
+            count += 1;
+        }
+        // a point element referencing every vertex, so viewers render them
+        if count > 0 {
+            write!(writer, "p")?;
+            for idx in 1..=count {
+                write!(writer, " {}", idx)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for World {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct Snapshot {
+            scanners: Vec<Placement>,
+            beacons: Vec<Vector3>,
+        }
+
+        let snapshot = Snapshot {
+            scanners: self.placements().collect(),
+            beacons: self.beacons(),
+        };
+        serde::Serialize::serialize(&snapshot, serializer)
+    }
+}
+
+/// How to export the resolved world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "kebab-case")]
+pub enum ExportFormat {
+    Csv,
+    Ply,
+    Obj,
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+/// Print the resolved world to stdout instead of solving.
+pub fn export(
+    input: &str,
+    format: ExportFormat,
+    matching: Matching,
+    min_overlap: usize,
+) -> Result<(), Error> {
+    let world = World::resolve_with(parse_scanners(input)?, matching, min_overlap)?;
+    let stdout = std::io::stdout();
+    let stdout = stdout.lock();
+    match format {
+        ExportFormat::Csv => world.write_csv(stdout)?,
+        ExportFormat::Ply => world.write_ply(stdout)?,
+        ExportFormat::Obj => world.write_obj(stdout)?,
+        #[cfg(feature = "serde")]
+        ExportFormat::Json => serde_json::to_writer_pretty(stdout, &world)?,
+    }
+    Ok(())
+}
+
+fn parse_scanners(input: &str) -> Result<Vec<Scanner>, Error> {
+    blocks(input)
+        .iter()
+        .enumerate()
+        .map(|(block, lines)| Scanner::parse_block(block, lines))
+        .collect()
+}
+
+fn resolved_scanners(
+    input: &str,
+    matching: Matching,
+    min_overlap: usize,
+) -> Result<Vec<Scanner>, Error> {
+    let mut scanners = parse_scanners(input)?;
+    resolve_with(&mut scanners, matching, min_overlap)?;
+    Ok(scanners)
+}
+
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
+    solve_part1_with(input, Matching::default(), MIN_OVERLAP)
+}
+
+pub fn solve_part1_with(
+    input: &str,
+    matching: Matching,
+    min_overlap: usize,
+) -> Result<usize, Error> {
+    let scanners = resolved_scanners(input, matching, min_overlap)?;
+    Ok(unique_beacons_with(&scanners, matching).len())
+}
+
+pub fn solve_part2(input: &str) -> Result<i32, Error> {
+    solve_part2_with(input, Matching::default(), MIN_OVERLAP)
+}
+
+pub fn solve_part2_with(input: &str, matching: Matching, min_overlap: usize) -> Result<i32, Error> {
+    let scanners = resolved_scanners(input, matching, min_overlap)?;
+    max_scanner_distance(&scanners).ok_or(Error::NoSolution)
+}
+
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    part1_with(input, Matching::default(), MIN_OVERLAP)
+}
+
+pub fn part1_with(input: &Path, matching: Matching, min_overlap: usize) -> Result<usize, Error> {
+    solve_part1_with(&std::fs::read_to_string(input)?, matching, min_overlap)
+}
+
+pub fn part2(input: &Path) -> Result<i32, Error> {
+    part2_with(input, Matching::default(), MIN_OVERLAP)
+}
+
+pub fn part2_with(input: &Path, matching: Matching, min_overlap: usize) -> Result<i32, Error> {
+    solve_part2_with(&std::fs::read_to_string(input)?, matching, min_overlap)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("scanner block {block}, line {line}: expected `--- scanner N ---`, found {found:?}")]
+        BadHeader {
+            block: usize,
+            line: usize,
+            found: String,
+        },
+        #[error("scanner block {block}, line {line}: expected `x,y,z`, found {found:?}")]
+        BadBeacon {
+            block: usize,
+            line: usize,
+            found: String,
+            source: parse_display::ParseError,
+        },
+        #[error("scanner block {block}, line {line}: no beacons follow the header")]
+        EmptyBlock { block: usize, line: usize },
+        #[error("line {line}: unexpected input after the scanner block: {found:?}")]
+        TrailingInput { line: usize, found: String },
+        #[cfg(feature = "serde")]
+        #[error(transparent)]
+        Json(#[from] serde_json::Error),
+        #[error("scanner {0} does not overlap any other scanner")]
+        Unaligned(usize),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+";
+
+    fn example() -> Vec<Scanner> {
+        EXAMPLE
+            .trim()
+            .split("\n\n")
+            .map(|block| block.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fingerprints_identify_overlaps() {
+        let fingerprints: Vec<_> = example().iter().map(Fingerprint::new).collect();
+        let mut overlapping = Vec::new();
+        for a in 0..fingerprints.len() {
+            for b in a + 1..fingerprints.len() {
+                let shared = fingerprints[a].shared(&fingerprints[b], Matching::Exact);
+                if shared >= shared_distances(MIN_OVERLAP) {
+                    overlapping.push((a, b));
+                }
+            }
+        }
+        assert_eq!(overlapping, [(0, 1), (1, 3), (1, 4), (2, 4)]);
+    }
+
+    #[test]
+    fn example_scanner_positions() {
+        let mut scanners = example();
+        resolve(&mut scanners).unwrap();
+        let positions: Vec<_> = scanners
+            .iter()
+            .map(|scanner| scanner.absolute_position.unwrap())
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                Vector3 { x: 0, y: 0, z: 0 },
+                Vector3 {
+                    x: 68,
+                    y: -1246,
+                    z: -43
+                },
+                Vector3 {
+                    x: 1105,
+                    y: -1205,
+                    z: 1229
+                },
+                Vector3 {
+                    x: -92,
+                    y: -2380,
+                    z: -20
+                },
+                Vector3 {
+                    x: -20,
+                    y: -1133,
+                    z: 1061
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn align_step_by_step() {
+        let mut scanners = example();
+        scanners[0].absolute_position = Some(Vector3::default());
+        scanners[0].orientation = Some(Orientation::default());
+        assert_eq!(scanners[2].align_to(&scanners[0], MIN_OVERLAP), None);
+
+        let (orientation, position) = scanners[1].align_to(&scanners[0], MIN_OVERLAP).unwrap();
+        assert_eq!(
+            position,
+            Vector3 {
+                x: 68,
+                y: -1246,
+                z: -43
+            }
+        );
+        scanners[1].orientation = Some(orientation);
+        scanners[1].absolute_position = Some(position);
+
+        let (_, position) = scanners[4].align_to(&scanners[1], MIN_OVERLAP).unwrap();
+        assert_eq!(
+            position,
+            Vector3 {
+                x: -20,
+                y: -1133,
+                z: 1061
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors_locate_the_problem() {
+        let input = "--- scanner 0 ---\n1,2,3\n\n\n--- scanner 1 ---\n4,5,6\n7,8\n";
+        assert!(matches!(
+            parse_scanners(input),
+            Err(Error::BadBeacon {
+                block: 1,
+                line: 7,
+                ..
+            })
+        ));
+
+        let input = "--- scanner 0 ---\n1,2,3\n\n--- scanner one ---\n4,5,6\n";
+        assert!(matches!(
+            parse_scanners(input),
+            Err(Error::BadHeader {
+                block: 1,
+                line: 4,
+                ..
+            })
+        ));
+
+        let input = "--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n\n";
+        assert!(matches!(
+            parse_scanners(input),
+            Err(Error::EmptyBlock { block: 1, line: 4 })
+        ));
+
+        let input = "--- scanner 0 ---\n1,2,3\n\ngarbage\n";
+        assert!(matches!(
+            parse_scanners(input),
+            Err(Error::BadHeader { block: 1, .. })
+        ));
+        assert!(matches!(
+            input.parse::<Scanner>(),
+            Err(Error::TrailingInput { line: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn example_part1() {
+        let mut scanners = example();
+        resolve(&mut scanners).unwrap();
+        assert_eq!(unique_beacons(&scanners).len(), 79);
+    }
+
+    #[test]
+    fn example_part2() {
+        let mut scanners = example();
+        resolve(&mut scanners).unwrap();
+        assert_eq!(max_scanner_distance(&scanners), Some(3621));
+    }
+
+    #[test]
+    fn example_world() {
+        let world = World::resolve(example()).unwrap();
+        let placements: Vec<_> = world.placements().collect();
+        assert_eq!(placements.len(), 5);
+        assert_eq!(world.beacons().len(), 79);
+        assert_eq!(placements[0].orientation, Orientation::default());
+        assert_eq!(
+            placements[3].position,
+            Vector3 {
+                x: -92,
+                y: -2380,
+                z: -20
+            }
+        );
+
+        let mut csv = Vec::new();
+        world.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("kind,id,x,y,z,orientation"));
+        assert_eq!(lines.next(), Some("scanner,0,0,0,0,+x+y+z"));
+        assert_eq!(lines.count(), 4 + 79);
+    }
+
+    #[test]
+    fn example_point_clouds() {
+        let world = World::resolve(example()).unwrap();
+
+        let mut ply = Vec::new();
+        world.write_ply(&mut ply).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        let (header, body) = ply.split_once("end_header\n").unwrap();
+        assert!(header.starts_with("ply\nformat ascii 1.0\n"));
+        assert!(header.contains("element vertex 84\n"));
+        let vertices: Vec<_> = body.lines().collect();
+        assert_eq!(vertices.len(), 5 + 79);
+        assert_eq!(vertices[0], "0 0 0 255 64 64");
+        assert!(vertices[5].ends_with(" 255 255 255"));
+
+        let mut obj = Vec::new();
+        world.write_obj(&mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("v ")).count(),
+            84
+        );
+        assert!(obj.contains("v 0 0 0 1.000 0.251 0.251\n"));
+        assert!(obj.trim_end().ends_with(" 84"));
+    }
+
+    #[test]
+    fn incremental_world() {
+        let mut scanners: Vec<_> = example().into_iter().map(Some).collect();
+        let mut world = World::new();
+        let mut add = |idx: usize| world.add_scanner(scanners[idx].take().unwrap());
+
+        assert_eq!(
+            add(0),
+            MatchStatus::Placed {
+                also_placed: vec![]
+            }
+        );
+        // 2 overlaps only 4; 3 and 4 overlap only 1, among the scanners added so far
+        assert_eq!(add(2), MatchStatus::Queued);
+        assert_eq!(add(3), MatchStatus::Queued);
+        assert_eq!(add(4), MatchStatus::Queued);
+        match add(1) {
+            MatchStatus::Placed { mut also_placed } => {
+                also_placed.sort_unstable();
+                assert_eq!(also_placed, [2, 3, 4]);
+            }
+            MatchStatus::Queued => panic!("scanner 1 overlaps scanner 0"),
+        }
+
+        assert_eq!(world.queued().count(), 0);
+        assert_eq!(
+            world.beacons(),
+            World::resolve(example()).unwrap().beacons()
+        );
+        let mut positions: Vec<_> = world
+            .placements()
+            .map(|placement| (placement.id, placement.position))
+            .collect();
+        positions.sort_unstable();
+        assert_eq!(
+            positions[4],
+            (
+                4,
+                Vector3 {
+                    x: -20,
+                    y: -1133,
+                    z: 1061
+                }
+            )
+        );
+    }
+
+    /// The example, with every reading perturbed by up to 1 along each axis.
+    fn noisy_example() -> Vec<Scanner> {
+        let mut state = 0x2545_f491_u32;
+        let mut noise = move || {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % 3) as i32 - 1
+        };
+        let mut scanners = example();
+        for beacon in scanners.iter_mut().flat_map(|scanner| &mut scanner.beacons) {
+            *beacon = *beacon
+                + Vector3 {
+                    x: noise(),
+                    y: noise(),
+                    z: noise(),
+                };
+        }
+        scanners
+    }
+
+    #[test]
+    fn noisy_example_needs_tolerance() {
+        let mut scanners = noisy_example();
+        assert!(matches!(resolve(&mut scanners), Err(Error::Unaligned(_))));
+    }
+
+    #[test]
+    fn noisy_example_within_tolerance() {
+        let matching = Matching::Within(2);
+        let mut scanners = noisy_example();
+        resolve_with(&mut scanners, matching, MIN_OVERLAP).unwrap();
+        let mut exact = example();
+        resolve(&mut exact).unwrap();
+        for (noisy, exact) in scanners.iter().zip(&exact) {
+            assert_eq!(noisy.orientation, exact.orientation);
+            let error = noisy.absolute_position.unwrap() - exact.absolute_position.unwrap();
+            assert!(
+                error.chebyshev() <= 2,
+                "scanner {} is off by {}",
+                noisy.id,
+                error
+            );
+        }
+        assert_eq!(unique_beacons_with(&scanners, matching).len(), 79);
+
+        let world = World::resolve_with(noisy_example(), matching, MIN_OVERLAP).unwrap();
+        assert_eq!(world.beacons().len(), 79);
+    }
+
+    #[test]
+    fn lower_overlap_tolerates_missing_beacons() {
+        let mut exact = example();
+        resolve(&mut exact).unwrap();
+        let seen_by_1: HashSet<_> = exact[1].absolute_beacons().unwrap().collect();
+
+        // scanner 1 is scanner 0's only link to the rest; now they share just 11 beacons
+        let mut scanners = example();
+        let shared = scanners[0]
+            .beacons
+            .iter()
+            .position(|beacon| seen_by_1.contains(beacon))
+            .unwrap();
+        scanners[0].beacons.remove(shared);
+        assert!(resolve(&mut scanners.clone()).is_err());
+
+        resolve_with(&mut scanners, Matching::Exact, MIN_OVERLAP - 1).unwrap();
+        for (scanner, exact) in scanners.iter().zip(&exact) {
+            assert_eq!(scanner.absolute_position, exact.absolute_position);
+        }
+        assert_eq!(unique_beacons(&scanners).len(), 79);
+    }
+
+    #[test]
+    fn unaligned_scanner_is_reported() {
+        let mut scanners = example();
+        scanners[2].beacons.truncate(3);
+        assert!(matches!(resolve(&mut scanners), Err(Error::Unaligned(2))));
+    }
+}
@@ -0,0 +1,87 @@
+use aoclib::{config::Config, website::get_input};
+use day19::{solve_part1_with, solve_part2_with, ExportFormat, Matching, MIN_OVERLAP};
+
+use color_eyre::eyre::Result;
+use input_source::InputSource;
+use structopt::StructOpt;
+
+const YEAR: u32 = 2021;
+const DAY: u8 = 19;
+
+#[derive(StructOpt, Debug)]
+struct RunArgs {
+    /// input file, or "-" to read from stdin
+    #[structopt(long, parse(from_os_str))]
+    input: Option<InputSource>,
+
+    /// skip part 1
+    #[structopt(long)]
+    no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    part2: bool,
+
+    /// report the progress of long-running parts on stderr
+    #[structopt(long)]
+    progress: bool,
+
+    /// print the resolved scanners and beacons instead of solving: "csv", "ply",
+    /// "obj", or (with the `serde` feature) "json"
+    #[structopt(long)]
+    export: Option<ExportFormat>,
+
+    /// print every resolved scanner's position and orientation, then every beacon,
+    /// as CSV; shorthand for `--export csv`
+    #[structopt(long, conflicts_with = "export")]
+    dump: bool,
+
+    /// treat readings as the same beacon when they disagree by at most this much
+    /// along each axis, instead of requiring them to be identical
+    #[structopt(long)]
+    tolerance: Option<i32>,
+
+    /// place a scanner once it shares at least this many beacons with a placed scanner,
+    /// instead of 12; lower it to tolerate missing or spurious beacons
+    #[structopt(long)]
+    min_overlap: Option<usize>,
+}
+
+impl RunArgs {
+    fn input(&self) -> Result<InputSource> {
+        match self.input {
+            None => {
+                let config = Config::load()?;
+                // this does nothing if the input file already exists, but
+                // simplifies the workflow after cloning the repo on a new computer
+                get_input(&config, YEAR, DAY)?;
+                Ok(config.input_for(YEAR, DAY).into())
+            }
+            Some(ref source) => Ok(source.clone()),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = RunArgs::from_args();
+    if args.progress {
+        progress::enable();
+    }
+    let input = args.input()?.read()?;
+    let matching = args.tolerance.map_or(Matching::Exact, Matching::Within);
+    let min_overlap = args.min_overlap.unwrap_or(MIN_OVERLAP);
+
+    if let Some(format) = args.export.or(args.dump.then_some(ExportFormat::Csv)) {
+        day19::export(&input, format, matching, min_overlap)?;
+        return Ok(());
+    }
+
+    if !args.no_part1 {
+        println!("{}", solve_part1_with(&input, matching, min_overlap)?);
+    }
+    if args.part2 {
+        println!("{}", solve_part2_with(&input, matching, min_overlap)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use crate::Vector3;
+use std::fmt;
+
+/// One of the 24 directions a scanner can face.
+///
+/// This is a rotation matrix: each row contains a single nonzero entry of ±1,
+/// and the determinant is 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation([[i32; 3]; 3]);
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Orientation([[1, 0, 0], [0, 1, 0], [0, 0, 1]])
+    }
+}
+
+/// Signed source axes, in output order: the default orientation is `+x+y+z`.
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.0 {
+            let (axis, sign) = row
+                .iter()
+                .enumerate()
+                .find(|(_, &entry)| entry != 0)
+                .expect("every row of a rotation has a nonzero entry");
+            let sign = if *sign > 0 { '+' } else { '-' };
+            write!(f, "{}{}", sign, ['x', 'y', 'z'][axis])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Orientation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Orientation {
+    /// Rotate `vector` into this orientation.
+    pub fn apply(self, vector: Vector3) -> Vector3 {
+        let [x, y, z] = self
+            .0
+            .map(|row| row[0] * vector.x + row[1] * vector.y + row[2] * vector.z);
+        Vector3 { x, y, z }
+    }
+
+    /// The rotation which applies `first`, then `self`.
+    pub fn compose(self, first: Orientation) -> Orientation {
+        let mut product = [[0; 3]; 3];
+        for (row, product_row) in product.iter_mut().enumerate() {
+            for (col, entry) in product_row.iter_mut().enumerate() {
+                *entry = (0..3).map(|k| self.0[row][k] * first.0[k][col]).sum();
+            }
+        }
+        Orientation(product)
+    }
+
+    /// The rotation which undoes this one.
+    ///
+    /// Rotation matrices are orthogonal, so this is the transpose.
+    pub fn inverse(self) -> Orientation {
+        let mut transpose = [[0; 3]; 3];
+        for (row, transpose_row) in transpose.iter_mut().enumerate() {
+            for (col, entry) in transpose_row.iter_mut().enumerate() {
+                *entry = self.0[col][row];
+            }
+        }
+        Orientation(transpose)
+    }
+
+    fn determinant(self) -> i32 {
+        let [a, b, c] = self.0;
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+
+    /// Every proper rotation of the axes.
+    ///
+    /// Of the 48 signed permutation matrices, half are reflections, with determinant -1.
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        permutations.into_iter().flat_map(|axes| {
+            (0..8).filter_map(move |signs: u8| {
+                let mut matrix = [[0; 3]; 3];
+                for (row, &axis) in axes.iter().enumerate() {
+                    matrix[row][axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let orientation = Orientation(matrix);
+                (orientation.determinant() == 1).then_some(orientation)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn there_are_24_orientations() {
+        let orientations: HashSet<_> = Orientation::all().collect();
+        assert_eq!(orientations.len(), 24);
+    }
+
+    #[test]
+    fn orientations_are_rotations() {
+        let v = Vector3 { x: 1, y: 2, z: 3 };
+        let images: HashSet<_> = Orientation::all().map(|o| o.apply(v)).collect();
+        assert_eq!(images.len(), 24);
+        // a rotation preserves the handedness of the basis: x × y = z
+        for orientation in Orientation::all() {
+            let x = orientation.apply(Vector3 { x: 1, y: 0, z: 0 });
+            let y = orientation.apply(Vector3 { x: 0, y: 1, z: 0 });
+            let z = orientation.apply(Vector3 { x: 0, y: 0, z: 1 });
+            let cross = Vector3 {
+                x: x.y * y.z - x.z * y.y,
+                y: x.z * y.x - x.x * y.z,
+                z: x.x * y.y - x.y * y.x,
+            };
+            assert_eq!(cross, z, "{:?} is a reflection", orientation);
+        }
+    }
+
+    #[test]
+    fn composition_is_closed() {
+        let orientations: HashSet<_> = Orientation::all().collect();
+        for a in Orientation::all() {
+            for b in Orientation::all() {
+                assert!(orientations.contains(&a.compose(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn composition_applies_in_order() {
+        let v = Vector3 { x: 1, y: 2, z: 3 };
+        for a in Orientation::all() {
+            for b in Orientation::all() {
+                assert_eq!(a.compose(b).apply(v), a.apply(b.apply(v)));
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes() {
+        for orientation in Orientation::all() {
+            assert_eq!(
+                orientation.compose(orientation.inverse()),
+                Orientation::default()
+            );
+            assert_eq!(
+                orientation.inverse().compose(orientation),
+                Orientation::default()
+            );
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Orientation::default().to_string(), "+x+y+z");
+        assert_eq!(
+            Orientation([[0, -1, 0], [0, 0, 1], [-1, 0, 0]]).to_string(),
+            "-y+z-x"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::{fmt, path::Path, str::FromStr};
+
+/// The image enhancement algorithm: the output pixel for each 3x3 neighborhood,
+/// read as a 9-bit number from top left to bottom right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Algorithm([bool; 512]);
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut algorithm = [false; 512];
+        let s = s.trim();
+        if s.chars().count() != algorithm.len() {
+            return Err(Error::MalformedAlgorithm);
+        }
+        for (output, ch) in algorithm.iter_mut().zip(s.chars()) {
+            *output = pixel(ch).ok_or(Error::MalformedAlgorithm)?;
+        }
+        Ok(Algorithm(algorithm))
+    }
+}
+
+fn pixel(ch: char) -> Option<bool> {
+    match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+/// A finite image on an infinite background.
+///
+/// Every pixel outside the stored bounds has the background value. An algorithm
+/// which lights an unlit neighborhood flips the whole background on each step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+    background: bool,
+}
+
+impl FromStr for Image {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut pixels = Vec::new();
+        for line in s.trim().lines() {
+            let line = line.trim();
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(Error::MalformedImage);
+            }
+            for ch in line.chars() {
+                pixels.push(pixel(ch).ok_or(Error::MalformedImage)?);
+            }
+        }
+        let width = width.unwrap_or_default();
+        Ok(Image {
+            width,
+            height: pixels.len().checked_div(width).unwrap_or_default(),
+            pixels,
+            background: false,
+        })
+    }
+}
+
+/// Only the stored bounds are drawn: `#` is lit, `.` is dark.
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for &lit in row {
+                write!(f, "{}", if lit { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the infinite background is lit.
+    pub fn background(&self) -> bool {
+        self.background
+    }
+
+    /// The pixel at `(x, y)`, relative to the top left of the stored bounds.
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return self.background;
+        }
+        self.pixels[y as usize * self.width + x as usize]
+    }
+
+    /// The number of lit pixels, unless there are infinitely many.
+    pub fn lit(&self) -> Option<usize> {
+        (!self.background).then(|| self.pixels.iter().filter(|&&lit| lit).count())
+    }
+
+    /// Apply `algorithm` once.
+    ///
+    /// The output grows by one pixel on each side: beyond that, every neighborhood
+    /// is entirely background.
+    pub fn enhance(&self, algorithm: &Algorithm) -> Image {
+        let width = self.width + 2;
+        let height = self.height + 2;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in -1..=self.height as i32 {
+            for x in -1..=self.width as i32 {
+                let mut index = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        index = (index << 1) | self.get(x + dx, y + dy) as usize;
+                    }
+                }
+                pixels.push(algorithm.0[index]);
+            }
+        }
+        let background = algorithm.0[if self.background { 511 } else { 0 }];
+        Image {
+            width,
+            height,
+            pixels,
+            background,
+        }
+    }
+
+    /// Successive enhancements of this image by `algorithm`, starting with the first.
+    pub fn enhancements(self, algorithm: &Algorithm) -> Enhancements<'_> {
+        Enhancements {
+            algorithm,
+            image: self,
+        }
+    }
+}
+
+/// Iterator over successive enhancements of an image.
+#[derive(Debug, Clone)]
+pub struct Enhancements<'a> {
+    algorithm: &'a Algorithm,
+    image: Image,
+}
+
+impl Iterator for Enhancements<'_> {
+    type Item = Image;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.image = self.image.enhance(self.algorithm);
+        Some(self.image.clone())
+    }
+}
+
+fn parse_input(input: &str) -> Result<(Algorithm, Image), Error> {
+    let input = input.replace("\r\n", "\n");
+    let (algorithm, image) = input
+        .trim()
+        .split_once("\n\n")
+        .ok_or(Error::MalformedInput)?;
+    Ok((algorithm.parse()?, image.parse()?))
+}
+
+/// The number of lit pixels after enhancing the input image `steps` times.
+pub fn lit_after(input: &str, steps: usize) -> Result<usize, Error> {
+    let (algorithm, image) = parse_input(input)?;
+    let image = match steps {
+        0 => image,
+        _ => image
+            .enhancements(&algorithm)
+            .nth(steps - 1)
+            .expect("enhancements never end"),
+    };
+    image.lit().ok_or(Error::InfinitelyLit)
+}
+
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
+    lit_after(input, 2)
+}
+
+pub fn solve_part2(input: &str) -> Result<usize, Error> {
+    lit_after(input, 50)
+}
+
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("malformed input")]
+        MalformedInput,
+        #[error("enhancement algorithm must be 512 '#' or '.' characters")]
+        MalformedAlgorithm,
+        #[error("image must be a rectangle of '#' or '.' characters")]
+        MalformedImage,
+        #[error("infinitely many pixels are lit")]
+        InfinitelyLit,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###
+";
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(lit_after(EXAMPLE, 2).unwrap(), 35);
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(lit_after(EXAMPLE, 50).unwrap(), 3351);
+    }
+
+    #[test]
+    fn example_first_enhancement() {
+        let (algorithm, image) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(image.lit(), Some(10));
+        let enhanced = image.enhance(&algorithm);
+        assert_eq!((enhanced.width(), enhanced.height()), (7, 7));
+        assert_eq!(
+            enhanced.to_string(),
+            ".##.##.\n#..#.#.\n##.#..#\n####..#\n.#..##.\n..##..#\n...#.#.\n"
+        );
+    }
+
+    /// An algorithm which lights dark neighborhoods and darkens lit ones.
+    fn flipping_algorithm() -> Algorithm {
+        let mut algorithm = [false; 512];
+        algorithm[0] = true;
+        Algorithm(algorithm)
+    }
+
+    #[test]
+    fn background_flips() {
+        let algorithm = flipping_algorithm();
+        let mut steps = "#".parse::<Image>().unwrap().enhancements(&algorithm);
+        let first = steps.next().unwrap();
+        assert!(first.background());
+        assert_eq!(first.lit(), None);
+        assert!(first.get(-100, 100));
+        // only the center's neighborhood is entirely dark again
+        let second = steps.next().unwrap();
+        assert!(!second.background());
+        assert_eq!(second.lit(), Some(1));
+        assert!(second.get(2, 2));
+    }
+
+    #[test]
+    fn infinitely_lit_is_an_error() {
+        let input = format!("#{}\n\n#", ".".repeat(511));
+        assert!(matches!(lit_after(&input, 1), Err(Error::InfinitelyLit)));
+        assert_eq!(lit_after(&input, 2).unwrap(), 1);
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert!(matches!(
+            lit_after("#.#\n\n#", 2),
+            Err(Error::MalformedAlgorithm)
+        ));
+        let input = format!("{}\n\n#.\n#", ".".repeat(512));
+        assert!(matches!(lit_after(&input, 2), Err(Error::MalformedImage)));
+        assert!(matches!(lit_after(".", 2), Err(Error::MalformedInput)));
+    }
+}
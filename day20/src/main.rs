@@ -0,0 +1,53 @@
+use aoclib::{config::Config, website::get_input};
+use day20::{solve_part1, solve_part2};
+
+use color_eyre::eyre::Result;
+use input_source::InputSource;
+use structopt::StructOpt;
+
+const YEAR: u32 = 2021;
+const DAY: u8 = 20;
+
+#[derive(StructOpt, Debug)]
+struct RunArgs {
+    /// input file, or "-" to read from stdin
+    #[structopt(long, parse(from_os_str))]
+    input: Option<InputSource>,
+
+    /// skip part 1
+    #[structopt(long)]
+    no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    part2: bool,
+}
+
+impl RunArgs {
+    fn input(&self) -> Result<InputSource> {
+        match self.input {
+            None => {
+                let config = Config::load()?;
+                // this does nothing if the input file already exists, but
+                // simplifies the workflow after cloning the repo on a new computer
+                get_input(&config, YEAR, DAY)?;
+                Ok(config.input_for(YEAR, DAY).into())
+            }
+            Some(ref source) => Ok(source.clone()),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = RunArgs::from_args();
+    let input = args.input()?.read()?;
+
+    if !args.no_part1 {
+        println!("{}", solve_part1(&input)?);
+    }
+    if args.part2 {
+        println!("{}", solve_part2(&input)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day21::{deterministic_game, quantum_game};
+
+fn bench_dirac_dice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dirac dice");
+    for starts in [[4, 8], [1, 10], [7, 7]] {
+        let label = format!("{}-{}", starts[0], starts[1]);
+        group.bench_with_input(
+            BenchmarkId::new("deterministic", &label),
+            &starts,
+            |b, &starts| b.iter(|| deterministic_game(starts)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("quantum", &label),
+            &starts,
+            |b, &starts| b.iter(|| quantum_game(starts)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dirac_dice);
+criterion_main!(benches);
@@ -0,0 +1,168 @@
+use aoclib::input::parse_str;
+use std::{collections::HashMap, path::Path};
+
+const BOARD_SIZE: u32 = 10;
+const DETERMINISTIC_GOAL: u32 = 1000;
+const QUANTUM_GOAL: u32 = 21;
+
+/// How often each total of three rolls of the 3-sided die occurs, among the 27 universes.
+const QUANTUM_ROLLS: [(u32, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr)]
+#[display("Player {player} starting position: {position}")]
+struct StartingPosition {
+    player: u8,
+    position: u32,
+}
+
+/// Move `spaces` forward around the circular board from `position`.
+fn advance(position: u32, spaces: u32) -> u32 {
+    (position + spaces - 1) % BOARD_SIZE + 1
+}
+
+/// Play with the deterministic 100-sided die until someone reaches 1000.
+///
+/// Returns the losing score multiplied by the number of times the die was rolled.
+pub fn deterministic_game(starts: [u32; 2]) -> u64 {
+    let mut positions = starts;
+    let mut scores = [0; 2];
+    let mut die = (1..=100).cycle();
+    let mut rolls = 0;
+    for player in [0, 1].into_iter().cycle() {
+        let spaces: u32 = die.by_ref().take(3).sum();
+        rolls += 3;
+        positions[player] = advance(positions[player], spaces);
+        scores[player] += positions[player];
+        if scores[player] >= DETERMINISTIC_GOAL {
+            return u64::from(scores[1 - player]) * rolls;
+        }
+    }
+    unreachable!("players alternate forever")
+}
+
+/// The state of a quantum game, from the perspective of the player about to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Turn {
+    position: u32,
+    score: u32,
+    other_position: u32,
+    other_score: u32,
+}
+
+/// Count the universes in which each player wins, mover first.
+///
+/// Many universes share a state, so each state's outcome is computed only once.
+fn count_wins(turn: Turn, memo: &mut HashMap<Turn, [u64; 2]>) -> [u64; 2] {
+    if let Some(&wins) = memo.get(&turn) {
+        return wins;
+    }
+    let mut wins = [0; 2];
+    for (spaces, universes) in QUANTUM_ROLLS {
+        let position = advance(turn.position, spaces);
+        let score = turn.score + position;
+        if score >= QUANTUM_GOAL {
+            wins[0] += universes;
+            continue;
+        }
+        // the other player moves next
+        let [other_wins, own_wins] = count_wins(
+            Turn {
+                position: turn.other_position,
+                score: turn.other_score,
+                other_position: position,
+                other_score: score,
+            },
+            memo,
+        );
+        wins[0] += own_wins * universes;
+        wins[1] += other_wins * universes;
+    }
+    memo.insert(turn, wins);
+    wins
+}
+
+/// Play with the 3-sided quantum die to 21, returning the universes each player wins in.
+pub fn quantum_game(starts: [u32; 2]) -> [u64; 2] {
+    count_wins(
+        Turn {
+            position: starts[0],
+            score: 0,
+            other_position: starts[1],
+            other_score: 0,
+        },
+        &mut HashMap::new(),
+    )
+}
+
+fn parse_starts(input: &str) -> Result<[u32; 2], Error> {
+    let mut starts = [None; 2];
+    for start in parse_str::<StartingPosition>(input)? {
+        let player = usize::from(start.player)
+            .checked_sub(1)
+            .filter(|&idx| idx < starts.len())
+            .ok_or(Error::UnknownPlayer(start.player))?;
+        if !(1..=BOARD_SIZE).contains(&start.position) {
+            return Err(Error::OffBoard(start.position));
+        }
+        starts[player] = Some(start.position);
+    }
+    match starts {
+        [Some(first), Some(second)] => Ok([first, second]),
+        _ => Err(Error::MissingPlayer),
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<u64, Error> {
+    Ok(deterministic_game(parse_starts(input)?))
+}
+
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+/// The number of universes won by the player who wins in more of them.
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
+    let wins = quantum_game(parse_starts(input)?);
+    wins.into_iter().max().ok_or(Error::NoSolution)
+}
+
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("there is no player {0}")]
+        UnknownPlayer(u8),
+        #[error("position {0} is not on the board")]
+        OffBoard(u32),
+        #[error("both players need a starting position")]
+        MissingPlayer,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u32; 2] = [4, 8];
+
+    #[test]
+    fn board_wraps() {
+        assert_eq!(advance(7, 3), 10);
+        assert_eq!(advance(7, 5), 2);
+        assert_eq!(advance(10, 10), 10);
+    }
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(deterministic_game(EXAMPLE), 739785);
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(quantum_game(EXAMPLE), [444356092776315, 341960390180808]);
+    }
+}
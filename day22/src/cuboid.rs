@@ -0,0 +1,121 @@
+/// An axis-aligned box of integer points, with inclusive bounds on each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [i64; 3],
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// The box spanning `min..=max`, if that is not empty along any axis.
+    pub fn new(min: [i64; 3], max: [i64; 3]) -> Option<Self> {
+        (0..3)
+            .all(|axis| min[axis] <= max[axis])
+            .then_some(Cuboid { min, max })
+    }
+
+    /// The number of integer points in this box.
+    pub fn volume(&self) -> u64 {
+        (0..3)
+            .map(|axis| (self.max[axis] - self.min[axis] + 1) as u64)
+            .product()
+    }
+
+    /// The points common to both boxes, if any.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let mut min = [0; 3];
+        let mut max = [0; 3];
+        for axis in 0..3 {
+            min[axis] = self.min[axis].max(other.min[axis]);
+            max[axis] = self.max[axis].min(other.max[axis]);
+        }
+        Cuboid::new(min, max)
+    }
+
+    pub fn contains(&self, point: [i64; 3]) -> bool {
+        (0..3).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Every integer point in this box.
+    pub fn points(&self) -> impl Iterator<Item = [i64; 3]> {
+        let Cuboid { min, max } = *self;
+        (min[0]..=max[0]).flat_map(move |x| {
+            (min[1]..=max[1]).flat_map(move |y| (min[2]..=max[2]).map(move |z| [x, y, z]))
+        })
+    }
+}
+
+/// A union of boxes, tracked as a signed sum of possibly overlapping boxes.
+///
+/// Adding a box first subtracts its intersection with everything already counted,
+/// so no point is ever counted more than once. Removing a box does only the
+/// subtraction.
+#[derive(Debug, Clone, Default)]
+pub struct CuboidUnion {
+    terms: Vec<(Cuboid, i64)>,
+}
+
+impl CuboidUnion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Exclude `cuboid` from the union.
+    pub fn remove(&mut self, cuboid: &Cuboid) {
+        let corrections: Vec<_> = self
+            .terms
+            .iter()
+            .filter_map(|(term, sign)| Some((term.intersection(cuboid)?, -sign)))
+            .collect();
+        self.terms.extend(corrections);
+    }
+
+    /// Include `cuboid` in the union.
+    pub fn insert(&mut self, cuboid: Cuboid) {
+        self.remove(&cuboid);
+        self.terms.push((cuboid, 1));
+    }
+
+    /// The number of integer points in the union.
+    pub fn volume(&self) -> u64 {
+        let volume: i64 = self
+            .terms
+            .iter()
+            .map(|(term, sign)| term.volume() as i64 * sign)
+            .sum();
+        volume as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(min: i64, max: i64) -> Cuboid {
+        Cuboid::new([min; 3], [max; 3]).unwrap()
+    }
+
+    #[test]
+    fn empty_boxes_are_rejected() {
+        assert!(Cuboid::new([0, 0, 1], [0, 0, 0]).is_none());
+        assert_eq!(cube(3, 3).volume(), 1);
+    }
+
+    #[test]
+    fn intersection() {
+        assert_eq!(cube(0, 4).intersection(&cube(2, 9)), Some(cube(2, 4)));
+        assert_eq!(cube(0, 4).intersection(&cube(5, 9)), None);
+    }
+
+    #[test]
+    fn union_counts_each_point_once() {
+        let mut union = CuboidUnion::new();
+        union.insert(cube(0, 2));
+        union.insert(cube(1, 3));
+        union.insert(cube(1, 2));
+        assert_eq!(union.volume(), 27 + 27 - 8);
+        union.remove(&cube(0, 0));
+        assert_eq!(union.volume(), 27 + 27 - 8 - 1);
+        union.remove(&cube(10, 11));
+        assert_eq!(union.volume(), 45);
+    }
+}
@@ -0,0 +1,24 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day23::Burrow;
+
+const EXAMPLE: &str = "
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+
+fn bench_organize(c: &mut Criterion) {
+    let burrow: Burrow<2> = EXAMPLE.parse().expect("the example parses");
+    let unfolded = burrow.unfold();
+
+    let mut group = c.benchmark_group("organize");
+    group.sample_size(10);
+    group.bench_function("folded", |b| b.iter(|| burrow.organize()));
+    group.bench_function("unfolded", |b| b.iter(|| unfolded.organize()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_organize);
+criterion_main!(benches);
@@ -0,0 +1,523 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+    fmt,
+    path::Path,
+    str::FromStr,
+};
+
+const HALLWAY_LEN: usize = 11;
+const ROOMS: usize = 4;
+/// Hallway positions directly outside each room. Amphipods never stop on them.
+const DOORS: [usize; ROOMS] = [2, 4, 6, 8];
+/// Bits used to encode a single space of the burrow.
+const SPACE_BITS: u32 = 3;
+
+/// The rows inserted into each room when the diagram is unfolded.
+const UNFOLDED_ROWS: [[Amphipod; ROOMS]; 2] = [
+    [Amphipod::D, Amphipod::C, Amphipod::B, Amphipod::A],
+    [Amphipod::D, Amphipod::B, Amphipod::A, Amphipod::C],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Amphipod {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Amphipod {
+    const ALL: [Amphipod; ROOMS] = [Amphipod::A, Amphipod::B, Amphipod::C, Amphipod::D];
+
+    /// Energy spent per step.
+    fn energy(self) -> u32 {
+        match self {
+            Amphipod::A => 1,
+            Amphipod::B => 10,
+            Amphipod::C => 100,
+            Amphipod::D => 1000,
+        }
+    }
+
+    /// Index of the room this amphipod belongs in.
+    fn room(self) -> usize {
+        self as usize
+    }
+
+    fn from_char(ch: char) -> Option<Self> {
+        Amphipod::ALL
+            .into_iter()
+            .find(|amphipod| amphipod.to_char() == ch)
+    }
+
+    fn to_char(self) -> char {
+        (b'A' + self as u8) as char
+    }
+}
+
+type Space = Option<Amphipod>;
+
+/// The positions of every amphipod, in a burrow whose rooms hold `DEPTH` each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Burrow<const DEPTH: usize> {
+    hallway: [Space; HALLWAY_LEN],
+    /// The occupants of each room, from the doorway down.
+    rooms: [[Space; DEPTH]; ROOMS],
+}
+
+impl<const DEPTH: usize> Burrow<DEPTH> {
+    /// Pack every space into a single integer, three bits apiece.
+    ///
+    /// This keeps the search's bookkeeping small: even the unfolded burrow has only
+    /// 27 spaces.
+    pub fn encode(&self) -> u128 {
+        self.hallway
+            .iter()
+            .chain(self.rooms.iter().flatten())
+            .fold(0, |encoded, space| {
+                let code = space.map_or(0, |amphipod| amphipod as u128 + 1);
+                (encoded << SPACE_BITS) | code
+            })
+    }
+
+    /// Unpack a burrow packed by [`Burrow::encode`].
+    pub fn decode(mut encoded: u128) -> Self {
+        let mut burrow = Burrow {
+            hallway: [None; HALLWAY_LEN],
+            rooms: [[None; DEPTH]; ROOMS],
+        };
+        // the last space packed is in the lowest bits
+        let spaces = burrow
+            .rooms
+            .iter_mut()
+            .flatten()
+            .rev()
+            .chain(burrow.hallway.iter_mut().rev());
+        for space in spaces {
+            let code = (encoded & ((1 << SPACE_BITS) - 1)) as usize;
+            *space = code.checked_sub(1).map(|idx| Amphipod::ALL[idx]);
+            encoded >>= SPACE_BITS;
+        }
+        burrow
+    }
+
+    /// Whether every amphipod is in its own room.
+    pub fn is_organized(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(room, spaces)| {
+            spaces
+                .iter()
+                .all(|space| space.map(Amphipod::room) == Some(room))
+        })
+    }
+
+    /// Whether amphipods may enter `room`: it holds no strangers.
+    fn room_accepts(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|amphipod| amphipod.room() == room)
+    }
+
+    /// Whether every hallway space between `from` and `to` is empty, excluding `from`.
+    fn hallway_clear(&self, from: usize, to: usize) -> bool {
+        let between = if from < to {
+            from + 1..=to
+        } else {
+            to..=from - 1
+        };
+        self.hallway[between].iter().all(Option::is_none)
+    }
+
+    /// Every state reachable by moving a single amphipod, with the energy it costs.
+    ///
+    /// An amphipod in a room may only move to the hallway; one in the hallway may
+    /// only move into its own room, once that holds no strangers.
+    fn moves(&self) -> Vec<(Self, u32)> {
+        let mut moves = Vec::new();
+
+        for (position, space) in self.hallway.iter().enumerate() {
+            let amphipod = match space {
+                Some(amphipod) => *amphipod,
+                None => continue,
+            };
+            let room = amphipod.room();
+            if !self.room_accepts(room) || !self.hallway_clear(position, DOORS[room]) {
+                continue;
+            }
+            let depth = self.rooms[room]
+                .iter()
+                .rposition(Option::is_none)
+                .expect("a room accepting amphipods has space, or the burrow is organized");
+            let mut next = *self;
+            next.hallway[position] = None;
+            next.rooms[room][depth] = Some(amphipod);
+            let steps = position.abs_diff(DOORS[room]) + depth + 1;
+            moves.push((next, steps as u32 * amphipod.energy()));
+        }
+
+        for (room, &door) in DOORS.iter().enumerate() {
+            if self.room_accepts(room) {
+                continue;
+            }
+            let (depth, amphipod) = match self.rooms[room]
+                .iter()
+                .enumerate()
+                .find_map(|(depth, space)| space.map(|amphipod| (depth, amphipod)))
+            {
+                Some(top) => top,
+                None => continue,
+            };
+            for position in 0..HALLWAY_LEN {
+                if DOORS.contains(&position) || !self.hallway_clear(door, position) {
+                    continue;
+                }
+                let mut next = *self;
+                next.rooms[room][depth] = None;
+                next.hallway[position] = Some(amphipod);
+                let steps = depth + 1 + door.abs_diff(position);
+                moves.push((next, steps as u32 * amphipod.energy()));
+            }
+        }
+
+        moves
+    }
+
+    /// A lower bound on the energy still required to organize the burrow.
+    ///
+    /// Each amphipod not yet settled must at least reach the top of its own room.
+    fn remaining_energy(&self) -> u32 {
+        let mut energy = 0;
+        for (position, space) in self.hallway.iter().enumerate() {
+            if let Some(amphipod) = space {
+                let steps = position.abs_diff(DOORS[amphipod.room()]) + 1;
+                energy += steps as u32 * amphipod.energy();
+            }
+        }
+        for (room, spaces) in self.rooms.iter().enumerate() {
+            // the deepest stranger has to leave, and so does everything above it
+            let unsettled = spaces
+                .iter()
+                .rposition(|space| space.map(Amphipod::room) != Some(room))
+                .map_or(0, |stranger| stranger + 1);
+            for (depth, space) in spaces.iter().enumerate().take(unsettled) {
+                if let Some(amphipod) = space {
+                    // leaving and reentering the same room needs a step aside and back
+                    let across = DOORS[room].abs_diff(DOORS[amphipod.room()]).max(2);
+                    let steps = depth + 1 + across + 1;
+                    energy += steps as u32 * amphipod.energy();
+                }
+            }
+        }
+        energy
+    }
+
+    /// Find the cheapest way to organize the burrow, by A* search.
+    pub fn organize(&self) -> Option<Solution<DEPTH>> {
+        let start = self.encode();
+        let mut energy_to = HashMap::new();
+        let mut came_from = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        energy_to.insert(start, 0);
+        queue.push(Reverse((self.remaining_energy(), 0, start)));
+
+        while let Some(Reverse((_, energy, encoded))) = queue.pop() {
+            if energy > energy_to[&encoded] {
+                // superseded by a cheaper route
+                continue;
+            }
+            let burrow = Self::decode(encoded);
+            if burrow.is_organized() {
+                let mut states = vec![burrow];
+                let mut current = encoded;
+                while let Some(&previous) = came_from.get(&current) {
+                    states.push(Self::decode(previous));
+                    current = previous;
+                }
+                states.reverse();
+                return Some(Solution { energy, states });
+            }
+            for (next, cost) in burrow.moves() {
+                let next_energy = energy + cost;
+                let next_encoded = next.encode();
+                match energy_to.entry(next_encoded) {
+                    Entry::Occupied(entry) if *entry.get() <= next_energy => continue,
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(next_energy);
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(next_energy);
+                    }
+                }
+                came_from.insert(next_encoded, encoded);
+                queue.push(Reverse((
+                    next_energy + next.remaining_energy(),
+                    next_energy,
+                    next_encoded,
+                )));
+            }
+        }
+        None
+    }
+}
+
+impl Burrow<2> {
+    /// Insert the two hidden rows into the middle of every room.
+    pub fn unfold(&self) -> Burrow<4> {
+        let mut rooms = [[None; 4]; ROOMS];
+        for (room, spaces) in rooms.iter_mut().enumerate() {
+            *spaces = [
+                self.rooms[room][0],
+                Some(UNFOLDED_ROWS[0][room]),
+                Some(UNFOLDED_ROWS[1][room]),
+                self.rooms[room][1],
+            ];
+        }
+        Burrow {
+            hallway: self.hallway,
+            rooms,
+        }
+    }
+}
+
+impl<const DEPTH: usize> FromStr for Burrow<DEPTH> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_space = |ch: char| match ch {
+            '.' => Ok(None),
+            _ => Amphipod::from_char(ch).map(Some).ok_or(Error::Malformed),
+        };
+
+        let mut lines = s.trim().lines().map(str::trim);
+        if lines.next() != Some("#############") {
+            return Err(Error::Malformed);
+        }
+        let hallway = lines
+            .next()
+            .and_then(|line| line.strip_prefix('#'))
+            .and_then(|line| line.strip_suffix('#'))
+            .filter(|line| line.len() == HALLWAY_LEN)
+            .ok_or(Error::Malformed)?;
+        let mut burrow = Burrow {
+            hallway: [None; HALLWAY_LEN],
+            rooms: [[None; DEPTH]; ROOMS],
+        };
+        for (space, ch) in burrow.hallway.iter_mut().zip(hallway.chars()) {
+            *space = parse_space(ch)?;
+        }
+
+        for depth in 0..DEPTH {
+            let line = lines.next().ok_or(Error::WrongDepth)?;
+            let spaces: Vec<_> = line.split('#').filter(|part| !part.is_empty()).collect();
+            if spaces.len() != ROOMS {
+                return Err(Error::WrongDepth);
+            }
+            for (room, space) in spaces.into_iter().enumerate() {
+                let mut chars = space.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => burrow.rooms[room][depth] = parse_space(ch)?,
+                    _ => return Err(Error::Malformed),
+                }
+            }
+        }
+        if lines.next() != Some("#########") || lines.next().is_some() {
+            return Err(Error::WrongDepth);
+        }
+
+        // the search assumes each room's occupants exactly fill it
+        for amphipod in Amphipod::ALL {
+            let count = burrow
+                .hallway
+                .iter()
+                .chain(burrow.rooms.iter().flatten())
+                .filter(|&&space| space == Some(amphipod))
+                .count();
+            if count != DEPTH {
+                return Err(Error::Malformed);
+            }
+        }
+        Ok(burrow)
+    }
+}
+
+/// Draw the burrow as the puzzle does.
+impl<const DEPTH: usize> fmt::Display for Burrow<DEPTH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let to_char = |space: &Space| space.map_or('.', Amphipod::to_char);
+        writeln!(f, "#############")?;
+        write!(f, "#")?;
+        for space in &self.hallway {
+            write!(f, "{}", to_char(space))?;
+        }
+        writeln!(f, "#")?;
+        for depth in 0..DEPTH {
+            write!(f, "{}", if depth == 0 { "###" } else { "  #" })?;
+            for spaces in &self.rooms {
+                write!(f, "{}#", to_char(&spaces[depth]))?;
+            }
+            writeln!(f, "{}", if depth == 0 { "##" } else { "" })?;
+        }
+        writeln!(f, "  #########")
+    }
+}
+
+/// The cheapest way to organize a burrow.
+#[derive(Debug, Clone)]
+pub struct Solution<const DEPTH: usize> {
+    pub energy: u32,
+    /// Every state of the burrow along the way, from the initial to the organized one.
+    pub states: Vec<Burrow<DEPTH>>,
+}
+
+/// Displays the energy spent; the alternate form (`{:#}`) shows every state along the way.
+impl<const DEPTH: usize> fmt::Display for Solution<DEPTH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write!(f, "{}", self.energy);
+        }
+        let mut energy = 0;
+        for (idx, state) in self.states.iter().enumerate() {
+            if idx > 0 {
+                energy += self.states[idx - 1]
+                    .moves()
+                    .into_iter()
+                    .find_map(|(next, cost)| (next == *state).then_some(cost))
+                    .expect("consecutive states are one move apart");
+                writeln!(f)?;
+            }
+            writeln!(f, "energy spent: {}", energy)?;
+            write!(f, "{}", state)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Solution<2>, Error> {
+    input
+        .parse::<Burrow<2>>()?
+        .organize()
+        .ok_or(Error::NoSolution)
+}
+
+pub fn part1(input: &Path) -> Result<Solution<2>, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Solution<4>, Error> {
+    input
+        .parse::<Burrow<2>>()?
+        .unfold()
+        .organize()
+        .ok_or(Error::NoSolution)
+}
+
+pub fn part2(input: &Path) -> Result<Solution<4>, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("malformed burrow diagram")]
+        Malformed,
+        #[error("rooms in the diagram have the wrong depth")]
+        WrongDepth,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+
+    fn example() -> Burrow<2> {
+        EXAMPLE.parse().unwrap()
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let burrow = example();
+        assert_eq!(burrow.to_string(), EXAMPLE.trim_start());
+        let unfolded = burrow.unfold();
+        assert_eq!(unfolded.to_string().parse::<Burrow<4>>().unwrap(), unfolded);
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        let burrow = example();
+        assert_eq!(Burrow::decode(burrow.encode()), burrow);
+        for (next, _) in burrow.unfold().moves() {
+            assert_eq!(Burrow::decode(next.encode()), next);
+        }
+    }
+
+    #[test]
+    fn wrong_depth() {
+        assert!(matches!(
+            EXAMPLE.parse::<Burrow<4>>(),
+            Err(Error::WrongDepth)
+        ));
+    }
+
+    #[test]
+    fn wrong_amphipod_count() {
+        let extra = "
+#############
+#A..........#
+###A#C#B#D###
+  #A#D#C#B#
+  #########
+";
+        assert!(matches!(extra.parse::<Burrow<2>>(), Err(Error::Malformed)));
+
+        let missing = "
+#############
+#...........#
+###.#C#B#D###
+  #A#D#C#B#
+  #########
+";
+        assert!(matches!(
+            missing.parse::<Burrow<2>>(),
+            Err(Error::Malformed)
+        ));
+    }
+
+    #[test]
+    fn example_part1() {
+        let solution = example().organize().unwrap();
+        assert_eq!(solution.energy, 12521);
+        assert_eq!(solution.to_string(), "12521");
+        assert_eq!(solution.states.first(), Some(&example()));
+        assert!(solution.states.last().unwrap().is_organized());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(example().unfold().organize().unwrap().energy, 44169);
+    }
+
+    #[test]
+    fn remaining_energy_is_a_lower_bound() {
+        let solution = example().organize().unwrap();
+        let mut spent = 0;
+        for pair in solution.states.windows(2) {
+            assert!(pair[0].remaining_energy() <= solution.energy - spent);
+            spent += pair[0]
+                .moves()
+                .into_iter()
+                .find_map(|(next, cost)| (next == pair[1]).then_some(cost))
+                .unwrap();
+        }
+        assert_eq!(spent, solution.energy);
+    }
+}
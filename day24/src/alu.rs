@@ -0,0 +1,214 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "lowercase")]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+/// The second argument of an instruction: a register or a literal number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Literal(i64),
+}
+
+impl FromStr for Operand {
+    type Err = parse_display::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(register) => Ok(Operand::Register(register)),
+            Err(_) => s
+                .parse()
+                .map(Operand::Literal)
+                .map_err(|_| parse_display::ParseError::new()),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Literal(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+pub enum Instruction {
+    #[display("inp {0}")]
+    Inp(Register),
+    #[display("add {0} {1}")]
+    Add(Register, Operand),
+    #[display("mul {0} {1}")]
+    Mul(Register, Operand),
+    #[display("div {0} {1}")]
+    Div(Register, Operand),
+    #[display("mod {0} {1}")]
+    Mod(Register, Operand),
+    #[display("eql {0} {1}")]
+    Eql(Register, Operand),
+}
+
+/// The arithmetic logic unit: four integer registers, initially zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register as usize]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Literal(value) => value,
+        }
+    }
+
+    /// Execute a single instruction, reading from `input` if it needs to.
+    pub fn execute(
+        &mut self,
+        instruction: Instruction,
+        input: &mut impl Iterator<Item = i64>,
+    ) -> Result<(), AluError> {
+        let (register, value) = match instruction {
+            Instruction::Inp(register) => (register, input.next().ok_or(AluError::NoInput)?),
+            Instruction::Add(register, operand) => {
+                (register, self.get(register) + self.value(operand))
+            }
+            Instruction::Mul(register, operand) => {
+                (register, self.get(register) * self.value(operand))
+            }
+            Instruction::Div(register, operand) => {
+                let divisor = self.value(operand);
+                if divisor == 0 {
+                    return Err(AluError::DivideByZero(instruction));
+                }
+                // integer division truncates toward zero, as the ALU's does
+                (register, self.get(register) / divisor)
+            }
+            Instruction::Mod(register, operand) => {
+                let (dividend, divisor) = (self.get(register), self.value(operand));
+                if dividend < 0 || divisor <= 0 {
+                    return Err(AluError::InvalidModulo(instruction));
+                }
+                (register, dividend % divisor)
+            }
+            Instruction::Eql(register, operand) => {
+                (register, (self.get(register) == self.value(operand)) as i64)
+            }
+        };
+        self.registers[register as usize] = value;
+        Ok(())
+    }
+
+    /// Execute every instruction of `program` in order.
+    pub fn run(
+        &mut self,
+        program: &[Instruction],
+        input: impl IntoIterator<Item = i64>,
+    ) -> Result<(), AluError> {
+        let mut input = input.into_iter();
+        for &instruction in program {
+            self.execute(instruction, &mut input)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum AluError {
+    #[error("the program read more input than was given")]
+    NoInput,
+    #[error("division by zero: {0}")]
+    DivideByZero(Instruction),
+    #[error("modulo of a negative number or by a nonpositive one: {0}")]
+    InvalidModulo(Instruction),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(source: &str) -> Vec<Instruction> {
+        source
+            .trim()
+            .lines()
+            .map(|line| line.trim().parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn negate() {
+        let program = program("inp x\nmul x -1");
+        let mut alu = Alu::new();
+        alu.run(&program, [7]).unwrap();
+        assert_eq!(alu.get(Register::X), -7);
+    }
+
+    #[test]
+    fn three_times() {
+        let program = program("inp z\ninp x\nmul z 3\neql z x");
+        for (input, expect) in [([2, 6], 1), ([2, 5], 0), ([-3, -9], 1)] {
+            let mut alu = Alu::new();
+            alu.run(&program, input).unwrap();
+            assert_eq!(alu.get(Register::Z), expect, "input {:?}", input);
+        }
+    }
+
+    #[test]
+    fn binary() {
+        let program = program(
+            "
+            inp w
+            add z w
+            mod z 2
+            div w 2
+            add y w
+            mod y 2
+            div w 2
+            add x w
+            mod x 2
+            div w 2
+            mod w 2
+            ",
+        );
+        let mut alu = Alu::new();
+        alu.run(&program, [10]).unwrap();
+        let bits = [Register::W, Register::X, Register::Y, Register::Z].map(|r| alu.get(r));
+        assert_eq!(bits, [1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut alu = Alu::new();
+        assert_eq!(alu.run(&program("inp w"), []), Err(AluError::NoInput));
+        assert!(matches!(
+            alu.run(&program("div w 0"), []),
+            Err(AluError::DivideByZero(_))
+        ));
+        assert!(matches!(
+            alu.run(&program("add w -1\nmod w 2"), []),
+            Err(AluError::InvalidModulo(_))
+        ));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let source = "inp w\nadd x -12\nmul y z\ndiv z 26\nmod x 26\neql x w";
+        let rendered: Vec<_> = program(source).iter().map(ToString::to_string).collect();
+        assert_eq!(rendered.join("\n"), source);
+    }
+}
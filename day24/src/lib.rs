@@ -0,0 +1,272 @@
+pub mod alu;
+
+use alu::{Alu, AluError, Instruction, Operand, Register};
+use aoclib::input::parse_str;
+use std::path::Path;
+
+const DIGITS: usize = 14;
+const BLOCK_LEN: usize = 18;
+
+/// The parameters of one of MONAD's fourteen blocks, each of which reads one digit.
+///
+/// Each block treats `z` as a stack of base-26 numbers. Blocks which divide `z` by
+/// 1 push `digit + y_offset`; blocks which divide by 26 pop the top value, and
+/// push again unless `top + x_offset == digit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    pub divisor: i64,
+    pub x_offset: i64,
+    pub y_offset: i64,
+}
+
+impl Block {
+    /// The instructions implementing this block.
+    pub fn instructions(&self) -> Vec<Instruction> {
+        use Instruction::*;
+        use Operand::Literal;
+        use Register::*;
+
+        let reg = Operand::Register;
+        vec![
+            Inp(W),
+            Mul(X, Literal(0)),
+            Add(X, reg(Z)),
+            Mod(X, Literal(26)),
+            Div(Z, Literal(self.divisor)),
+            Add(X, Literal(self.x_offset)),
+            Eql(X, reg(W)),
+            Eql(X, Literal(0)),
+            Mul(Y, Literal(0)),
+            Add(Y, Literal(25)),
+            Mul(Y, reg(X)),
+            Add(Y, Literal(1)),
+            Mul(Z, reg(Y)),
+            Mul(Y, Literal(0)),
+            Add(Y, reg(W)),
+            Add(Y, Literal(self.y_offset)),
+            Mul(Y, reg(X)),
+            Add(Z, reg(Y)),
+        ]
+    }
+
+    /// Recognize a block of MONAD, by comparing it to the instructions its
+    /// parameters would produce.
+    fn extract(instructions: &[Instruction]) -> Option<Block> {
+        let literal = |idx: usize| match instructions.get(idx)? {
+            Instruction::Div(_, Operand::Literal(value))
+            | Instruction::Add(_, Operand::Literal(value)) => Some(*value),
+            _ => None,
+        };
+        let block = Block {
+            divisor: literal(4)?,
+            x_offset: literal(5)?,
+            y_offset: literal(15)?,
+        };
+        (block.instructions() == instructions).then_some(block)
+    }
+}
+
+/// Split MONAD into its blocks.
+pub fn extract_blocks(program: &[Instruction]) -> Result<Vec<Block>, Error> {
+    if program.len() != DIGITS * BLOCK_LEN {
+        return Err(Error::UnrecognizedProgram);
+    }
+    program
+        .chunks(BLOCK_LEN)
+        .map(|chunk| Block::extract(chunk).ok_or(Error::UnrecognizedProgram))
+        .collect()
+}
+
+/// `digits[pop] == digits[push] + offset`: MONAD accepts the number only if every
+/// block which can pop does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub push: usize,
+    pub pop: usize,
+    pub offset: i64,
+}
+
+/// Pair each popping block with the block whose value it pops.
+pub fn constraints(blocks: &[Block]) -> Result<Vec<Constraint>, Error> {
+    let mut stack = Vec::new();
+    let mut constraints = Vec::new();
+    for (idx, block) in blocks.iter().enumerate() {
+        match block.divisor {
+            1 => stack.push(idx),
+            26 => {
+                let push = stack.pop().ok_or(Error::Unsatisfiable)?;
+                constraints.push(Constraint {
+                    push,
+                    pop: idx,
+                    offset: blocks[push].y_offset + block.x_offset,
+                });
+            }
+            _ => return Err(Error::UnrecognizedProgram),
+        }
+    }
+    if !stack.is_empty() {
+        return Err(Error::Unsatisfiable);
+    }
+    Ok(constraints)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extreme {
+    Largest,
+    Smallest,
+}
+
+/// The most extreme digits which satisfy every constraint.
+pub fn model_number(constraints: &[Constraint], extreme: Extreme) -> Result<[i64; DIGITS], Error> {
+    let mut digits = [0; DIGITS];
+    for &Constraint { push, pop, offset } in constraints {
+        // the pushed digit, and the pushed digit plus the offset, must both be in 1..=9
+        let low = 1.max(1 - offset);
+        let high = 9.min(9 - offset);
+        if low > high {
+            return Err(Error::Unsatisfiable);
+        }
+        let digit = match extreme {
+            Extreme::Largest => high,
+            Extreme::Smallest => low,
+        };
+        digits[push] = digit;
+        digits[pop] = digit + offset;
+    }
+    Ok(digits)
+}
+
+/// Whether MONAD accepts these digits, according to the ALU.
+pub fn is_accepted(program: &[Instruction], digits: &[i64]) -> Result<bool, Error> {
+    let mut alu = Alu::new();
+    alu.run(program, digits.iter().copied())?;
+    Ok(alu.get(Register::Z) == 0)
+}
+
+/// Find the extreme model number MONAD accepts, and confirm it on the ALU.
+pub fn solve(program: &[Instruction], extreme: Extreme) -> Result<String, Error> {
+    let blocks = extract_blocks(program)?;
+    let digits = model_number(&constraints(&blocks)?, extreme)?;
+    let model_number: String = digits.iter().map(ToString::to_string).collect();
+    if !is_accepted(program, &digits)? {
+        return Err(Error::Rejected(model_number));
+    }
+    Ok(model_number)
+}
+
+pub fn solve_part1(input: &str) -> Result<String, Error> {
+    let program: Vec<Instruction> = parse_str(input)?.collect();
+    solve(&program, Extreme::Largest)
+}
+
+pub fn part1(input: &Path) -> Result<String, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<String, Error> {
+    let program: Vec<Instruction> = parse_str(input)?.collect();
+    solve(&program, Extreme::Smallest)
+}
+
+pub fn part2(input: &Path) -> Result<String, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error(transparent)]
+        Alu(#[from] AluError),
+        #[error("program is not a recognizable MONAD")]
+        UnrecognizedProgram,
+        #[error("no model number satisfies MONAD")]
+        Unsatisfiable,
+        #[error("MONAD rejected derived model number {0}")]
+        Rejected(String),
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A MONAD shaped like the puzzle inputs.
+    const PARAMETERS: [(i64, i64, i64); DIGITS] = [
+        (1, 12, 4),
+        (1, 11, 11),
+        (1, 13, 5),
+        (1, 11, 11),
+        (1, 14, 14),
+        (26, -10, 7),
+        (1, 11, 11),
+        (26, -9, 4),
+        (26, -3, 6),
+        (1, 13, 5),
+        (26, -5, 9),
+        (26, -10, 12),
+        (26, -4, 14),
+        (26, -5, 14),
+    ];
+
+    fn monad() -> Vec<Instruction> {
+        PARAMETERS
+            .iter()
+            .flat_map(|&(divisor, x_offset, y_offset)| {
+                Block {
+                    divisor,
+                    x_offset,
+                    y_offset,
+                }
+                .instructions()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blocks_are_recognized() {
+        let blocks = extract_blocks(&monad()).unwrap();
+        assert_eq!(blocks.len(), DIGITS);
+        assert_eq!(
+            blocks[5],
+            Block {
+                divisor: 26,
+                x_offset: -10,
+                y_offset: 7
+            }
+        );
+
+        let mut altered = monad();
+        altered[20] = Instruction::Add(Register::Y, Operand::Literal(1));
+        assert!(matches!(
+            extract_blocks(&altered),
+            Err(Error::UnrecognizedProgram)
+        ));
+    }
+
+    #[test]
+    fn largest() {
+        assert_eq!(solve(&monad(), Extreme::Largest).unwrap(), "92915979999498");
+    }
+
+    #[test]
+    fn smallest() {
+        assert_eq!(
+            solve(&monad(), Extreme::Smallest).unwrap(),
+            "21611513911181"
+        );
+    }
+
+    #[test]
+    fn constraint_violations_are_rejected() {
+        let program = monad();
+        let mut digits = model_number(
+            &constraints(&extract_blocks(&program).unwrap()).unwrap(),
+            Extreme::Largest,
+        )
+        .unwrap();
+        assert!(is_accepted(&program, &digits).unwrap());
+        digits[13] -= 1;
+        assert!(!is_accepted(&program, &digits).unwrap());
+    }
+}
@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day25::{double_buffered::DoubleBuffered, SeaFloor, Stepper};
+
+const STEPS: usize = 100;
+
+/// A map the size of a puzzle input, with a deterministic scattering of both herds.
+fn map(width: usize, height: usize) -> SeaFloor {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut map = String::with_capacity((width + 1) * height);
+    for _ in 0..height {
+        for _ in 0..width {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            map.push(match state % 5 {
+                0 | 1 => '.',
+                2 | 3 => '>',
+                _ => 'v',
+            });
+        }
+        map.push('\n');
+    }
+    map.parse().expect("the generated map parses")
+}
+
+fn run(mut stepper: impl Stepper) {
+    for _ in 0..STEPS {
+        stepper.step();
+    }
+}
+
+fn bench_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    for size in [139, 500] {
+        let sea_floor = map(size, size);
+        group.bench_with_input(
+            BenchmarkId::new("sea floor", size),
+            &sea_floor,
+            |b, sea_floor| b.iter(|| run(sea_floor.clone())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("double buffered", size),
+            &sea_floor,
+            |b, sea_floor| b.iter(|| run(DoubleBuffered::from(sea_floor.clone()))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_step);
+criterion_main!(benches);
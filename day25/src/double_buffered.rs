@@ -0,0 +1,135 @@
+//! A sea floor which never allocates while stepping.
+//!
+//! Each herd's move is computed from the front buffer into the back buffer, which
+//! then becomes the front buffer for the next move.
+
+use crate::{destination, Cell, SeaFloor, Stepper};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct DoubleBuffered {
+    width: usize,
+    height: usize,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+}
+
+impl From<SeaFloor> for DoubleBuffered {
+    fn from(sea_floor: SeaFloor) -> Self {
+        DoubleBuffered {
+            width: sea_floor.width,
+            height: sea_floor.height,
+            back: vec![Cell::Empty; sea_floor.cells.len()],
+            front: sea_floor.cells,
+        }
+    }
+}
+
+impl From<DoubleBuffered> for SeaFloor {
+    fn from(buffered: DoubleBuffered) -> Self {
+        SeaFloor {
+            width: buffered.width,
+            height: buffered.height,
+            cells: buffered.front,
+        }
+    }
+}
+
+/// The back buffer is scratch space, so only the front buffer is compared.
+impl PartialEq for DoubleBuffered {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.front == other.front
+    }
+}
+
+impl Eq for DoubleBuffered {}
+
+impl fmt::Display for DoubleBuffered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.front.chunks(self.width) {
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl DoubleBuffered {
+    /// The index of the cell from which a member of `herd` would move into `idx`.
+    fn source(&self, idx: usize, herd: Cell) -> usize {
+        let (x, y) = (idx % self.width, idx / self.width);
+        match herd {
+            Cell::East => y * self.width + (x + self.width - 1) % self.width,
+            Cell::South => (y + self.height - 1) % self.height * self.width + x,
+            Cell::Empty => unreachable!("empty cells do not move"),
+        }
+    }
+
+    fn move_herd(&mut self, herd: Cell) -> bool {
+        let mut moved = false;
+        for idx in 0..self.front.len() {
+            let cell = self.front[idx];
+            self.back[idx] = if cell == herd
+                && self.front[destination(self.width, self.height, idx, herd)] == Cell::Empty
+            {
+                moved = true;
+                Cell::Empty
+            } else if cell == Cell::Empty && self.front[self.source(idx, herd)] == herd {
+                herd
+            } else {
+                cell
+            };
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+        moved
+    }
+}
+
+impl Stepper for DoubleBuffered {
+    fn step(&mut self) -> bool {
+        let east = self.move_herd(Cell::East);
+        let south = self.move_herd(Cell::South);
+        east || south
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::first_stalled_step;
+
+    const EXAMPLE: &str = "
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+";
+
+    #[test]
+    fn agrees_with_sea_floor() {
+        let mut sea_floor: SeaFloor = EXAMPLE.parse().unwrap();
+        let mut buffered = DoubleBuffered::from(sea_floor.clone());
+        loop {
+            let moved = sea_floor.step();
+            assert_eq!(buffered.step(), moved);
+            assert_eq!(buffered.to_string(), sea_floor.to_string());
+            if !moved {
+                break;
+            }
+        }
+        assert_eq!(SeaFloor::from(buffered), sea_floor);
+    }
+
+    #[test]
+    fn example() {
+        let mut buffered = DoubleBuffered::from(EXAMPLE.parse::<SeaFloor>().unwrap());
+        assert_eq!(first_stalled_step(&mut buffered).unwrap(), 58);
+    }
+}
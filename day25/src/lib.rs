@@ -0,0 +1,250 @@
+pub mod double_buffered;
+
+use double_buffered::DoubleBuffered;
+use std::{fmt, path::Path, str::FromStr};
+
+/// A simulation which advances one step at a time.
+pub trait Stepper {
+    /// Advance one step, returning whether anything changed.
+    fn step(&mut self) -> bool;
+}
+
+/// The number of the first step on which nothing changes.
+///
+/// Not every map stalls: a herd which fills its whole row or column can keep
+/// moving forever. The stepper is checkpointed at each power-of-two step, so a
+/// map which cycles returns to some checkpoint and fails with `NoSolution`.
+pub fn first_stalled_step<S>(stepper: &mut S) -> Result<usize, Error>
+where
+    S: Stepper + Clone + PartialEq,
+{
+    let mut checkpoint = stepper.clone();
+    for step in 1_usize.. {
+        if !stepper.step() {
+            return Ok(step);
+        }
+        if *stepper == checkpoint {
+            return Err(Error::NoSolution);
+        }
+        if step.is_power_of_two() {
+            checkpoint = stepper.clone();
+        }
+    }
+    unreachable!("a cycle reaches a checkpoint long before the step counter overflows")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '.' => Some(Cell::Empty),
+            '>' => Some(Cell::East),
+            'v' => Some(Cell::South),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v',
+        }
+    }
+}
+
+/// The index of the cell each member of `herd` at `idx` would move into.
+///
+/// Both herds wrap around the edges of the map.
+fn destination(width: usize, height: usize, idx: usize, herd: Cell) -> usize {
+    let (x, y) = (idx % width, idx / width);
+    match herd {
+        Cell::East => y * width + (x + 1) % width,
+        Cell::South => (y + 1) % height * width + x,
+        Cell::Empty => unreachable!("empty cells do not move"),
+    }
+}
+
+/// The sea cucumbers on the sea floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaFloor {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl FromStr for SeaFloor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for line in s.trim().lines() {
+            let line = line.trim();
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(Error::Ragged);
+            }
+            for ch in line.chars() {
+                cells.push(Cell::from_char(ch).ok_or(Error::UnknownCell(ch))?);
+            }
+        }
+        let width = width.filter(|&width| width > 0).ok_or(Error::Empty)?;
+        Ok(SeaFloor {
+            width,
+            height: cells.len() / width,
+            cells,
+        })
+    }
+}
+
+impl fmt::Display for SeaFloor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl SeaFloor {
+    /// Move every member of `herd` which faces an empty cell, all at once.
+    fn move_herd(&mut self, herd: Cell) -> bool {
+        let moves: Vec<_> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == herd)
+            .map(|(idx, _)| (idx, destination(self.width, self.height, idx, herd)))
+            .filter(|&(_, destination)| self.cells[destination] == Cell::Empty)
+            .collect();
+        for &(from, to) in &moves {
+            self.cells[from] = Cell::Empty;
+            self.cells[to] = herd;
+        }
+        !moves.is_empty()
+    }
+}
+
+/// The east-facing herd moves first, then the south-facing herd.
+impl Stepper for SeaFloor {
+    fn step(&mut self) -> bool {
+        let east = self.move_herd(Cell::East);
+        let south = self.move_herd(Cell::South);
+        east || south
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<usize, Error> {
+    let sea_floor: SeaFloor = input.parse()?;
+    first_stalled_step(&mut DoubleBuffered::from(sea_floor))
+}
+
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+/// Day 25 has no part 2 puzzle.
+pub fn solve_part2(_input: &str) -> Result<&'static str, Error> {
+    Ok("there is no part 2: remotely start the sleigh")
+}
+
+pub fn part2(input: &Path) -> Result<&'static str, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {
+        #[error("unknown cell {0:?}")]
+        UnknownCell(char),
+        #[error("rows of the map have different lengths")]
+        Ragged,
+        #[error("the map is empty")]
+        Empty,
+    }
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+";
+
+    #[test]
+    fn herd_order() {
+        let mut sea_floor: SeaFloor = "...>>>>>...".parse().unwrap();
+        sea_floor.step();
+        assert_eq!(sea_floor.to_string(), "...>>>>.>..\n");
+        sea_floor.step();
+        assert_eq!(sea_floor.to_string(), "...>>>.>.>.\n");
+    }
+
+    #[test]
+    fn wraparound() {
+        let mut sea_floor: SeaFloor = "..........
+.>v....v..
+.......>..
+.........."
+            .parse()
+            .unwrap();
+        sea_floor.step();
+        assert_eq!(
+            sea_floor.to_string(),
+            "..........\n.>........\n..v....v>.\n..........\n"
+        );
+
+        let mut edge: SeaFloor = ".>\nv.".parse().unwrap();
+        edge.step();
+        assert_eq!(edge.to_string(), ">.\nv.\n");
+        edge.step();
+        assert_eq!(edge.to_string(), "v>\n..\n");
+    }
+
+    #[test]
+    fn example() {
+        let mut sea_floor: SeaFloor = EXAMPLE.parse().unwrap();
+        assert_eq!(first_stalled_step(&mut sea_floor).unwrap(), 58);
+    }
+
+    #[test]
+    fn never_stalls() {
+        for map in [">.", ">>.", "v\n.", ".>\n>v\nv.", "..>..\n.....\n>..v."] {
+            let mut sea_floor: SeaFloor = map.parse().unwrap();
+            assert!(matches!(
+                first_stalled_step(&mut sea_floor),
+                Err(Error::NoSolution)
+            ));
+            assert!(matches!(solve_part1(map), Err(Error::NoSolution)));
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(matches!("..\n...".parse::<SeaFloor>(), Err(Error::Ragged)));
+        assert!(matches!(
+            ".x".parse::<SeaFloor>(),
+            Err(Error::UnknownCell('x'))
+        ));
+        assert!(matches!("".parse::<SeaFloor>(), Err(Error::Empty)));
+    }
+}
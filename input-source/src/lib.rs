@@ -0,0 +1,70 @@
+//! Where a day's puzzle input comes from: a file, or standard input.
+
+use std::{
+    ffi::OsStr,
+    fmt,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// The name which stands for standard input on the command line.
+pub const STDIN: &str = "-";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    Path(PathBuf),
+}
+
+impl From<&OsStr> for InputSource {
+    fn from(arg: &OsStr) -> Self {
+        if arg == STDIN {
+            InputSource::Stdin
+        } else {
+            InputSource::Path(arg.into())
+        }
+    }
+}
+
+impl From<PathBuf> for InputSource {
+    fn from(path: PathBuf) -> Self {
+        InputSource::Path(path)
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl InputSource {
+    /// Read the whole input.
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            InputSource::Path(path) => std::fs::read_to_string(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_is_stdin() {
+        assert_eq!(InputSource::from(OsStr::new("-")), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from(OsStr::new("input.txt")),
+            InputSource::Path("input.txt".into())
+        );
+    }
+}
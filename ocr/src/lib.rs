@@ -0,0 +1,145 @@
+//! Read the dot-matrix letters which some puzzles draw as their answers.
+//!
+//! Letters are 4 dots wide and 6 tall, with a blank column between adjacent letters.
+
+use aoclib::geometry::{tile::Bool, Map, Point};
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+const SPACING: usize = 1;
+
+/// Each known letter, with its dots listed row by row from the top.
+const GLYPHS: &[(char, &str)] = &[
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// The dots of the glyph starting at column `left`, row by row from the top.
+///
+/// Columns beyond the right edge of the map are blank.
+fn glyph_at(rows: &[Vec<bool>], left: usize) -> String {
+    rows.iter()
+        .flat_map(|row| (left..left + GLYPH_WIDTH).map(|x| row.get(x).copied().unwrap_or_default()))
+        .map(|dot| if dot { '#' } else { '.' })
+        .collect()
+}
+
+/// Read the letters drawn on `map`.
+///
+/// The map must be exactly one letter tall, with its first letter at its left edge.
+pub fn recognize(map: &Map<Bool>) -> Result<String, Error> {
+    let rows: Vec<Vec<bool>> = (map.low_y()..=map.high_y())
+        .rev()
+        .map(|y| {
+            (map.low_x()..=map.high_x())
+                .map(|x| bool::from(map[Point::new(x, y)]))
+                .collect()
+        })
+        .collect();
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(Error::Height(rows.len()));
+    }
+
+    (0..map.width())
+        .step_by(GLYPH_WIDTH + SPACING)
+        .enumerate()
+        .map(|(position, left)| {
+            let glyph = glyph_at(&rows, left);
+            GLYPHS
+                .iter()
+                .find(|(_, dots)| *dots == glyph)
+                .map(|&(letter, _)| letter)
+                .ok_or(Error::UnknownGlyph(position, glyph))
+        })
+        .collect()
+}
+
+/// Draw `glyph` with one row per line.
+fn draw(glyph: &str) -> String {
+    glyph
+        .as_bytes()
+        .chunks(GLYPH_WIDTH)
+        .map(|row| format!("{}\n", String::from_utf8_lossy(row)))
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("letters are {} dots tall, but the map is {0}", GLYPH_HEIGHT)]
+    Height(usize),
+    #[error("unknown letter at position {0}:\n{}", draw(.1))]
+    UnknownGlyph(usize, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(drawing: &str) -> Map<Bool> {
+        let rows: Vec<&str> = drawing.trim().lines().collect();
+        let mut map = Map::<Bool>::new(rows[0].len(), rows.len());
+        let top = map.high_y();
+        for (row, line) in rows.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                map[Point::new(x as i32, top - row as i32)] = (ch == '#').into();
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn glyphs_are_distinct() {
+        for (idx, (letter, dots)) in GLYPHS.iter().enumerate() {
+            assert_eq!(dots.len(), GLYPH_WIDTH * GLYPH_HEIGHT, "{}", letter);
+            assert!(GLYPHS[idx + 1..].iter().all(|(_, other)| other != dots));
+        }
+    }
+
+    #[test]
+    fn recognize_word() {
+        let drawing = "
+#..#.####.#....#.....##.
+#..#.#....#....#....#..#
+####.###..#....#....#..#
+#..#.#....#....#....#..#
+#..#.#....#....#....#..#
+#..#.####.####.####..##.
+";
+        assert_eq!(recognize(&map(drawing)).unwrap(), "HELLO");
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        let drawing = "
+#..#.#..#
+#..#.#..#
+####.#..#
+#..#.#..#
+#..#..##.
+#..#.#..#
+";
+        assert!(matches!(
+            recognize(&map(drawing)),
+            Err(Error::UnknownGlyph(1, _))
+        ));
+    }
+
+    #[test]
+    fn wrong_height() {
+        assert!(matches!(recognize(&map("####")), Err(Error::Height(1))));
+    }
+}
@@ -0,0 +1,120 @@
+//! Progress reports from long-running solvers.
+//!
+//! Reporting is off until [`enable`] is called, so solvers can report from their inner loops
+//! unconditionally. When enabled, a [`Progress`] prints a line to stderr at most once per
+//! [`INTERVAL`], and once more when it is dropped.
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The least time between two reports from the same [`Progress`].
+pub const INTERVAL: Duration = Duration::from_secs(1);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn on progress reports for the rest of the program.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Progress through a known amount of work.
+///
+/// Updates take `&self`, so one `Progress` can be shared between threads.
+#[derive(Debug)]
+pub struct Progress {
+    label: &'static str,
+    total: u64,
+    done: AtomicU64,
+    started: Instant,
+    /// Milliseconds after `started` at which the next report is due.
+    next_report: AtomicU64,
+}
+
+impl Progress {
+    pub fn new(label: &'static str, total: u64) -> Self {
+        Progress {
+            label,
+            total,
+            done: AtomicU64::new(0),
+            started: Instant::now(),
+            next_report: AtomicU64::new(INTERVAL.as_millis() as u64),
+        }
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// Record `amount` more work done, reporting if a report is due.
+    pub fn inc(&self, amount: u64) {
+        self.done.fetch_add(amount, Ordering::Relaxed);
+        if !is_enabled() {
+            return;
+        }
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        let due = self.next_report.load(Ordering::Relaxed);
+        // only the thread which claims this report prints it
+        if elapsed >= due
+            && self
+                .next_report
+                .compare_exchange(
+                    due,
+                    elapsed + INTERVAL.as_millis() as u64,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            self.report();
+        }
+    }
+
+    fn line(&self) -> String {
+        let done = self.done();
+        let percent = if self.total == 0 {
+            100.0
+        } else {
+            done as f64 * 100.0 / self.total as f64
+        };
+        format!(
+            "{}: {}/{} ({:.1}%) in {:.1?}",
+            self.label,
+            done,
+            self.total,
+            percent,
+            self.started.elapsed()
+        )
+    }
+
+    fn report(&self) {
+        eprintln!("{}", self.line());
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if is_enabled() {
+            self.report();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_while_disabled() {
+        let progress = Progress::new("test", 10);
+        progress.inc(3);
+        progress.inc(4);
+        assert_eq!(progress.done(), 7);
+        assert!(progress.line().starts_with("test: 7/10 (70.0%) in "));
+    }
+}
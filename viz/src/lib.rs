@@ -0,0 +1,173 @@
+//! Frame-based terminal animation, for watching a day's state evolve.
+//!
+//! Each frame clears the terminal, draws itself from the top-left corner, and then waits a moment
+//! before the next frame replaces it.
+
+use aoclib::geometry::{Map, Point};
+use std::{
+    fmt::{self, Display, Write as _},
+    io::{self, Stdout, Write},
+    thread,
+    time::Duration,
+};
+
+/// Clear the screen and move the cursor to its top-left corner.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightWhite,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[37m",
+            Color::BrightWhite => "\x1b[1;97m",
+        }
+    }
+}
+
+/// An item which displays in a color, if its animation uses color.
+#[derive(Debug, Clone, Copy)]
+pub struct Painted<T> {
+    item: T,
+    color: Option<Color>,
+}
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.color {
+            Some(color) => write!(f, "{}{}{}", color.code(), self.item, RESET),
+            None => self.item.fmt(f),
+        }
+    }
+}
+
+/// Draws successive frames to a terminal.
+#[derive(Debug)]
+pub struct Animation<W = Stdout> {
+    out: W,
+    delay: Duration,
+    color: bool,
+}
+
+impl Animation {
+    /// Animate on stdout, in color, at ten frames per second.
+    pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> Animation<W> {
+    pub fn with_writer(out: W) -> Self {
+        Animation {
+            out,
+            delay: Duration::from_millis(100),
+            color: true,
+        }
+    }
+
+    /// How long each frame stays on screen.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Whether frames may use color.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Display `item` in `color`, if this animation uses color.
+    pub fn paint<T: Display>(&self, color: Color, item: T) -> Painted<T> {
+        Painted {
+            item,
+            color: self.color.then_some(color),
+        }
+    }
+
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.out.write_all(CLEAR.as_bytes())?;
+        self.out.flush()
+    }
+
+    /// Replace the previous frame with `frame`, then wait for this animation's delay.
+    pub fn draw(&mut self, frame: impl Display) -> io::Result<()> {
+        write!(self.out, "{}{}", CLEAR, frame)?;
+        self.out.flush()?;
+        if !self.delay.is_zero() {
+            thread::sleep(self.delay);
+        }
+        Ok(())
+    }
+}
+
+/// Render `map` as a frame, top row first, drawing each tile with `tile`.
+pub fn render<T, D: Display>(map: &Map<T>, mut tile: impl FnMut(Point, &T) -> D) -> String {
+    let mut frame = String::new();
+    for y in (map.low_y()..=map.high_y()).rev() {
+        for x in map.low_x()..=map.high_x() {
+            let point = Point::new(x, y);
+            write!(frame, "{}", tile(point, &map[point])).expect("writing to a string succeeds");
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_without_color() {
+        let animation = Animation::with_writer(Vec::new()).color(false);
+        assert_eq!(animation.paint(Color::Red, 7).to_string(), "7");
+        let animation = animation.color(true);
+        assert_eq!(
+            animation.paint(Color::Red, 7).to_string(),
+            "\x1b[31m7\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn draw_clears_first() {
+        let mut animation = Animation::with_writer(Vec::new()).delay(Duration::ZERO);
+        animation.draw("one\n").unwrap();
+        animation.draw("two\n").unwrap();
+        assert_eq!(
+            String::from_utf8(animation.out).unwrap(),
+            format!("{0}one\n{0}two\n", CLEAR)
+        );
+    }
+
+    #[test]
+    fn render_top_row_first() {
+        let mut map = Map::<u8>::new(2, 2);
+        map[Point::new(0, 1)] = 1;
+        map[Point::new(1, 0)] = 2;
+        assert_eq!(render(&map, |_, tile| *tile), "10\n02\n");
+    }
+}
@@ -0,0 +1,142 @@
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// How many days the workspace manifest lists on each line of its members.
+const MEMBERS_PER_LINE: usize = 10;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml");
+const LIB_TEMPLATE: &str = include_str!("../templates/lib.rs");
+const MAIN_TEMPLATE: &str = include_str!("../templates/main.rs");
+
+#[derive(StructOpt, Debug)]
+enum Task {
+    /// generate a new day's crate and add it to the workspace
+    NewDay {
+        /// the day to generate
+        day: u8,
+    },
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is within the workspace")
+        .to_owned()
+}
+
+/// Substitute the day into a template.
+fn fill(template: &str, day: u8) -> String {
+    template
+        .replace("{{day}}", &format!("day{:02}", day))
+        .replace("{{day_number}}", &day.to_string())
+}
+
+/// Add `member` to the end of the workspace's members.
+fn add_member(manifest: &str, member: &str) -> Result<String> {
+    let start = manifest
+        .find("members = [")
+        .ok_or_else(|| eyre!("workspace manifest has no members"))?;
+    let end = start
+        + manifest[start..]
+            .find("\n]")
+            .ok_or_else(|| eyre!("workspace members are not terminated"))?;
+    let last_line = &manifest[manifest[..end].rfind('\n').map_or(0, |idx| idx + 1)..end];
+
+    let entry = format!("\"{}\",", member);
+    let insertion = if last_line.trim_start().starts_with("\"day")
+        && last_line.split_whitespace().count() < MEMBERS_PER_LINE
+    {
+        format!(" {}", entry)
+    } else {
+        format!("\n    {}", entry)
+    };
+
+    let mut manifest = manifest.to_owned();
+    manifest.insert_str(end, &insertion);
+    Ok(manifest)
+}
+
+fn new_day(day: u8) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        bail!("day must be in 1..=25; got {}", day);
+    }
+    let root = workspace_root();
+    let name = format!("day{:02}", day);
+    let crate_dir = root.join(&name);
+    if crate_dir.exists() {
+        bail!("{} already exists", crate_dir.display());
+    }
+
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .wrap_err_with(|| format!("reading {}", manifest_path.display()))?;
+    let manifest = add_member(&manifest, &name)?;
+
+    std::fs::create_dir_all(crate_dir.join("src"))?;
+    for (path, template) in [
+        ("Cargo.toml", CARGO_TEMPLATE),
+        ("src/lib.rs", LIB_TEMPLATE),
+        ("src/main.rs", MAIN_TEMPLATE),
+    ] {
+        let path = crate_dir.join(path);
+        std::fs::write(&path, fill(template, day))
+            .wrap_err_with(|| format!("writing {}", path.display()))?;
+    }
+    std::fs::write(&manifest_path, manifest)
+        .wrap_err_with(|| format!("writing {}", manifest_path.display()))?;
+
+    println!("created {}", crate_dir.display());
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    match Task::from_args() {
+        Task::NewDay { day } => new_day(day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_template() {
+        let main = fill(MAIN_TEMPLATE, 7);
+        assert!(main.contains("use day07::{solve_part1, solve_part2};"));
+        assert!(main.contains("const DAY: u8 = 7;"));
+        assert!(!main.contains("{{"));
+    }
+
+    #[test]
+    fn add_member_to_partial_line() {
+        let manifest = "[workspace]\nmembers = [\n    \"xtask\",\n    \"day01\", \"day02\",\n]\n";
+        assert_eq!(
+            add_member(manifest, "day03").unwrap(),
+            "[workspace]\nmembers = [\n    \"xtask\",\n    \"day01\", \"day02\", \"day03\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn add_member_after_full_line() {
+        let days: Vec<_> = (1..=10).map(|day| format!("\"day{:02}\",", day)).collect();
+        let manifest = format!("[workspace]\nmembers = [\n    {}\n]\n", days.join(" "));
+        assert_eq!(
+            add_member(&manifest, "day11").unwrap(),
+            format!(
+                "[workspace]\nmembers = [\n    {}\n    \"day11\",\n]\n",
+                days.join(" ")
+            )
+        );
+    }
+
+    #[test]
+    fn add_member_after_other_crates() {
+        let manifest = "[workspace]\nmembers = [\n    \"xtask\",\n]\n";
+        assert_eq!(
+            add_member(manifest, "day01").unwrap(),
+            "[workspace]\nmembers = [\n    \"xtask\",\n    \"day01\",\n]\n"
+        );
+    }
+}
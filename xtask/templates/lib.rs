@@ -0,0 +1,46 @@
+use std::path::Path;
+
+pub fn solve_part1(input: &str) -> Result<u64, Error> {
+    unimplemented!("{} bytes of input", input.len())
+}
+
+pub fn solve_part2(input: &str) -> Result<u64, Error> {
+    unimplemented!("{} bytes of input", input.len())
+}
+
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    solve_part1(&std::fs::read_to_string(input)?)
+}
+
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    solve_part2(&std::fs::read_to_string(input)?)
+}
+
+aoc_errors::day_error! {
+    pub enum Error {}
+}
+
+aoc_wasm::wasm_bindings!(solve_part1, solve_part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = "
+";
+
+    #[rstest]
+    #[ignore = "example not yet filled in"]
+    #[case(EXAMPLE, 0)]
+    fn example_part1(#[case] input: &str, #[case] expect: u64) {
+        assert_eq!(solve_part1(input.trim()).unwrap(), expect);
+    }
+
+    #[rstest]
+    #[ignore = "example not yet filled in"]
+    #[case(EXAMPLE, 0)]
+    fn example_part2(#[case] input: &str, #[case] expect: u64) {
+        assert_eq!(solve_part2(input.trim()).unwrap(), expect);
+    }
+}